@@ -1,31 +1,75 @@
 use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
 use std::hash::{Hash, Hasher};
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 use std::{fmt::Display, vec};
 use tui_input::backend::crossterm::EventHandler;
 use tui_input::Input;
 
-use chrono::{DateTime, Datelike, Days, Local, NaiveDate, TimeDelta, Weekday};
+use arboard::Clipboard;
+
+use chrono::{Datelike, Days, Months, NaiveDate, TimeDelta, Weekday};
 use color_eyre::Result;
 use firestore::FirestoreDb;
-use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, Paragraph},
+    widgets::Paragraph,
     DefaultTerminal, Frame,
 };
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+mod actions;
+mod commands;
+mod screens;
+
+use actions::{write_checkpoints, Outcome, PendingWrite, Selection, Write};
+use commands::{help_entries, key_hint, Command};
+use screens::Screen;
 
 use crate::{
-    firestore::{delete_checkpoint, find_checkpoints, insert_checkpoint, update_checkpoint},
+    calendar::{self, meeting_checkpoints, CalendarConfig, Meeting},
+    config::{
+        save_layout, with_stored_password, Config, GitConfig, GoalsConfig, LayoutConfig,
+        RemindConfig,
+    },
+    firestore::{
+        find_all_checkpoints, find_checkpoints, find_checkpoints_between, find_note,
+        find_project_messages, save_note, search_checkpoints, set_day_locked, set_day_type,
+    },
+    git::{self, Commit},
+    holidays::Holidays,
+    idle::idle_time,
+    note_editor::NoteEditor,
+    notification::{self, Reminders},
     pbs::{fetch_tasks, AuthConfig, PbsTask},
-    time::{calculate_duration_minutes, human_duration, round_to_nearest_fifteen_minutes, Week},
-    timeline_widget::Timeline,
-    widgets::HelpLine,
+    projects::{self, Project},
+    reload::{ConfigFile, ConfigWatcher},
+    slack::{self, SlackConfig},
+    stats::{flex_balance, PeriodStats},
+    summary::{day_summary, SummaryFormat},
+    theme::Theme,
+    time::{
+        activity_units, billable_units, copy_to_date, count_overlaps, dominant_project,
+        fix_overlaps, is_near_duplicate, merge_near_duplicates, parse_date, parse_date_time,
+        parse_hh_mm, running_span, system_timezone, unregistered_spans, DayStats, LunchBreak,
+        OverlapFix, Rounding, Time, TimeSettings, Week,
+    },
+    timeline_widget::{BrailleTimeline, DayTypeBar, HourRuler, Timeline, Zoom},
+    webhook::WebhookConfig,
+    widgets::{
+        Clock, ConfirmPopup, Goals, HelpLine, Hyperlink, Minimap, StatusLine, Toast, HEATMAP_WEEKS,
+    },
 };
 
-use ratatui::widgets::{Clear, List, ListItem, ListState};
+use ratatui::widgets::{ListState, Row, Table};
 
 #[derive(Default)]
 pub struct TimeSpan {
@@ -38,69 +82,450 @@ impl Display for TimeSpan {
     }
 }
 
-#[derive(Default, Clone, Serialize, Deserialize)]
+/// Resolves once the controlling terminal is closed or the system asks the process to terminate,
+/// or with the error when the signals can't be listened to
+fn shutdown_signal() -> tokio::sync::oneshot::Receiver<std::io::Result<()>> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let _ = tx.send(wait_for_shutdown().await);
+    });
+    rx
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown() -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut hangup = signal(SignalKind::hangup())?;
+    let mut terminate = signal(SignalKind::terminate())?;
+    tokio::select! {
+        _ = hangup.recv() => {}
+        _ = terminate.recv() => {}
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+async fn wait_for_shutdown() -> std::io::Result<()> {
+    use tokio::signal::windows::{ctrl_close, ctrl_shutdown};
+
+    let mut close = ctrl_close()?;
+    let mut shutdown = ctrl_shutdown()?;
+    tokio::select! {
+        _ = close.recv() => {}
+        _ = shutdown.recv() => {}
+    }
+    Ok(())
+}
+
+/// What the time following a checkpoint was spent on
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckpointKind {
+    #[default]
+    Work,
+    Break,
+    Absence,
+    /// The clock was stopped, nothing is tracked until the next checkpoint
+    Stop,
+}
+
+/// Whole day without work, it has no hours left to track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayType {
+    Vacation,
+    Sick,
+    Holiday,
+}
+
+impl DayType {
+    pub fn label(self) -> &'static str {
+        match self {
+            DayType::Vacation => "Vacation",
+            DayType::Sick => "Sick day",
+            DayType::Holiday => "Public holiday",
+        }
+    }
+
+    pub fn color(self, theme: &Theme) -> Color {
+        match self {
+            DayType::Vacation => theme.good,
+            DayType::Sick => theme.bad,
+            DayType::Holiday => theme.accent,
+        }
+    }
+}
+
+impl CheckpointKind {
+    fn next(self) -> Self {
+        match self {
+            CheckpointKind::Work => CheckpointKind::Break,
+            CheckpointKind::Break => CheckpointKind::Absence,
+            CheckpointKind::Absence | CheckpointKind::Stop => CheckpointKind::Work,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Checkpoint {
     #[serde(alias = "_firestore_id")]
     pub id: Option<String>,
-    pub time: DateTime<Local>,
+    #[serde(with = "crate::firestore::utc_time")]
+    pub time: Time,
     pub project: Option<String>,
+    /// Kind of work within the project, see [`Project::activities`]
+    #[serde(default)]
+    pub activity: Option<String>,
     pub message: Option<String>,
     pub registered: bool,
+    #[serde(default)]
+    pub kind: CheckpointKind,
+    /// `#tags` found in the message
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Whether the span counts toward client invoices, internal meetings usually don't
+    #[serde(default = "default_billable")]
+    pub billable: bool,
+    /// Toggl Track time entry of the span, once it is synced
+    #[serde(default)]
+    pub toggl_id: Option<i64>,
+}
+
+fn default_billable() -> bool {
+    true
+}
+
+impl Default for Checkpoint {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Checkpoint {
     pub fn new() -> Self {
         Self {
             id: None,
-            time: Local::now(),
+            time: chrono::Utc::now().with_timezone(&system_timezone()),
             project: None,
+            activity: None,
             message: None,
             registered: false,
+            kind: CheckpointKind::Work,
+            tags: vec![],
+            billable: true,
+            toggl_id: None,
         }
     }
 
-    pub fn rounded_time(&self) -> DateTime<Local> {
-        round_to_nearest_fifteen_minutes(self.time)
+    /// Sets the message and the tags found in it
+    pub fn set_message(&mut self, message: String) {
+        self.tags = parse_tags(&message);
+        self.message = Some(message);
+    }
+
+    pub fn has_message(&self) -> bool {
+        self.message
+            .as_deref()
+            .is_some_and(|message| !message.is_empty())
+    }
+
+    /// The project followed by the activity, e.g. `ABC-1/review`
+    pub fn project_label(&self) -> Option<String> {
+        let project = self.project.as_deref()?;
+        Some(match &self.activity {
+            Some(activity) => format!("{}/{}", project, activity),
+            None => project.to_string(),
+        })
+    }
+
+    /// Breaks and absences are not counted as work
+    pub fn is_work(&self) -> bool {
+        self.kind == CheckpointKind::Work
     }
 
-    pub fn color(&self) -> Color {
+    pub fn rounded_time(&self, rounding: &Rounding) -> Time {
+        rounding.round(self.time)
+    }
+
+    /// Case-insensitive substring match against the project and message
+    pub fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        [&self.project, &self.message]
+            .into_iter()
+            .flatten()
+            .any(|value| value.to_lowercase().contains(&query))
+    }
+
+    pub fn color(&self, theme: &Theme) -> Color {
         if self.message.is_none() {
-            return Color::DarkGray;
+            return theme.missing_message;
         }
 
         if let Some(project_id) = &self.project {
-            let mut hasher = DefaultHasher::new();
-            project_id.hash(&mut hasher);
-            let hash = hasher.finish();
-            Color::Indexed((hash % 216) as u8 + 16)
+            project_color(project_id)
         } else {
             Color::White
         }
     }
 }
 
+/// Color of the project derived from its id, so that it is stable across runs
+pub fn project_color(project_id: &str) -> Color {
+    let mut hasher = DefaultHasher::new();
+    project_id.hash(&mut hasher);
+    let hash = hasher.finish();
+    Color::Indexed((hash % 216) as u8 + 16)
+}
+
+/// Lowercased `#tags` in the order of their first occurrence in the message
+pub fn parse_tags(message: &str) -> Vec<String> {
+    let mut tags: Vec<String> = vec![];
+    for word in message.split_whitespace() {
+        let Some(tag) = word.strip_prefix('#') else {
+            continue;
+        };
+        let tag: String = tag
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+            .collect::<String>()
+            .to_lowercase();
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Action waiting for the user's confirmation or choice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfirmAction {
+    DeleteCheckpoint,
+    DeleteSelection,
+    MergeSpans,
+    CopyPreviousDay,
+    CopyDaySummary,
+    UnlockDay,
+    RegisterDay,
+    SetDayType,
+    FixOverlaps,
+    StopAtIdle,
+    MergeDuplicate,
+}
+
+/// How often the UI is redrawn without any input
+const TICK_RATE: Duration = Duration::from_secs(1);
+/// How often the spinner moves while background calls are in flight
+const SPINNER_RATE: Duration = Duration::from_millis(100);
+/// How long a toast stays on the screen
+const TOAST_DURATION: Duration = Duration::from_secs(5);
+/// How often the desktop is asked for the idle time
+const IDLE_CHECK_RATE: Duration = Duration::from_secs(30);
+
+/// How often the running project is compared to the Slack status
+const SLACK_CHECK_RATE: Duration = Duration::from_secs(15);
+
+/// How often today's checkpoints are checked for reminders
+const REMIND_CHECK_RATE: Duration = Duration::from_secs(60);
+
+const YES_NO: &[(&str, &str)] = &[("y", "confirm"), ("n", "cancel")];
+
+impl ConfirmAction {
+    fn message(&self) -> &'static str {
+        match self {
+            ConfirmAction::DeleteCheckpoint => "Delete the selected checkpoint?",
+            ConfirmAction::DeleteSelection => "Delete all checkpoints in the selection?",
+            ConfirmAction::CopyPreviousDay => {
+                "The selected day is not empty. Copy the previous day's checkpoints anyway?"
+            }
+            ConfirmAction::MergeSpans => {
+                "Merge the selected span with the next one. Whose project and comment should stay?"
+            }
+            ConfirmAction::CopyDaySummary => "Copy the summary of the selected day as",
+            ConfirmAction::UnlockDay => "The selected day is locked. Unlock it for editing?",
+            ConfirmAction::RegisterDay => "Mark all checkpoints of the selected day as registered?",
+            ConfirmAction::SetDayType => "Mark the selected day as",
+            ConfirmAction::FixOverlaps => {
+                "Put the overlapping checkpoints of the selected day in order by"
+            }
+            ConfirmAction::StopAtIdle => {
+                "The clock kept running while you were away. End the span when you left?"
+            }
+            ConfirmAction::MergeDuplicate => {
+                "The new checkpoint lands in the same unit as the previous one. Merge them?"
+            }
+        }
+    }
+
+    fn choices(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            ConfirmAction::DeleteCheckpoint
+            | ConfirmAction::DeleteSelection
+            | ConfirmAction::CopyPreviousDay
+            | ConfirmAction::UnlockDay
+            | ConfirmAction::RegisterDay
+            | ConfirmAction::StopAtIdle
+            | ConfirmAction::MergeDuplicate => YES_NO,
+            ConfirmAction::MergeSpans => &[("1", "selected"), ("2", "next"), ("n", "cancel")],
+            ConfirmAction::CopyDaySummary => &[("t", "text"), ("m", "Markdown"), ("n", "cancel")],
+            ConfirmAction::SetDayType => &[
+                ("v", "vacation"),
+                ("s", "sick"),
+                ("h", "holiday"),
+                ("w", "working day"),
+                ("n", "cancel"),
+            ],
+            ConfirmAction::FixOverlaps => &[
+                ("s", "swapping their times"),
+                ("c", "clamping to the previous checkpoint"),
+                ("n", "cancel"),
+            ],
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum InputMode {
     #[default]
     Normal,
     Editing,
+    InsertTime,
+    EditTime,
+    SplitTime,
+    MoveDate,
+    JumpDate,
+    Search,
+    TagFilter,
 }
 
 pub struct App {
+    /// Stack of opened screens, the week is at the bottom
+    screens: Vec<Screen>,
+    /// Number of background calls whose outcome has not been applied yet
+    pending: usize,
+    /// Frame of the spinner shown while `pending` is not zero
+    spinner: usize,
+    outcomes_tx: UnboundedSender<Outcome>,
+    outcomes_rx: UnboundedReceiver<Outcome>,
+    /// Edits already shown in the week, waiting to be written to the store
+    writes_tx: UnboundedSender<PendingWrite>,
+    writer: JoinHandle<()>,
+    /// Number of edits sent to `writes_tx` that did not reach the store yet
+    queued_writes: usize,
+    /// Week loaded while edits were still queued, loaded again once they reached the store
+    deferred_load: Option<(NaiveDate, Selection)>,
+    /// Message shown over the week and the time it was shown at
+    toast: Option<(String, Instant)>,
+    /// Opened on the first copy
+    clipboard: Option<Clipboard>,
+    /// Span copied to be put at the current time
+    yanked: Option<Checkpoint>,
+    /// Dominant project of each day of the loaded weeks
+    minimap: BTreeMap<NaiveDate, String>,
+    /// Days whose checkpoints must not be changed until they are unlocked
+    locked_days: BTreeSet<NaiveDate>,
+    /// Ids of the [overnight splits](overnight_splits) shown in the week, stored with the next
+    /// edit
+    unsaved_splits: BTreeSet<String>,
+    /// Vacation, sick days and holidays
+    day_types: BTreeMap<NaiveDate, DayType>,
+    /// Public holidays from the config
+    holidays: Holidays,
+    /// Projects from `projects.toml` in their order
+    projects: Vec<Project>,
+    /// Assigned projects, the most recently used first
+    recent_projects: Vec<String>,
+    /// Project picked in the project popup while one of its activities is being chosen
+    activity_project: Option<Project>,
+    activity_popup_state: ListState,
+    project_popup_state: ListState,
+    /// Query typed into the command palette
+    palette_input: Input,
+    palette_state: ListState,
+    /// Notes of `note_date` opened in the notes popup
+    note_editor: NoteEditor,
+    note_date: NaiveDate,
     /// Is the application running?
     running: bool,
     input: Input,
     input_mode: InputMode,
+    input_invalid: bool,
     db: FirestoreDb,
     mondays: Vec<NaiveDate>,
     selected_mon_idx: usize,
     week: Week,
     auth_config: AuthConfig,
     tasks: Vec<PbsTask>,
-    show_task_popup: bool,
     show_task_url: bool,
     task_popup_state: ListState,
     task_url_prefix: Option<String>,
+    /// Currency of the project rates, shown with the earnings on the stats screen
+    currency: Option<String>,
+    pending_confirm: Option<ConfirmAction>,
+    /// Index of the checkpoint where the visual selection started
+    visual_anchor: Option<usize>,
+    theme: Theme,
+    /// Index into [`Zoom::LEVELS`]
+    zoom_level: usize,
+    search_results: Vec<Checkpoint>,
+    search_popup_state: ListState,
+    /// Only spans of checkpoints with this tag are highlighted in the timeline
+    tag_filter: Option<String>,
+    /// Only spans of this project are highlighted in the timeline
+    project_filter: Option<String>,
+    /// Date the week view opens on instead of the current week
+    start_date: Option<NaiveDate>,
+    message_templates: Vec<String>,
+    /// Messages previously used with the selected checkpoint's project, offered while editing
+    message_history: Vec<String>,
+    /// Text typed before cycling through completions and the index of the shown completion
+    completion: Option<(String, usize)>,
+    template_popup_state: ListState,
+    git: GitConfig,
+    /// Commits made during the selected span, offered as its comment
+    commits: Vec<Commit>,
+    commit_popup_state: ListState,
+    calendar: Option<CalendarConfig>,
+    /// Meetings of the selected day in the calendar, offered to be added as spans
+    meetings: Vec<Meeting>,
+    meeting_popup_state: ListState,
+    /// Unregistered spans across all dates with their duration in minutes
+    review_items: Vec<(Checkpoint, u32)>,
+    review_state: ListState,
+    week_stats: PeriodStats,
+    month_stats: PeriodStats,
+    /// Stats of each day shown in the heatmap of the stats screen
+    daily_stats: BTreeMap<NaiveDate, DayStats>,
+    /// Stats of each day the flex balance is counted over
+    flex_days: BTreeMap<NaiveDate, DayStats>,
+    layout: LayoutConfig,
+    goals: GoalsConfig,
+    lunch_break: Option<LunchBreak>,
+    /// Idle minutes after which ending the running span is suggested
+    idle_minutes: Option<u32>,
+    idle_checked: Instant,
+    /// When the idleness began that the user is asked about
+    idle_since: Option<Time>,
+    slack: Option<SlackConfig>,
+    /// Status last sent to Slack
+    slack_status: Option<slack::Status>,
+    slack_checked: Instant,
+    webhooks: Vec<WebhookConfig>,
+    remind: RemindConfig,
+    /// Reminders shown as desktop notifications today
+    reminders: Reminders,
+    reminders_checked: Instant,
+    /// Where the layout is persisted when it changes
+    config_path: PathBuf,
+    /// Modification time of the config file after the layout was last saved to it, that write
+    /// is not reloaded
+    layout_saved: Option<SystemTime>,
+    /// Changes of `config.toml` and `projects.toml`, none when they can't be watched
+    config_watcher: Option<ConfigWatcher>,
+    /// How the times are shown and measured
+    settings: TimeSettings,
+    /// Profile whose files are watched and whose status is cached, `None` is the default one
+    profile: Option<String>,
 }
 
 impl App {
@@ -108,52 +533,219 @@ impl App {
     pub fn new(
         db: FirestoreDb,
         mondays: Vec<NaiveDate>,
-        auth_config: AuthConfig,
-        task_url_prefix: Option<String>,
+        config: Config,
+        config_path: PathBuf,
+        profile: Option<String>,
+        projects: Vec<Project>,
+        holidays: Holidays,
     ) -> Self {
-        let today = Local::now().date_naive();
-        let current_monday = today - TimeDelta::days(today.weekday().num_days_from_monday() as i64);
+        let settings = config.time_settings();
+        let today = settings.now().date_naive();
+        let current_monday = settings.locale.week_start(today);
         let selected_mon_idx = mondays
             .iter()
             .position(|&m| m == current_monday)
             .unwrap_or(0);
 
+        let (outcomes_tx, outcomes_rx) = unbounded_channel();
+        let (writes_tx, writes_rx) = unbounded_channel();
+        let writer = tokio::spawn(write_checkpoints(
+            db.clone(),
+            writes_rx,
+            outcomes_tx.clone(),
+        ));
+
         Self {
+            screens: vec![Screen::Week],
+            pending: 0,
+            spinner: 0,
+            outcomes_tx,
+            outcomes_rx,
+            writes_tx,
+            writer,
+            queued_writes: 0,
+            deferred_load: None,
+            toast: None,
+            clipboard: None,
+            yanked: None,
+            minimap: BTreeMap::new(),
+            locked_days: BTreeSet::new(),
+            unsaved_splits: BTreeSet::new(),
+            day_types: BTreeMap::new(),
+            holidays,
+            projects,
+            recent_projects: vec![],
+            activity_project: None,
+            activity_popup_state: ListState::default(),
+            project_popup_state: ListState::default(),
+            palette_input: Input::default(),
+            palette_state: ListState::default(),
+            note_editor: NoteEditor::default(),
+            note_date: today,
             running: true,
             input: Input::default(),
             input_mode: InputMode::default(),
+            input_invalid: false,
             db,
             mondays,
             selected_mon_idx,
-            week: Week::new(),
-            auth_config,
+            week: Week {
+                show_weekend: config.layout.show_weekend,
+                first_day_of_week: settings.locale.first_day_of_week,
+                ..Week::new()
+            },
+            auth_config: config.auth,
             tasks: vec![],
-            show_task_popup: false,
             show_task_url: false,
             task_popup_state: ListState::default(),
-            task_url_prefix,
+            task_url_prefix: config.task_url_prefix,
+            currency: config.currency,
+            pending_confirm: None,
+            visual_anchor: None,
+            theme: config.theme.theme(),
+            zoom_level: Zoom::DEFAULT_LEVEL,
+            search_results: vec![],
+            search_popup_state: ListState::default(),
+            tag_filter: None,
+            project_filter: None,
+            start_date: None,
+            message_templates: config.messages.templates,
+            message_history: vec![],
+            completion: None,
+            template_popup_state: ListState::default(),
+            git: config.git,
+            commits: vec![],
+            commit_popup_state: ListState::default(),
+            calendar: config.calendar,
+            meetings: vec![],
+            meeting_popup_state: ListState::default(),
+            review_items: vec![],
+            review_state: ListState::default(),
+            week_stats: PeriodStats::default(),
+            month_stats: PeriodStats::default(),
+            daily_stats: BTreeMap::new(),
+            flex_days: BTreeMap::new(),
+            layout: config.layout,
+            goals: config.goals,
+            lunch_break: config.lunch_break,
+            idle_minutes: config.idle_minutes,
+            idle_checked: Instant::now(),
+            idle_since: None,
+            slack: config.slack,
+            slack_status: None,
+            slack_checked: Instant::now(),
+            webhooks: config.webhooks,
+            remind: config.remind,
+            reminders: Reminders::default(),
+            reminders_checked: Instant::now(),
+            config_path,
+            layout_saved: None,
+            config_watcher: None,
+            settings,
+            profile,
         }
     }
 
+    /// Opens the week view on the date instead of the current week
+    pub fn with_start_date(mut self, date: Option<NaiveDate>) -> Self {
+        self.start_date = date;
+        self
+    }
+
+    /// Starts with the spans of other projects dimmed
+    pub fn with_project_filter(mut self, project: Option<String>) -> Self {
+        self.project_filter = project;
+        self
+    }
+
     /// Run the application's main loop.
     pub async fn run(mut self, mut terminal: DefaultTerminal) -> Result<()> {
         self.running = true;
 
-        self.load_week().await;
+        match self.start_date.take() {
+            Some(date) => self.go_to_date(date, Selection::Day(date.weekday())),
+            None => self.load_week(Selection::Start),
+        }
+        self.load_minimap();
+        self.load_locked_days();
+        self.load_day_types();
+        self.load_flex_days();
+        self.watch_config();
+
+        let mut shutdown = shutdown_signal();
 
         while self.running {
+            match shutdown.try_recv() {
+                Ok(Ok(())) => {
+                    // The terminal is gone or the system is going down, don't leave the clock running
+                    self.stop_clock_on_shutdown().await;
+                    break;
+                }
+                Ok(Err(err)) => {
+                    self.show_toast(format!("The clock won't stop on shutdown: {}", err))
+                }
+                Err(_) => {}
+            }
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_crossterm_events().await?;
+            self.apply_outcomes();
+            self.reload_changed_files();
+            self.check_idle();
+            self.update_slack_status();
+            self.check_reminders();
         }
+
+        self.clear_slack_status().await;
+        // Let the queued edits reach the store before quitting
+        drop(self.writes_tx);
+        let _ = self.writer.await;
         Ok(())
     }
 
+    /// Shows the message over the week for [`TOAST_DURATION`]
+    fn show_toast(&mut self, message: String) {
+        self.toast = Some((message, Instant::now()));
+    }
+
     /// Renders the user interface.
     ///
     /// This is where you add new widgets. See the following resources for more information:
     /// - <https://docs.rs/ratatui/latest/ratatui/widgets/index.html>
     /// - <https://github.com/ratatui/ratatui/tree/master/examples>
     fn draw(&mut self, frame: &mut Frame) {
+        // Draw the topmost full screen and everything opened on top of it
+        let base = self
+            .screens
+            .iter()
+            .rposition(|screen| screen.is_full())
+            .unwrap_or(0);
+        for idx in base..self.screens.len() {
+            self.draw_screen(self.screens[idx], frame);
+        }
+
+        if let Some(action) = self.pending_confirm {
+            let popup = ConfirmPopup {
+                title: "Confirm",
+                message: action.message(),
+                choices: action.choices(),
+                theme: self.theme,
+            };
+            frame.render_widget(popup, centered_rect(40, 20, frame.area()));
+        }
+
+        if let Some((message, shown)) = &self.toast {
+            if shown.elapsed() < TOAST_DURATION {
+                let toast = Toast {
+                    message,
+                    theme: self.theme,
+                };
+                frame.render_widget(toast, toast_rect(message, frame.area()));
+            }
+        }
+    }
+
+    /// Renders the timeline of the selected week with the details of the selected checkpoint
+    fn draw_week(&mut self, frame: &mut Frame) {
         let unregistered_count = self.week.unregistered_checkpoints.len();
         let unregistered_height = if unregistered_count > 0 {
             // Add 2 for the bottom and top border of the block
@@ -162,17 +754,33 @@ impl App {
             0
         };
 
+        // The message is edited where it's displayed, unless the detail pane is hidden
+        let editing_inline = self.input_mode == InputMode::Editing
+            && self.layout.show_detail
+            && self.week.selected_checkpoint().is_some();
+
+        // the input is always shown while typing into it
+        let typing = self.input_mode != InputMode::Normal && !editing_inline;
+        let input_height = if self.layout.show_input || typing {
+            3
+        } else {
+            0
+        };
+
+        let days = self.week.visible_days().len() as u16;
         let mut constraints = Vec::new();
         if unregistered_height > 0 {
             constraints.push(Constraint::Length(unregistered_height));
         }
         constraints.extend(vec![
-            Constraint::Length(1),         // days
-            Constraint::Length(1),         // spacer
-            Constraint::Length(3 * 5 + 4), // timeline
-            Constraint::Length(1),         // spacer
+            Constraint::Length(1),            // days
+            Constraint::Length(1),            // minimap
+            Constraint::Length(1),            // hour ruler
+            Constraint::Length(4 * days - 1), // timeline
+            Constraint::Length(1),            // goals
             Constraint::Fill(1),
-            Constraint::Length(3), // input
+            Constraint::Length(input_height),
+            Constraint::Length(1), // status
             Constraint::Length(1), // controls
         ]);
 
@@ -188,219 +796,385 @@ impl App {
                 .iter()
                 .map(|(ch, minutes)| {
                     Line::from(vec![
-                        Span::from(ch.time.format("%d.%m %H:%M ").to_string()),
+                        Span::from(format!(
+                            "{} {} ",
+                            self.settings.locale.day(ch.time.date_naive()),
+                            self.settings.locale.time(ch.time.time())
+                        )),
                         Span::from(ch.project.as_deref().unwrap_or("-").to_string()).bold(),
                         Span::from(" "),
-                        Span::from(format!("({}) ", human_duration(*minutes))).fg(Color::Yellow),
+                        Span::from(format!("({}) ", self.settings.duration(*minutes)))
+                            .fg(self.theme.accent),
                         Span::from(ch.message.as_deref().unwrap_or("")),
                     ])
                 })
                 .collect();
             let paragraph =
-                Paragraph::new(lines).block(Block::bordered().title("Unregistered Checkpoints"));
+                Paragraph::new(lines).block(self.theme.block().title("Unregistered Checkpoints"));
             frame.render_widget(paragraph, unregistered_area);
             area_index += 1;
         }
 
         let weeks_area = areas[area_index];
-        let timeline_area = areas[area_index + 2];
-        let fill_area = areas[area_index + 4];
-        let input_area = areas[area_index + 5];
-        let controls_area = areas[area_index + 6];
-
-        frame.render_widget(HelpLine::default(), controls_area);
+        let minimap_area = areas[area_index + 1];
+        let ruler_area = areas[area_index + 2];
+        let timeline_area = areas[area_index + 3];
+        let goals_area = areas[area_index + 4];
+        let fill_area = areas[area_index + 5];
+        let input_area = areas[area_index + 6];
+        let status_area = areas[area_index + 7];
+        let controls_area = areas[area_index + 8];
+
+        let now = self.settings.now();
+        let today = now.date_naive();
+        let monday = self.mondays[self.selected_mon_idx];
+        let clock = self
+            .week
+            .days()
+            .into_iter()
+            .enumerate()
+            .find(|(idx, _)| monday + Days::new(*idx as u64) == today)
+            .map(
+                |(_, (_, checkpoints))| match running_span(checkpoints, now) {
+                    Some((checkpoint, minutes)) => {
+                        let label = match checkpoint.kind {
+                            CheckpointKind::Work => checkpoint.project.as_deref().unwrap_or("-"),
+                            CheckpointKind::Break => "break",
+                            CheckpointKind::Absence => "absence",
+                            CheckpointKind::Stop => "stop",
+                        };
+                        Clock::Running {
+                            label: label.to_string(),
+                            minutes,
+                        }
+                    }
+                    None => Clock::Stopped,
+                },
+            );
+        let status = StatusLine {
+            stats: DayStats::from_checkpoints(self.week.active_day(), &self.settings.rounding),
+            holiday: self.holidays.get(&self.selected_date()).cloned(),
+            flex: self.flex_balance(),
+            clock,
+            tag_filter: self.tag_filter.clone(),
+            project_filter: self.project_filter.clone(),
+            spinner: (self.pending > 0).then_some(self.theme.symbols.spinner[self.spinner]),
+            theme: self.theme,
+            settings: self.settings,
+        };
+        frame.render_widget(status, status_area);
+        let help = HelpLine {
+            entries: help_entries(self.visual_anchor.is_some()),
+            theme: self.theme,
+        };
+        frame.render_widget(help, controls_area);
 
-        let days_layout = Layout::horizontal(vec![Constraint::Length(5); self.mondays.len()])
+        let days_layout = Layout::horizontal(vec![Constraint::Length(9); self.mondays.len()])
             .spacing(1)
             .split(weeks_area);
 
         for (i, day) in self.mondays.iter().enumerate() {
-            let mut p = Paragraph::new(day.format("%d.%m").to_string());
+            let week = self.settings.locale.week_number(*day);
+            let mut p = Paragraph::new(format!("W{:02} {}", week, self.settings.locale.day(*day)));
             if self.selected_mon_idx == i {
                 p = p.underlined();
             }
             frame.render_widget(p, days_layout[i]);
         }
 
-        let [checkpoint_area] = Layout::vertical(vec![Constraint::Length(4)]).areas(fill_area);
-
-        let [mon_area, tue_area, wed_area, thu_area, fri_area] =
-            Layout::vertical(vec![Constraint::Length(3); 5])
-                .spacing(1)
-                .areas(timeline_area);
-
-        let mon_w = Timeline {
-            checkpoints: &self.week.mon,
-            selected_checkpoint_idx: if self.week.selected_weekday == Weekday::Mon {
-                Some(self.week.selected_checkpoint_idx)
-            } else {
-                None
-            },
-        };
-        let tue_w = Timeline {
-            checkpoints: &self.week.tue,
-            selected_checkpoint_idx: if self.week.selected_weekday == Weekday::Tue {
-                Some(self.week.selected_checkpoint_idx)
-            } else {
-                None
-            },
-        };
-        let wed_w = Timeline {
-            checkpoints: &self.week.wed,
-            selected_checkpoint_idx: if self.week.selected_weekday == Weekday::Wed {
-                Some(self.week.selected_checkpoint_idx)
-            } else {
-                None
-            },
-        };
-        let thu_w = Timeline {
-            checkpoints: &self.week.thu,
-            selected_checkpoint_idx: if self.week.selected_weekday == Weekday::Thu {
-                Some(self.week.selected_checkpoint_idx)
-            } else {
-                None
-            },
+        // The displayed week may have edits not in the loaded range yet
+        let mut minimap = self.minimap.clone();
+        for (idx, (_, checkpoints)) in self.week.days().into_iter().enumerate() {
+            let date = monday + Days::new(idx as u64);
+            match dominant_project(checkpoints, &self.settings.rounding) {
+                Some(project) => minimap.insert(date, project),
+                None => minimap.remove(&date),
+            };
+        }
+        let minimap = Minimap {
+            mondays: &self.mondays,
+            weekdays: self
+                .week
+                .visible_days()
+                .iter()
+                .map(|(day, _)| *day)
+                .collect(),
+            locale: self.settings.locale,
+            projects: &minimap,
+            selected: self.selected_date(),
+            theme: self.theme,
         };
-        let fri_w = Timeline {
-            checkpoints: &self.week.fri,
-            selected_checkpoint_idx: if self.week.selected_weekday == Weekday::Fri {
-                Some(self.week.selected_checkpoint_idx)
-            } else {
-                None
-            },
+        frame.render_widget(minimap, minimap_area);
+
+        let totals_width = if self.layout.show_totals {
+            self.layout.totals_width
+        } else {
+            0
         };
-        frame.render_widget(mon_w, mon_area);
-        frame.render_widget(tue_w, tue_area);
-        frame.render_widget(wed_w, wed_area);
-        frame.render_widget(thu_w, thu_area);
-        frame.render_widget(fri_w, fri_area);
+        let [detail_area, totals_area] =
+            Layout::horizontal(vec![Constraint::Fill(1), Constraint::Length(totals_width)])
+                .areas(fill_area);
+        let [checkpoint_area] = Layout::vertical(vec![Constraint::Length(4)]).areas(detail_area);
 
-        if let Some(selected_ch) = self.week.selected_checkpoint() {
-            let next_ch = self.week.next_checkpoint();
+        if self.layout.show_totals {
+            self.render_project_totals(frame, totals_area);
+        }
+
+        // The ruler follows the selected day as spans are not aligned across days, braille
+        // timelines label their hours themselves
+        if !self.layout.braille {
+            let ruler = HourRuler {
+                checkpoints: self.week.active_day(),
+                selected_checkpoint_idx: Some(self.week.selected_checkpoint_idx),
+                theme: self.theme,
+                zoom: Zoom::LEVELS[self.zoom_level],
+                settings: self.settings,
+            };
+            frame.render_widget(ruler, ruler_area);
+        }
+
+        let monday = self.mondays[self.selected_mon_idx];
+        let week_days_off = monday
+            .iter_days()
+            .take(7)
+            .filter(|date| !matches!(date.weekday(), Weekday::Sat | Weekday::Sun))
+            .filter(|date| self.day_off(*date).is_some())
+            .count();
+        let goals = Goals {
+            day_minutes: DayStats::from_checkpoints(
+                self.week.active_day(),
+                &self.settings.rounding,
+            )
+            .tracked_minutes,
+            week_minutes: self
+                .week
+                .days()
+                .iter()
+                .map(|(_, day)| {
+                    DayStats::from_checkpoints(day, &self.settings.rounding).tracked_minutes
+                })
+                .sum(),
+            goals: self.goals.without_days_off(
+                self.day_off(self.selected_date()).is_some(),
+                week_days_off as u32,
+            ),
+            theme: self.theme,
+            settings: self.settings,
+        };
+        frame.render_widget(goals, goals_area);
 
-            let rounded_start = selected_ch.rounded_time();
+        let day_areas = Layout::vertical(vec![Constraint::Length(3); days as usize])
+            .spacing(1)
+            .split(timeline_area);
+
+        for ((weekday, checkpoints), day_area) in
+            self.week.visible_days().into_iter().zip(day_areas.iter())
+        {
+            let is_selected_day = self.week.selected_weekday == weekday;
+            let date = self
+                .settings
+                .locale
+                .date_in_week(self.mondays[self.selected_mon_idx], weekday);
+            let locked = self.locked_days.contains(&date);
+            let holiday = self.holidays.get(&date);
+            let day_type = match self.day_types.get(&date) {
+                Some(&day_type) => Some(day_type),
+                // Work logged on a holiday stays visible to be flagged
+                None => holiday
+                    .filter(|_| checkpoints.is_empty())
+                    .map(|_| DayType::Holiday),
+            };
+            if let Some(day_type) = day_type {
+                let bar = DayTypeBar {
+                    date,
+                    day_type,
+                    name: holiday.cloned(),
+                    selected: is_selected_day,
+                    theme: self.theme,
+                    locale: self.settings.locale,
+                };
+                frame.render_widget(bar, *day_area);
+                continue;
+            }
+            if self.layout.braille {
+                let timeline = BrailleTimeline {
+                    checkpoints,
+                    locked,
+                    holiday: holiday.is_some(),
+                    selected_checkpoint_idx: is_selected_day
+                        .then_some(self.week.selected_checkpoint_idx),
+                    selected_range: self.visual_range().filter(|_| is_selected_day),
+                    theme: self.theme,
+                    tag_filter: self.tag_filter.as_deref(),
+                    project_filter: self.project_filter.as_deref(),
+                    settings: self.settings,
+                };
+                frame.render_widget(timeline, *day_area);
+                continue;
+            }
+            let timeline = Timeline {
+                checkpoints,
+                locked,
+                holiday: holiday.is_some(),
+                selected_checkpoint_idx: is_selected_day
+                    .then_some(self.week.selected_checkpoint_idx),
+                selected_range: self.visual_range().filter(|_| is_selected_day),
+                theme: self.theme,
+                zoom: Zoom::LEVELS[self.zoom_level],
+                rounded_times: self.layout.rounded_times,
+                tag_filter: self.tag_filter.as_deref(),
+                project_filter: self.project_filter.as_deref(),
+                settings: self.settings,
+            };
+            frame.render_widget(timeline, *day_area);
+        }
 
-            let mut lines = vec![Line::from(vec![
-                Span::from(" Started: ").fg(Color::Gray),
-                Span::from(selected_ch.time.format("%H:%M").to_string()),
-                Span::from(" ("),
-                Span::from(rounded_start.format("%H:%M").to_string()),
-                Span::from(")"),
-            ])];
+        if let Some(selected_ch) = self
+            .week
+            .selected_checkpoint()
+            .filter(|_| self.layout.show_detail)
+        {
+            let next_ch = self.week.next_checkpoint();
 
+            // The times as displayed, followed by the other kind in parentheses
+            let rounded = self.layout.rounded_times;
+            let time_spans = |label, ch: &Checkpoint| {
+                let (raw, rounded_time) = (
+                    self.settings.locale.time(ch.time.time()),
+                    self.settings
+                        .locale
+                        .time(ch.rounded_time(&self.settings.rounding).time()),
+                );
+                let (shown, other) = if rounded {
+                    (rounded_time, format!(" (raw {})", raw))
+                } else {
+                    (raw, format!(" (rounded {})", rounded_time))
+                };
+                Line::from(vec![
+                    Span::from(label).fg(self.theme.help),
+                    Span::from(shown),
+                    Span::from(other).fg(self.theme.help),
+                ])
+            };
+
+            let mut lines = vec![time_spans(" Started: ", selected_ch)];
             if let Some(next_ch) = next_ch {
-                let rounded_end = next_ch.rounded_time();
+                lines.push(time_spans("Finished: ", next_ch));
+            }
+
+            let comment_label = " Comment: ";
+            if editing_inline {
+                // keep 1 for the cursor
+                let width = checkpoint_area
+                    .width
+                    .saturating_sub(comment_label.len() as u16 + 1);
+                let scroll = self.input.visual_scroll(width as usize);
+                let value: String = self.input.value().chars().skip(scroll).collect();
+                let row = lines.len() as u16;
                 lines.push(Line::from(vec![
-                    Span::from("Finished: ").fg(Color::Gray),
-                    Span::from(next_ch.time.format("%H:%M").to_string()),
-                    Span::from(" ("),
-                    Span::from(rounded_end.format("%H:%M").to_string()),
-                    Span::from(")"),
+                    Span::from(comment_label).fg(self.theme.accent),
+                    Span::from(value),
                 ]));
+                if row < checkpoint_area.height {
+                    let x = self.input.visual_cursor().max(scroll) - scroll;
+                    frame.set_cursor_position((
+                        checkpoint_area.x + comment_label.len() as u16 + x as u16,
+                        checkpoint_area.y + row,
+                    ));
+                }
+            } else {
+                let mut comment = vec![Span::from(comment_label).fg(self.theme.help)];
+                for word in selected_ch
+                    .message
+                    .as_deref()
+                    .unwrap_or("")
+                    .split_inclusive(' ')
+                {
+                    if word.starts_with('#') {
+                        comment.push(Span::from(word).fg(self.theme.accent).bold());
+                    } else {
+                        comment.push(Span::from(word).fg(self.theme.comment));
+                    }
+                }
+                lines.push(Line::from(comment));
             }
 
-            lines.push(Line::from(vec![
-                Span::from(" Comment: ").fg(Color::Gray),
-                Span::from(selected_ch.message.as_deref().unwrap_or("")).fg(Color::Green),
-            ]));
-
+            let project_label = " Project: ";
+            let activity_suffix = selected_ch
+                .activity
+                .as_ref()
+                .map(|activity| format!(" / {}", activity))
+                .unwrap_or_default();
+            let mut task_link = None;
             if let Some(prefix) = &self.task_url_prefix {
                 lines.push(Line::from(vec![
-                    Span::from(" Project: ").fg(Color::Gray),
-                    Span::from(prefix).fg(Color::Gray),
+                    Span::from(project_label).fg(self.theme.help),
+                    Span::from(prefix).fg(self.theme.help),
                     Span::from(selected_ch.project.as_deref().unwrap_or("")),
+                    Span::from(activity_suffix.clone()).fg(self.theme.help),
                 ]));
+                if let Some(project) = &selected_ch.project {
+                    task_link = Some((lines.len() - 1, format!("{}{}", prefix, project)));
+                }
             } else {
                 lines.push(Line::from(vec![
-                    Span::from(" Project: ").fg(Color::Gray),
+                    Span::from(project_label).fg(self.theme.help),
                     Span::from(selected_ch.project.as_deref().unwrap_or("")),
+                    Span::from(activity_suffix).fg(self.theme.help),
                 ]));
             }
 
-            frame.render_widget(Paragraph::new(lines), checkpoint_area);
-        }
-
-        self.render_input(frame, input_area);
-
-        if self.show_task_popup {
-            let area = centered_rect(60, 80, frame.area());
-            frame.render_widget(Clear, area);
-            let items: Vec<ListItem> = self
-                .tasks
-                .iter()
-                .map(|t| {
-                    let mut header_spans = vec![];
-
-                    if let (Some(spent), Some(total)) = (&t.time_spent, &t.time_total) {
-                        let parse_time = |time_str: &str| -> Option<i32> {
-                            let parts: Vec<&str> = time_str.split(':').collect();
-                            if parts.len() == 2 {
-                                let hours: i32 = parts[0].parse().ok()?;
-                                let minutes: i32 = parts[1].parse().ok()?;
-                                Some(hours * 60 + minutes)
-                            } else {
-                                None
-                            }
-                        };
-
-                        if let (Some(spent_mins), Some(total_mins)) = (parse_time(spent), parse_time(total)) {
-                            let left_mins = total_mins - spent_mins;
-                            let sign = if left_mins < 0 { "-" } else { "" };
-                            let abs_minutes = left_mins.abs();
-                            let h = abs_minutes / 60;
-                            let m = abs_minutes % 60;
-                            let color = if left_mins < 0 { Color::Red } else { Color::Green };
-                            header_spans.push(Span::from(format!("[{}{}:{:02}] ", sign, h, m)).fg(color));
-                        }
-                    }
-
-                    header_spans.push(Span::from(format!("{} - {}", t.id, t.name)));
-
-                    match (&t.time_spent, &t.time_total) {
-                        (Some(s), Some(total)) => {
-                            header_spans.push(Span::from(" ["));
-                            header_spans.push(Span::from(s.to_string()).fg(Color::Green));
-                            header_spans.push(Span::from(" / "));
-                            header_spans.push(Span::from(total.to_string()).fg(Color::Blue));
-                            header_spans.push(Span::from("]"));
-                        }
-                        (Some(s), None) => {
-                            header_spans.push(Span::from(" ["));
-                            header_spans.push(Span::from(s.to_string()).fg(Color::Green));
-                            header_spans.push(Span::from("]"));
-                        }
-                        _ => {}
-                    }
+            lines.push(Line::from(vec![
+                Span::from("Billable: ").fg(self.theme.help),
+                Span::from(if selected_ch.billable { "yes" } else { "no" }),
+            ]));
 
-                    let header = Line::from(header_spans);
+            frame.render_widget(Paragraph::new(lines), checkpoint_area);
 
-                    if self.show_task_url {
-                        if let Some(prefix) = &self.task_url_prefix {
-                            let url = format!("{}{}", prefix, t.id);
-                            let lines = vec![header, Line::from(Span::from(url).fg(Color::Blue))];
-                            ListItem::new(lines)
-                        } else {
-                            ListItem::new(header)
-                        }
-                    } else {
-                        ListItem::new(header)
-                    }
-                })
-                .collect();
-            let list = List::new(items)
-                .block(Block::bordered().title("Select Task"))
-                .highlight_style(Style::default().fg(Color::Yellow))
-                .highlight_symbol("▶ ");
+            // Make the URL clickable in terminals supporting hyperlinks
+            if let Some((row, url)) =
+                task_link.filter(|(row, _)| (*row as u16) < checkpoint_area.height)
+            {
+                let link_area = Rect {
+                    x: checkpoint_area.x + project_label.len() as u16,
+                    y: checkpoint_area.y + row as u16,
+                    width: checkpoint_area
+                        .width
+                        .saturating_sub(project_label.len() as u16),
+                    height: 1,
+                };
+                frame.render_widget(Hyperlink { url: &url }, link_area);
+            }
+        }
 
-            frame.render_stateful_widget(list, area, &mut self.task_popup_state);
+        if editing_inline {
+            frame.render_widget(
+                self.theme
+                    .block()
+                    .title("Input")
+                    .style(Style::default().gray()),
+                input_area,
+            );
+        } else {
+            self.render_input(frame, input_area);
         }
     }
 
     /// Reads the crossterm events and updates the state of [`App`].
     ///
-    /// If your application needs to perform work in between handling events, you can use the
-    /// [`event::poll`] function to check if there are any events available with a timeout.
+    /// Waits at most [`TICK_RATE`] for an event so that time dependent parts of the UI, like the
+    /// running span, are redrawn even when there is no input. While background calls are in
+    /// flight it waits only [`SPINNER_RATE`] to pick up their outcomes and animate the spinner.
     async fn handle_crossterm_events(&mut self) -> Result<()> {
+        let timeout = if self.pending > 0 {
+            self.spinner = (self.spinner + 1) % self.theme.symbols.spinner.len();
+            SPINNER_RATE
+        } else {
+            TICK_RATE
+        };
+        if !event::poll(timeout)? {
+            return Ok(());
+        }
         let event = event::read()?;
         match event {
             // it's important to check KeyEventKind::Press to avoid handling key release events
@@ -411,7 +1185,23 @@ impl App {
                         self.push_message().await;
                         self.stop_editing();
                     }
-                    KeyCode::Esc => self.stop_editing(),
+                    KeyCode::Esc => {
+                        self.input.reset();
+                        self.stop_editing();
+                    }
+                    KeyCode::Tab => self.complete_message(true),
+                    KeyCode::BackTab => self.complete_message(false),
+                    _ => {
+                        self.completion = None;
+                        self.input.handle_event(&event);
+                    }
+                },
+                _ => match key.code {
+                    KeyCode::Enter => self.submit_input().await,
+                    KeyCode::Esc => {
+                        self.input.reset();
+                        self.stop_editing();
+                    }
                     _ => {
                         self.input.handle_event(&event);
                     }
@@ -426,63 +1216,51 @@ impl App {
 
     /// Handles the key events and updates the state of [`App`].
     async fn on_key_event(&mut self, key: KeyEvent) {
-        if self.show_task_popup {
+        if let Some(action) = self.pending_confirm {
             match key.code {
-                KeyCode::Esc => self.show_task_popup = false,
-                KeyCode::Down => {
-                    self.task_popup_state.select_next();
-                }
-                KeyCode::Up => {
-                    self.task_popup_state.select_previous();
-                }
-                KeyCode::Right => {
-                    self.show_task_url = !self.show_task_url;
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                    self.pending_confirm = None;
                 }
-                KeyCode::Enter => {
-                    self.assign_selected_task().await;
-                    self.show_task_popup = false;
+                KeyCode::Char(c) => {
+                    let choice = c.to_ascii_lowercase().to_string();
+                    if action.choices().iter().any(|(key, _)| *key == choice) {
+                        self.pending_confirm = None;
+                        self.run_confirmed(action, &choice).await;
+                    }
                 }
                 _ => {}
             }
             return;
         }
 
-        match (key.modifiers, key.code) {
-            (_, KeyCode::Esc | KeyCode::Char('q'))
-            | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit().await,
-            // Add other key handlers here.
-            (_, KeyCode::Char('m')) => self.start_editing(),
-            (_, KeyCode::Char('p')) => self.fetch_tasks().await,
-            (_, KeyCode::Char(' ')) => self.append_checkpoint().await,
-            (_, KeyCode::Char('s')) => self.split_checkpoint().await,
-            (_, KeyCode::Char('d')) => self.delete_checkpoint().await,
-            (KeyModifiers::CONTROL, KeyCode::Char('l')) => self.lenghten_ctrl_r().await,
-            (_, KeyCode::Char('l')) => self.lenghten_r().await,
-            (KeyModifiers::CONTROL, KeyCode::Char('h')) => self.lenghten_ctrl_l().await,
-            (_, KeyCode::Char('h')) => self.lenghten_l().await,
-            (_, KeyCode::Right) => self.move_right().await,
-            (_, KeyCode::Left) => self.move_left().await,
-            (_, KeyCode::Up) => self.move_up().await,
-            (_, KeyCode::Down) => self.move_down().await,
-            (_, KeyCode::Tab) => self.cycle_weeks().await,
-            (_, KeyCode::Char('r')) => self.mark_registered().await,
-            _ => {}
+        match self.screen() {
+            Screen::Week => self.on_week_key(key).await,
+            screen => self.on_screen_key(screen, key).await,
         }
     }
 
-    async fn fetch_tasks(&mut self) {
-        match fetch_tasks(&self.auth_config).await {
-            Ok(tasks) => {
-                self.tasks = tasks;
-                self.show_task_popup = true;
-                self.task_popup_state.select(Some(0));
-            }
-            Err(err) => {
-                eprintln!("Failed to fetch tasks: {}", err);
-            }
+    /// Handles the keys of the week screen
+    async fn on_week_key(&mut self, key: KeyEvent) {
+        if let Some(command) = Command::for_key(&key, self.visual_anchor.is_some()) {
+            self.run_command(command).await;
         }
     }
 
+    fn fetch_tasks(&mut self) {
+        let auth_config = self.auth_config.clone();
+        self.spawn(async move {
+            let Ok(auth_config) =
+                tokio::task::spawn_blocking(move || with_stored_password(auth_config)).await
+            else {
+                return Outcome::Failed("The keyring could not be reached".to_string());
+            };
+            match fetch_tasks(&auth_config).await {
+                Ok(tasks) => Outcome::TasksFetched(tasks),
+                Err(err) => Outcome::Failed(format!("Failed to fetch tasks: {}", err)),
+            }
+        });
+    }
+
     async fn assign_selected_task(&mut self) {
         let task_id = if let Some(selected_idx) = self.task_popup_state.selected() {
             self.tasks.get(selected_idx).map(|t| t.id.to_string())
@@ -491,168 +1269,839 @@ impl App {
         };
 
         if let Some(id) = task_id {
-            // Update local state
-            {
-                if let Some(selected_checkpoint) = self.week.selected_checkpoint_mut() {
-                    selected_checkpoint.project = Some(id);
+            self.assign_project(id, None);
+        }
+    }
+
+    /// Set the project and activity of the selected checkpoint, or of all checkpoints in the
+    /// visual selection. Spans without a comment get the default one of the project, a single
+    /// span opens it for editing.
+    fn assign_project(&mut self, id: String, activity: Option<String>) {
+        self.note_recent_project(&id);
+        let default_message =
+            projects::find(&self.projects, &id).and_then(|project| project.default_message.clone());
+        let rollback = self.week.clone();
+        if let Some(range) = self.visual_range() {
+            self.visual_anchor = None;
+            let selection = &mut self.week.active_day_mut()[range];
+            for checkpoint in selection.iter_mut() {
+                checkpoint.project = Some(id.clone());
+                checkpoint.activity = activity.clone();
+                if let Some(message) = default_message
+                    .clone()
+                    .filter(|_| !checkpoint.has_message())
+                {
+                    checkpoint.set_message(message);
                 }
             }
+            let selection = selection.to_vec();
+            self.persist(rollback, vec![Write::Update(selection)]);
+            return;
+        }
 
-            // Update remote state
-            if let Some(selected_checkpoint) = self.week.selected_checkpoint() {
-                if let Err(err) = update_checkpoint(&self.db, selected_checkpoint).await {
-                    eprintln!("{}", err);
-                }
+        if let Some(selected_checkpoint) = self.week.selected_checkpoint_mut() {
+            selected_checkpoint.project = Some(id);
+            selected_checkpoint.activity = activity;
+            let prefill = default_message.filter(|_| !selected_checkpoint.has_message());
+            let selected_checkpoint = selected_checkpoint.clone();
+            self.persist(rollback, vec![Write::Update(vec![selected_checkpoint])]);
+            if let Some(message) = prefill {
+                self.edit_message(message);
             }
         }
     }
 
-    /// Set running to false to quit the application.
-    async fn quit(&mut self) {
-        self.running = false;
+    /// Moves the project to the top of the recently used ones
+    fn note_recent_project(&mut self, id: &str) {
+        self.recent_projects.retain(|recent| recent != id);
+        self.recent_projects.insert(0, id.to_string());
     }
 
-    /// Append new checkpoint with the current time
-    async fn append_checkpoint(&mut self) {
-        // Create a new checkpoint with the current time
-        match insert_checkpoint(&self.db, Checkpoint::new()).await {
-            Ok(checkpoint) => self.week.append_checkpoint(checkpoint),
-            Err(err) => eprintln!("{}", err),
-        };
-        self.load_week().await;
+    /// Assigns the most recently used project other than the one the selected span already
+    /// has, so that repeating the key alternates between the last two projects
+    fn assign_last_project(&mut self) {
+        let current = self
+            .week
+            .selected_checkpoint()
+            .filter(|_| self.visual_anchor.is_none())
+            .and_then(|ch| ch.project.clone());
+        let last = self
+            .recent_projects
+            .iter()
+            .find(|id| Some(*id) != current.as_ref())
+            .cloned();
+        match last {
+            Some(id) => self.assign_project(id, None),
+            None => self.show_toast("No other project used yet".to_string()),
+        }
     }
 
-    async fn split_checkpoint(&mut self) {
-        let (start_time, end_time) = {
-            let selected = self.week.selected_checkpoint();
-            let next = self.week.next_checkpoint();
-            match (selected, next) {
-                (Some(s), Some(n)) => (s.time, n.time),
-                _ => return,
-            }
-        };
-
-        let duration = end_time - start_time;
-        // Check if duration is positive
-        if duration <= TimeDelta::zero() {
+    fn open_project_popup(&mut self) {
+        if projects::active(&self.projects).next().is_none() {
+            self.show_toast("No active projects in projects.toml".to_string());
             return;
         }
+        self.open(Screen::Projects);
+        self.project_popup_state.select(Some(0));
+    }
+
+    fn start_visual_selection(&mut self) {
+        if self.week.selected_checkpoint().is_some() {
+            self.visual_anchor = Some(self.week.selected_checkpoint_idx);
+        }
+    }
 
-        let half_duration = duration / 2;
-        let mid_time = start_time + half_duration;
+    /// Indexes of the checkpoints covered by the visual selection
+    fn visual_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let current = self.week.selected_checkpoint_idx;
+        let last = self.week.active_day().len().checked_sub(1)?;
+        Some(anchor.min(current).min(last)..=anchor.max(current).min(last))
+    }
 
-        let mut new_checkpoint = Checkpoint::new();
-        new_checkpoint.time = mid_time;
+    /// Toggle the registered flag of all checkpoints in the visual selection at once
+    async fn mark_selection_registered(&mut self) {
+        let Some(range) = self.visual_range() else {
+            return;
+        };
+        self.visual_anchor = None;
 
-        if let Err(err) = insert_checkpoint(&self.db, new_checkpoint).await {
-            eprintln!("{}", err);
+        let rollback = self.week.clone();
+        let selection = &mut self.week.active_day_mut()[range];
+        let registered = !selection.iter().all(|ch| ch.registered);
+        for checkpoint in selection.iter_mut() {
+            checkpoint.registered = registered;
         }
-        self.load_week().await;
+        let selection = selection.to_vec();
+        self.persist(rollback, vec![Write::Update(selection)]);
     }
 
-    async fn delete_checkpoint(&mut self) {
-        if let Some(selected) = self.week.selected_checkpoint() {
-            if let Err(err) = delete_checkpoint(&self.db, selected).await {
-                eprintln!("{}", err);
-            }
-            self.load_week().await;
+    /// Put the checkpoints of the selected day back in time order
+    fn fix_day_overlaps(&mut self, fix: OverlapFix) {
+        let rollback = self.week.clone();
+        let day = self.week.active_day_mut();
+        let changed: Vec<Checkpoint> = fix_overlaps(day, fix)
+            .into_iter()
+            .map(|idx| day[idx].clone())
+            .collect();
+        if !changed.is_empty() {
+            self.persist(rollback, vec![Write::Update(changed)]);
+        }
+    }
+
+    /// Point out spans ending before they start, which count as zero minutes
+    fn warn_overlaps(&mut self, overlaps: usize) {
+        if overlaps > 0 {
+            self.show_toast(format!(
+                "{} span(s) end before they start, put them in order with {}",
+                overlaps,
+                key_hint(Command::FixOverlaps)
+            ));
         }
     }
 
-    async fn load_checkpoints(&mut self, day: NaiveDate) -> Vec<Checkpoint> {
-        match find_checkpoints(&self.db, &day).await {
-            Ok(checkpoints) => checkpoints,
-            Err(err) => {
-                eprintln!("{}", err);
-                vec![]
+    /// Marks every checkpoint of the selected day registered in a single batched write
+    fn register_day(&mut self) {
+        let rollback = self.week.clone();
+        let mut changed = vec![];
+        for checkpoint in self.week.active_day_mut() {
+            if !checkpoint.registered {
+                checkpoint.registered = true;
+                changed.push(checkpoint.clone());
             }
         }
+        if !changed.is_empty() {
+            self.persist(rollback, vec![Write::Update(changed)]);
+        }
     }
 
-    async fn load_week(&mut self) {
-        let first_mon = self.mondays[self.selected_mon_idx]; // must be mondays in a month
-        let mon = self.load_checkpoints(first_mon).await;
-        let tue = self.load_checkpoints(first_mon + Days::new(1)).await;
-        let wed = self.load_checkpoints(first_mon + Days::new(2)).await;
-        let thu = self.load_checkpoints(first_mon + Days::new(3)).await;
-        let fri = self.load_checkpoints(first_mon + Days::new(4)).await;
+    async fn delete_selection(&mut self) {
+        let Some(range) = self.visual_range() else {
+            return;
+        };
+        self.visual_anchor = None;
 
-        let mut unregistered: Vec<(Checkpoint, u32)> = vec![];
+        let rollback = self.week.clone();
+        self.week.selected_checkpoint_idx = *range.start();
+        let deleted = self.week.active_day_mut().drain(range).collect();
+        self.week.select_max_checkpoint_idx();
+        self.persist(rollback, vec![Write::Delete(deleted)]);
+    }
 
-        // Iterate through each day's checkpoints and collect unregistered ones, excluding the last checkpoint of each day
-        for day_checkpoints in [&mon, &tue, &wed, &thu, &fri] {
-            if day_checkpoints.is_empty() {
-                continue;
-            }
-            let last_idx = day_checkpoints.len() - 1;
-            for (idx, checkpoint) in day_checkpoints.iter().enumerate() {
-                if !checkpoint.registered && idx != last_idx {
-                    let start_time = checkpoint.time;
-                    let end_time = day_checkpoints[idx + 1].time;
+    /// Set running to false to quit the application.
+    async fn quit(&mut self) {
+        self.running = false;
+    }
 
-                    let minutes = calculate_duration_minutes(start_time, end_time);
+    /// Append new checkpoint with the current time
+    async fn append_checkpoint(&mut self) {
+        let mut checkpoints = self.lunch_break();
+        checkpoints.push(Checkpoint::new());
+        // Only the new checkpoint, selected once shown, can duplicate the one before it
+        if !self.add_checkpoints(checkpoints, true) {
+            return;
+        }
 
-                    unregistered.push((checkpoint.clone(), minutes));
-                }
-            }
+        let (day, idx) = (self.week.active_day(), self.week.selected_checkpoint_idx);
+        if idx > 0 && is_near_duplicate(&day[idx - 1], &day[idx], &self.settings.rounding) {
+            self.confirm(ConfirmAction::MergeDuplicate);
         }
+    }
 
-        self.week = Week {
-            mon,
-            tue,
-            wed,
-            thu,
-            fri,
-            unregistered_checkpoints: unregistered,
-            selected_weekday: chrono::Weekday::Mon,
-            selected_checkpoint_idx: 0,
-        };
+    /// Merges the near-duplicate checkpoints of the selected day
+    fn merge_day_duplicates(&mut self) {
+        let rollback = self.week.clone();
+        let (changed, removed) =
+            merge_near_duplicates(self.week.active_day_mut(), &self.settings.rounding);
+        self.week.select_max_checkpoint_idx();
+        self.persist_merge(rollback, changed, removed);
     }
 
-    async fn lenghten_r(&mut self) {
-        if let Some(selected) = self.week.selected_checkpoint_mut() {
-            if let Some(t) = selected.time.checked_add_signed(TimeDelta::minutes(15)) {
-                selected.time = t;
+    /// Stores the merged near-duplicates, nothing when none were found
+    fn persist_merge(
+        &mut self,
+        rollback: Week,
+        changed: Vec<Checkpoint>,
+        removed: Vec<Checkpoint>,
+    ) {
+        if removed.is_empty() {
+            return;
+        }
+        let mut writes = vec![Write::Delete(removed)];
+        if !changed.is_empty() {
+            writes.push(Write::Update(changed));
+        }
+        self.persist(rollback, writes);
+    }
 
-                if let Err(err) = update_checkpoint(&self.db, selected).await {
-                    eprintln!("{}", err);
-                }
+    /// Merges the near-duplicate checkpoints of every unlocked day of the displayed week
+    fn merge_week_duplicates(&mut self) {
+        let rollback = self.week.clone();
+        let monday = self.mondays[self.selected_mon_idx];
+        let (mut changed, mut removed) = (vec![], vec![]);
+        for (date, day) in monday.iter_days().zip(self.week.days_mut()) {
+            if !self.locked_days.contains(&date) {
+                let (day_changed, day_removed) =
+                    merge_near_duplicates(day, &self.settings.rounding);
+                changed.extend(day_changed);
+                removed.extend(day_removed);
             }
         }
+        self.week.select_max_checkpoint_idx();
+        self.show_toast(format!(
+            "Merged {} near-duplicate checkpoints",
+            removed.len()
+        ));
+        self.persist_merge(rollback, changed, removed);
     }
 
-    async fn lenghten_ctrl_r(&mut self) {
-        if let Some(next) = self.week.next_checkpoint_mut() {
-            if let Some(t) = next.time.checked_add_signed(TimeDelta::minutes(15)) {
-                next.time = t;
+    /// Break and resumed work to add before a checkpoint appended now while today is shown, see
+    /// [`LunchBreak`]
+    fn lunch_break(&mut self) -> Vec<Checkpoint> {
+        let Some(rule) = self.lunch_break else {
+            return vec![];
+        };
+        let now = self.settings.now();
+        let Some(today) = self.shown_day(now.date_naive()) else {
+            return vec![];
+        };
+        let (Some((start, end)), Some(last)) = (rule.place(today, now), today.last().cloned())
+        else {
+            return vec![];
+        };
+        self.show_toast(format!(
+            "Added a lunch break {}-{}",
+            self.settings.locale.time(start.time()),
+            self.settings.locale.time(end.time())
+        ));
+        let lunch = Checkpoint {
+            time: start,
+            kind: CheckpointKind::Break,
+            ..Checkpoint::new()
+        };
+        // The work before the break goes on after it
+        let resumed = Checkpoint {
+            id: None,
+            time: end,
+            registered: false,
+            toggl_id: None,
+            ..last
+        };
+        vec![lunch, resumed]
+    }
 
-                if let Err(err) = update_checkpoint(&self.db, next).await {
-                    eprintln!("{}", err);
-                }
+    /// Remembers the selected span to be put later
+    fn yank_span(&mut self) {
+        let Some(selected) = self.week.selected_checkpoint().cloned() else {
+            return;
+        };
+        let what = selected
+            .project
+            .clone()
+            .or(selected.message.clone())
+            .unwrap_or_else(|| "span".to_string());
+        self.yanked = Some(selected);
+        self.show_toast(format!(
+            "Yanked {}, put it now with {}",
+            what,
+            key_hint(Command::PutSpan)
+        ));
+    }
+
+    /// Starts a span at the current time with the project and message of the yanked one, for
+    /// recurring activities like standups
+    fn put_span(&mut self) {
+        let Some(yanked) = &self.yanked else {
+            return self.show_toast("Nothing yanked yet".to_string());
+        };
+        let checkpoint = Checkpoint {
+            id: None,
+            time: self.settings.now(),
+            registered: false,
+            toggl_id: None,
+            ..yanked.clone()
+        };
+        self.add_checkpoints(vec![checkpoint], true);
+    }
+
+    /// Shows the checkpoints in the displayed week, when they belong to it, and stores them.
+    ///
+    /// The last of them is selected when `select` is set, otherwise the selection stays. Returns
+    /// whether the last of them is shown, `false` also when a locked day refused them.
+    fn add_checkpoints(&mut self, checkpoints: Vec<Checkpoint>, select: bool) -> bool {
+        let rollback = self.week.clone();
+        let monday = self.mondays[self.selected_mon_idx];
+        let weekday = self.week.selected_weekday;
+        let idx = self.week.selected_checkpoint_idx;
+        let selected_id = self.week.selected_checkpoint().and_then(|ch| ch.id.clone());
+
+        if let Some(date) = checkpoints
+            .iter()
+            .map(|ch| ch.time.date_naive())
+            .find(|date| self.locked_days.contains(date))
+        {
+            self.refuse_locked(date);
+            return false;
+        }
+
+        let (mut added, mut shown) = (vec![], false);
+        for mut checkpoint in checkpoints {
+            // Known before the insert so that edits made meanwhile can refer to the checkpoint
+            checkpoint.id = Some(Uuid::new_v4().simple().to_string());
+            let date = checkpoint.time.date_naive();
+            shown = (0..7).contains(&(date - monday).num_days());
+            if shown {
+                self.week.selected_weekday = date.weekday();
+                self.week.insert_checkpoint(checkpoint.clone());
             }
+            added.push(checkpoint);
         }
+
+        if !select {
+            self.week.selected_weekday = weekday;
+            self.week.selected_checkpoint_idx = self
+                .week
+                .active_day()
+                .iter()
+                .position(|ch| selected_id.is_some() && ch.id == selected_id)
+                .unwrap_or(idx);
+        }
+        self.persist(rollback, vec![Write::Insert(added)]);
+        shown
     }
 
-    async fn lenghten_l(&mut self) {
+    /// Whether today's last checkpoint starts a span that is still running, as shown before the
+    /// edits reach the store. `None` when today is not in the displayed week.
+    fn is_clock_running(&self) -> Option<bool> {
+        let now = self.settings.now();
+        let today = self.shown_day(now.date_naive())?;
+        Some(running_span(today, now).is_some())
+    }
+
+    fn toggle_clock(&mut self) {
+        match self.is_clock_running() {
+            Some(true) => self.stop_clock(),
+            Some(false) => {
+                self.add_checkpoints(vec![Checkpoint::new()], false);
+            }
+            None => self.show_toast(format!(
+                "Go to today with {} to start or stop the clock",
+                key_hint(Command::JumpToToday)
+            )),
+        }
+    }
+
+    /// Ends the running span now with a stop checkpoint
+    fn stop_clock(&mut self) {
+        let checkpoint = Checkpoint {
+            kind: CheckpointKind::Stop,
+            ..Checkpoint::new()
+        };
+        self.add_checkpoints(vec![checkpoint], false);
+    }
+
+    /// Ends the running span when the app is going away. Today's checkpoints are read from the
+    /// store when another week is shown, there is no UI left to hold up.
+    async fn stop_clock_on_shutdown(&mut self) {
+        let running = match self.is_clock_running() {
+            Some(running) => running,
+            None => {
+                let now = self.settings.now();
+                // Nobody is left to tell about a failure
+                find_checkpoints(&self.db, &now.date_naive(), self.settings.timezone)
+                    .await
+                    .is_ok_and(|today| running_span(&today, now).is_some())
+            }
+        };
+        if running {
+            self.stop_clock();
+        }
+    }
+
+    /// Offers to end the running span when the user left the computer for
+    /// [`App::idle_minutes`], checked in the background every [`IDLE_CHECK_RATE`]
+    fn check_idle(&mut self) {
+        let Some(minutes) = self.idle_minutes else {
+            return;
+        };
+        if self.idle_checked.elapsed() < IDLE_CHECK_RATE
+            || self.pending_confirm.is_some()
+            || self.input_mode != InputMode::Normal
+        {
+            return;
+        }
+        self.idle_checked = Instant::now();
+
+        let (db, settings) = (self.db.clone(), self.settings);
+        self.spawn(async move {
+            // Asks a command, which must not hold up the other tasks
+            let idle = tokio::task::spawn_blocking(idle_time).await.ok().flatten();
+            let Some(since) = idle
+                .filter(|idle| idle.as_secs() >= minutes as u64 * 60)
+                .and_then(|idle| TimeDelta::from_std(idle).ok())
+                .map(|idle| settings.now() - idle)
+            else {
+                return Outcome::IdleChecked(None);
+            };
+            match find_checkpoints(&db, &since.date_naive(), settings.timezone).await {
+                Ok(checkpoints) => Outcome::IdleChecked(
+                    running_span(&checkpoints, settings.now())
+                        .is_some_and(|(running, _)| running.time < since)
+                        .then_some(since),
+                ),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Offers to end the running span at `since`, unless the user is busy with something else
+    /// since the check began
+    fn idle_checked(&mut self, since: Option<Time>) {
+        let Some(since) = since else {
+            return;
+        };
+        if self.pending_confirm.is_some() || self.input_mode != InputMode::Normal {
+            return;
+        }
+        self.idle_since = Some(since);
+        self.show_toast(format!(
+            "Idle since {}",
+            self.settings.locale.time(since.time())
+        ));
+        self.confirm(ConfirmAction::StopAtIdle);
+    }
+
+    /// Sets the Slack status to the one of the running span when that changed, checked every
+    /// [`SLACK_CHECK_RATE`] while today is shown. A failed update is tried again on the next check.
+    fn update_slack_status(&mut self) {
+        let Some(config) = self.slack.clone() else {
+            return;
+        };
+        // The week may still be loading, or an update still be on its way
+        if self.slack_checked.elapsed() < SLACK_CHECK_RATE || self.pending > 0 {
+            return;
+        }
+        self.slack_checked = Instant::now();
+
+        let now = self.settings.now();
+        let Some(today) = self.shown_day(now.date_naive()) else {
+            return;
+        };
+        let running = running_span(today, now).map(|(running, _)| running);
+        let status = slack::status(running, &self.projects, &config);
+        if self.slack_status.as_ref() == Some(&status) {
+            return;
+        }
+        self.spawn(async move {
+            match slack::set_status(&config, &status).await {
+                Ok(()) => Outcome::SlackStatusSet(status),
+                Err(err) => Outcome::Failed(format!("Failed to set the Slack status: {}", err)),
+            }
+        });
+    }
+
+    /// Shows the reminders of [remind] due for today as desktop notifications, checked every
+    /// [`REMIND_CHECK_RATE`] while today is shown
+    fn check_reminders(&mut self) {
+        if self.reminders_checked.elapsed() < REMIND_CHECK_RATE {
+            return;
+        }
+        self.reminders_checked = Instant::now();
+
+        let now = self.settings.now();
+        let Some(today) = self.shown_day(now.date_naive()).cloned() else {
+            return;
+        };
+        let target = self.goals.daily_target_hours;
+        for (summary, body) in self
+            .reminders
+            .due(&self.remind, &self.settings, target, &today, now)
+        {
+            if let Err(err) = notification::notify(&summary, &body) {
+                self.show_toast(format!("{}: {} ({})", summary, body, err));
+            }
+        }
+    }
+
+    /// Clears the Slack status when quitting, unless it is known to be clear already
+    async fn clear_slack_status(&mut self) {
+        let Some(config) = &self.slack else {
+            return;
+        };
+        // An update may still be on its way, only a cleared status is certain
+        if self.slack_status != Some(slack::Status::default()) {
+            // The app is going away, there is nobody to tell about a failure
+            let _ = slack::set_status(config, &slack::Status::default()).await;
+        }
+    }
+
+    /// Ends the running span when the idleness began
+    fn stop_at_idle(&mut self) {
+        if let Some(since) = self.idle_since.take() {
+            let checkpoint = Checkpoint {
+                kind: CheckpointKind::Stop,
+                time: since,
+                ..Checkpoint::new()
+            };
+            self.add_checkpoints(vec![checkpoint], false);
+        }
+    }
+
+    /// Parses the input as a time on the selected date, flagging the input as invalid otherwise
+    fn input_time(&mut self) -> Option<Time> {
+        let time = parse_hh_mm(self.input.value()).and_then(|time| {
+            self.settings
+                .display_time(self.selected_date().and_time(time))
+        });
+        if time.is_none() {
+            self.input_invalid = true;
+        }
+        time
+    }
+
+    /// Insert new checkpoint at the time typed into the input on the selected date
+    async fn insert_checkpoint_at(&mut self) {
+        let Some(time) = self.input_time() else {
+            return;
+        };
+
+        self.input.reset();
+        self.stop_editing();
+
+        let mut checkpoint = Checkpoint::new();
+        checkpoint.time = time;
+        self.add_checkpoints(vec![checkpoint], true);
+    }
+
+    /// Set the time of the selected checkpoint to the time typed into the input
+    async fn retime_checkpoint(&mut self) {
+        let Some(time) = self.input_time() else {
+            return;
+        };
+
+        self.input.reset();
+        self.stop_editing();
+
+        let rollback = self.week.clone();
+        let Some(mut checkpoint) = self.week.remove_selected_checkpoint() else {
+            return;
+        };
+        checkpoint.time = time;
+
+        self.week.insert_checkpoint(checkpoint.clone());
+        self.persist(rollback, vec![Write::Update(vec![checkpoint])]);
+    }
+
+    /// Move the selected checkpoint to the date typed into the input, keeping its clock time
+    /// unless a new one follows the date
+    fn move_checkpoint(&mut self) {
+        let Some(selected) = self.week.selected_checkpoint() else {
+            return;
+        };
+        let time = parse_date_time(self.input.value()).and_then(|(date, time)| {
+            let time = time.unwrap_or(selected.time.time());
+            self.settings.display_time(date.and_time(time))
+        });
+        let Some(time) = time else {
+            self.input_invalid = true;
+            return;
+        };
+        if self.refuse_locked(time.date_naive()) {
+            return;
+        }
+
+        self.input.reset();
+        self.stop_editing();
+
+        let rollback = self.week.clone();
+        let Some(mut checkpoint) = self.week.remove_selected_checkpoint() else {
+            return;
+        };
+        checkpoint.time = time;
+
+        // Follow the checkpoint when it stays in the displayed week
+        let date = time.date_naive();
+        let days_from_monday = (date - self.mondays[self.selected_mon_idx]).num_days();
+        if (0..7).contains(&days_from_monday) {
+            self.week.selected_weekday = date.weekday();
+            self.week.insert_checkpoint(checkpoint.clone());
+        }
+        self.persist(rollback, vec![Write::Update(vec![checkpoint])]);
+    }
+
+    /// Split the selected span at the time typed into the input
+    async fn split_checkpoint(&mut self) {
+        let Some((start_time, end_time)) = self.selected_span() else {
+            return;
+        };
+        let Some(split_time) = self.input_time() else {
+            return;
+        };
+        if split_time <= start_time || split_time >= end_time {
+            self.input_invalid = true;
+            return;
+        }
+
+        self.input.reset();
+        self.stop_editing();
+
+        let mut new_checkpoint = Checkpoint::new();
+        new_checkpoint.time = split_time;
+        new_checkpoint.project = self
+            .week
+            .selected_checkpoint()
+            .and_then(|ch| ch.project.clone());
+        self.add_checkpoints(vec![new_checkpoint], true);
+    }
+
+    /// Start and end time of the selected span
+    fn selected_span(&self) -> Option<(Time, Time)> {
+        let selected = self.week.selected_checkpoint()?;
+        let next = self.week.next_checkpoint()?;
+        Some((selected.time, next.time))
+    }
+
+    async fn submit_input(&mut self) {
+        match self.input_mode {
+            InputMode::InsertTime => self.insert_checkpoint_at().await,
+            InputMode::EditTime => self.retime_checkpoint().await,
+            InputMode::SplitTime => self.split_checkpoint().await,
+            InputMode::MoveDate => self.move_checkpoint(),
+            InputMode::JumpDate => self.jump_to_input_date(),
+            InputMode::Search => self.search(),
+            InputMode::TagFilter => self.filter_by_tag(),
+            InputMode::Normal | InputMode::Editing => {}
+        }
+    }
+
+    /// Ask for confirmation before running the action
+    fn confirm(&mut self, action: ConfirmAction) {
+        let applicable = match action {
+            ConfirmAction::DeleteCheckpoint => self.week.selected_checkpoint().is_some(),
+            ConfirmAction::DeleteSelection => self.visual_range().is_some(),
+            ConfirmAction::CopyPreviousDay => true,
+            ConfirmAction::MergeSpans => {
+                self.week.active_day().len() > self.week.selected_checkpoint_idx + 2
+            }
+            ConfirmAction::CopyDaySummary => self.week.active_day().len() > 1,
+            ConfirmAction::UnlockDay => true,
+            ConfirmAction::RegisterDay => self.week.active_day().iter().any(|ch| !ch.registered),
+            ConfirmAction::SetDayType => true,
+            ConfirmAction::FixOverlaps => count_overlaps(self.week.active_day()) > 0,
+            ConfirmAction::StopAtIdle => self.idle_since.is_some(),
+            ConfirmAction::MergeDuplicate => true,
+        };
+        if applicable {
+            self.pending_confirm = Some(action);
+        }
+    }
+
+    /// Run the action once the user picked one of its choices
+    async fn run_confirmed(&mut self, action: ConfirmAction, choice: &str) {
+        match action {
+            ConfirmAction::DeleteCheckpoint => self.delete_checkpoint().await,
+            ConfirmAction::DeleteSelection => self.delete_selection().await,
+            ConfirmAction::CopyPreviousDay => self.copy_previous_day(),
+            ConfirmAction::MergeSpans => self.merge_spans(choice == "2").await,
+            ConfirmAction::UnlockDay => self.set_day_locked(self.selected_date(), false),
+            ConfirmAction::RegisterDay => self.register_day(),
+            ConfirmAction::SetDayType => self.set_day_type(match choice {
+                "v" => Some(DayType::Vacation),
+                "s" => Some(DayType::Sick),
+                "h" => Some(DayType::Holiday),
+                _ => None,
+            }),
+            ConfirmAction::StopAtIdle => self.stop_at_idle(),
+            ConfirmAction::MergeDuplicate => self.merge_day_duplicates(),
+            ConfirmAction::FixOverlaps => self.fix_day_overlaps(if choice == "c" {
+                OverlapFix::Clamp
+            } else {
+                OverlapFix::Swap
+            }),
+            ConfirmAction::CopyDaySummary => self.copy_day_summary(if choice == "m" {
+                SummaryFormat::Markdown
+            } else {
+                SummaryFormat::Text
+            }),
+        }
+    }
+
+    /// Opens the notes of the selected day once they are loaded
+    fn open_note(&mut self) {
+        let db = self.db.clone();
+        let date = self.selected_date();
+        self.spawn(async move {
+            match find_note(&db, &date).await {
+                Ok(text) => Outcome::NoteLoaded { date, text },
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Stores the edited notes in the background
+    fn save_note(&mut self) {
+        let db = self.db.clone();
+        let date = self.note_date;
+        let text = self.note_editor.text();
+        self.spawn(async move {
+            match save_note(&db, &date, text).await {
+                Ok(()) => Outcome::Saved,
+                Err(err) => Outcome::Failed(format!("Notes were not saved: {}", err)),
+            }
+        });
+    }
+
+    /// Puts the summary of the selected day on the system clipboard
+    fn copy_day_summary(&mut self, format: SummaryFormat) {
+        let summary = day_summary(
+            self.selected_date(),
+            self.week.active_day(),
+            format,
+            &self.settings,
+        );
+        // The clipboard is kept open, on some platforms its content goes away with it
+        if self.clipboard.is_none() {
+            match Clipboard::new() {
+                Ok(clipboard) => self.clipboard = Some(clipboard),
+                Err(err) => return self.show_toast(format!("Clipboard unavailable: {}", err)),
+            }
+        }
+        if let Some(Err(err)) = self.clipboard.as_mut().map(|c| c.set_text(summary)) {
+            self.show_toast(format!("Failed to copy: {}", err));
+        }
+    }
+
+    /// Merge the selected span with the next one by deleting the checkpoint between them
+    async fn merge_spans(&mut self, keep_next: bool) {
+        let rollback = self.week.clone();
+        let Some(boundary) = self.week.merge_with_next(keep_next) else {
+            return;
+        };
+
+        let mut writes = vec![Write::Delete(vec![boundary])];
+        if let Some(selected) = self.week.selected_checkpoint() {
+            writes.push(Write::Update(vec![selected.clone()]));
+        }
+        self.persist(rollback, writes);
+    }
+
+    async fn delete_checkpoint(&mut self) {
+        let rollback = self.week.clone();
+        if let Some(selected) = self.week.remove_selected_checkpoint() {
+            self.week.select_max_checkpoint_idx();
+            self.persist(rollback, vec![Write::Delete(vec![selected])]);
+        }
+    }
+
+    /// Checkpoints of the date in the displayed week, as shown before they reach the store
+    fn shown_day(&self, date: NaiveDate) -> Option<&Vec<Checkpoint>> {
+        let first = self.mondays.get(self.selected_mon_idx)?;
+        if !(0..7).contains(&(date - *first).num_days()) {
+            return None;
+        }
+        self.week
+            .days()
+            .into_iter()
+            .find(|(weekday, _)| *weekday == date.weekday())
+            .map(|(_, day)| day)
+    }
+
+    async fn lenghten_r(&mut self) {
+        let rollback = self.week.clone();
         if let Some(selected) = self.week.selected_checkpoint_mut() {
-            if let Some(t) = selected.time.checked_add_signed(TimeDelta::minutes(-15)) {
+            if let Some(t) = selected
+                .time
+                .checked_add_signed(TimeDelta::minutes(self.settings.rounding.unit as i64))
+            {
                 selected.time = t;
+                let selected = selected.clone();
+                self.persist(rollback, vec![Write::Update(vec![selected])]);
+                self.warn_overlaps(count_overlaps(self.week.active_day()));
+            }
+        }
+    }
 
-                if let Err(err) = update_checkpoint(&self.db, selected).await {
-                    eprintln!("{}", err);
-                }
+    async fn lenghten_ctrl_r(&mut self) {
+        let rollback = self.week.clone();
+        if let Some(next) = self.week.next_checkpoint_mut() {
+            if let Some(t) = next
+                .time
+                .checked_add_signed(TimeDelta::minutes(self.settings.rounding.unit as i64))
+            {
+                next.time = t;
+                let next = next.clone();
+                self.persist(rollback, vec![Write::Update(vec![next])]);
+                self.warn_overlaps(count_overlaps(self.week.active_day()));
+            }
+        }
+    }
+
+    async fn lenghten_l(&mut self) {
+        let rollback = self.week.clone();
+        if let Some(selected) = self.week.selected_checkpoint_mut() {
+            if let Some(t) = selected
+                .time
+                .checked_add_signed(TimeDelta::minutes(-(self.settings.rounding.unit as i64)))
+            {
+                selected.time = t;
+                let selected = selected.clone();
+                self.persist(rollback, vec![Write::Update(vec![selected])]);
+                self.warn_overlaps(count_overlaps(self.week.active_day()));
             }
         }
     }
 
     async fn lenghten_ctrl_l(&mut self) {
+        let rollback = self.week.clone();
         if let Some(next) = self.week.next_checkpoint_mut() {
-            if let Some(t) = next.time.checked_add_signed(TimeDelta::minutes(-15)) {
+            if let Some(t) = next
+                .time
+                .checked_add_signed(TimeDelta::minutes(-(self.settings.rounding.unit as i64)))
+            {
                 next.time = t;
-
-                if let Err(err) = update_checkpoint(&self.db, next).await {
-                    eprintln!("{}", err);
-                }
+                let next = next.clone();
+                self.persist(rollback, vec![Write::Update(vec![next])]);
+                self.warn_overlaps(count_overlaps(self.week.active_day()));
             }
         }
     }
@@ -673,13 +2122,167 @@ impl App {
         self.week.select_next_day();
     }
 
-    async fn cycle_weeks(&mut self) {
-        self.selected_mon_idx = if self.mondays.len() > self.selected_mon_idx + 1 {
-            self.selected_mon_idx + 1
+    /// Moves to the ISO week `weeks` away from the selected one, keeping the selected weekday
+    fn shift_week(&mut self, weeks: i64) {
+        let monday = self.mondays[self.selected_mon_idx];
+        let Some(date) = monday.checked_add_signed(TimeDelta::weeks(weeks)) else {
+            return;
+        };
+        let weekday = self.week.selected_weekday;
+        self.go_to_date(date, Selection::Day(weekday));
+    }
+
+    fn select_week(&mut self, idx: usize) {
+        self.selected_mon_idx = idx;
+        self.load_week(Selection::Start);
+    }
+
+    /// Select the given date, adding its week to the loaded weeks when missing
+    fn go_to_date(&mut self, date: NaiveDate, selection: Selection) {
+        let monday = self.settings.locale.week_start(date);
+        let idx = match self.mondays.binary_search(&monday) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                self.mondays.insert(idx, monday);
+                self.load_minimap();
+                idx
+            }
+        };
+        self.selected_mon_idx = idx;
+        self.load_week(selection);
+    }
+
+    fn jump_to_input_date(&mut self) {
+        // Weekends are only part of the week view when they are shown
+        let Some(date) = parse_date(self.input.value()).filter(|date| {
+            self.layout.show_weekend || !matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+        }) else {
+            self.input_invalid = true;
+            return;
+        };
+
+        self.input.reset();
+        self.stop_editing();
+        self.go_to_date(date, Selection::Day(date.weekday()));
+    }
+
+    fn jump_to_today(&mut self) {
+        let today = self.settings.now().date_naive();
+        if !self.layout.show_weekend && matches!(today.weekday(), Weekday::Sat | Weekday::Sun) {
+            let friday = today - Days::new(today.weekday().num_days_from_monday() as u64 - 4);
+            self.go_to_date(friday, Selection::Day(Weekday::Fri));
         } else {
-            0
+            self.go_to_date(today, Selection::Day(today.weekday()));
+        }
+    }
+
+    /// Loads the stats of the selected week and month and shows them instead of the timeline
+    fn open_stats(&mut self) {
+        self.load_flex_days();
+        let (db, settings) = (self.db.clone(), self.settings);
+        let monday = self.mondays[self.selected_mon_idx];
+        let date = self.selected_date();
+        self.spawn(async move {
+            let timezone = settings.timezone;
+            let week =
+                find_checkpoints_between(&db, &monday, &(monday + Days::new(6)), timezone).await;
+
+            let first = date.with_day(1).unwrap();
+            let last = first + Months::new(1) - Days::new(1);
+            let month = find_checkpoints_between(&db, &first, &last, timezone).await;
+
+            let today = settings.now().date_naive();
+            let heatmap_start = today - Days::new(7 * HEATMAP_WEEKS as u64);
+            let heatmap = find_checkpoints_between(&db, &heatmap_start, &today, timezone).await;
+
+            match (week, month, heatmap) {
+                (Ok(week), Ok(month), Ok(heatmap)) => Outcome::StatsLoaded {
+                    week,
+                    month,
+                    heatmap,
+                },
+                (Err(err), _, _) | (_, Err(err), _) | (_, _, Err(err)) => {
+                    Outcome::Failed(err.to_string())
+                }
+            }
+        });
+    }
+
+    /// Renders a table with time spent on each project during the selected day and week
+    fn render_project_totals(&self, frame: &mut Frame, area: Rect) {
+        let day_totals = activity_units(self.week.active_day(), &self.settings.rounding);
+        let mut week_totals: BTreeMap<String, u16> = BTreeMap::new();
+        for (_, checkpoints) in self.week.days() {
+            for (project, units) in activity_units(checkpoints, &self.settings.rounding) {
+                *week_totals.entry(project).or_insert(0) += units;
+            }
+        }
+
+        let human = |units: u16| {
+            self.settings
+                .duration(units as u32 * self.settings.rounding.unit_minutes())
         };
-        self.load_week().await;
+        let mut rows: Vec<Row> = week_totals
+            .iter()
+            .map(|(project, week_units)| {
+                let day_units = day_totals.get(project).copied().unwrap_or(0);
+                Row::new(vec![project.clone(), human(day_units), human(*week_units)])
+            })
+            .collect();
+        rows.push(
+            Row::new(vec![
+                "Total".to_string(),
+                human(day_totals.values().sum()),
+                human(week_totals.values().sum()),
+            ])
+            .bold(),
+        );
+
+        let day_billable = billable_units(self.week.active_day(), &self.settings.rounding);
+        let week_billable = self
+            .week
+            .days()
+            .iter()
+            .map(|(_, checkpoints)| billable_units(checkpoints, &self.settings.rounding))
+            .fold((0, 0), |acc, units| (acc.0 + units.0, acc.1 + units.1));
+        if week_billable.1 > 0 {
+            rows.push(
+                Row::new(vec![
+                    "Billable".to_string(),
+                    human(day_billable.0),
+                    human(week_billable.0),
+                ])
+                .fg(self.theme.help),
+            );
+            rows.push(
+                Row::new(vec![
+                    "Non-billable".to_string(),
+                    human(day_billable.1),
+                    human(week_billable.1),
+                ])
+                .fg(self.theme.help),
+            );
+        }
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Fill(1),
+                Constraint::Length(8),
+                Constraint::Length(8),
+            ],
+        )
+        .header(Row::new(vec!["Project", "Day", "Week"]).fg(self.theme.help))
+        .block(self.theme.block().title("Totals"));
+        frame.render_widget(table, area);
+    }
+
+    fn zoom_in(&mut self) {
+        self.zoom_level = (self.zoom_level + 1).min(Zoom::LEVELS.len() - 1);
+    }
+
+    fn zoom_out(&mut self) {
+        self.zoom_level = self.zoom_level.saturating_sub(1);
     }
 
     fn render_input(&self, frame: &mut Frame, area: Rect) {
@@ -688,15 +2291,32 @@ impl App {
         let scroll = self.input.visual_scroll(width as usize);
         let style = match self.input_mode {
             InputMode::Normal => Style::default().gray(),
-            InputMode::Editing => Color::Yellow.into(),
+            _ => self.theme.accent.into(),
+        };
+        let title = match self.input_mode {
+            InputMode::Normal => "Input",
+            InputMode::Editing if self.message_history.is_empty() => "Input",
+            InputMode::Editing => "Input (<tab> completes)",
+            InputMode::InsertTime => "Insert at (HH:MM)",
+            InputMode::EditTime => "Edit time (HH:MM)",
+            InputMode::SplitTime => "Split at (HH:MM)",
+            InputMode::MoveDate => "Move to date, optionally with a new time (DD.MM.YYYY HH:MM)",
+            InputMode::JumpDate => "Go to date (YYYY-MM-DD or DD.MM.YYYY)",
+            InputMode::Search => "Search project or comment",
+            InputMode::TagFilter => "Filter by tag (empty clears the filters)",
+        };
+        let title = if self.input_invalid {
+            Line::from(format!("{} - invalid", title)).fg(self.theme.bad)
+        } else {
+            Line::from(title)
         };
         let input = Paragraph::new(self.input.value())
             .style(style)
             .scroll((0, scroll as u16))
-            .block(Block::bordered().title("Input"));
+            .block(self.theme.block().title(title));
         frame.render_widget(input, area);
 
-        if self.input_mode == InputMode::Editing {
+        if self.input_mode != InputMode::Normal {
             // Ratatui hides the cursor unless it's explicitly set. Position the  cursor past the
             // end of the input text and one line down from the border to the input line
             let x = self.input.visual_cursor().max(scroll) - scroll + 1;
@@ -704,32 +2324,570 @@ impl App {
         }
     }
 
-    fn start_editing(&mut self) {
+    async fn start_editing(&mut self) {
+        // Continue from the current message as it's edited in place
+        let message = self
+            .week
+            .selected_checkpoint()
+            .and_then(|ch| ch.message.clone())
+            .unwrap_or_default();
+        self.edit_message(message);
+    }
+
+    /// Opens the input on the message, offering the messages used with the selected
+    /// checkpoint's project
+    fn edit_message(&mut self, message: String) {
+        self.message_history = vec![];
+        self.completion = None;
+        if let Some(project) = self
+            .week
+            .selected_checkpoint()
+            .and_then(|ch| ch.project.clone())
+        {
+            let db = self.db.clone();
+            self.spawn(async move {
+                match find_project_messages(&db, &project).await {
+                    Ok(messages) => Outcome::MessagesLoaded(messages),
+                    Err(err) => Outcome::Failed(err.to_string()),
+                }
+            });
+        }
+        self.input = Input::new(message);
         self.input_mode = InputMode::Editing
     }
 
     fn stop_editing(&mut self) {
-        self.input_mode = InputMode::Normal
+        self.input_mode = InputMode::Normal;
+        self.input_invalid = false;
+        self.completion = None;
+    }
+
+    /// Replace the input with the next (or previous) message from history starting with the
+    /// text typed before completing
+    fn complete_message(&mut self, forward: bool) {
+        let (prefix, idx) = match self.completion.take() {
+            Some((prefix, idx)) => (prefix, Some(idx)),
+            None => (self.input.value().to_string(), None),
+        };
+        let candidates = completions(&self.message_history, &prefix);
+        if candidates.is_empty() {
+            return;
+        }
+        let idx = match (idx, forward) {
+            (None, true) => 0,
+            (None, false) => candidates.len() - 1,
+            (Some(idx), true) => (idx + 1) % candidates.len(),
+            (Some(idx), false) => (idx + candidates.len() - 1) % candidates.len(),
+        };
+        self.input = Input::new(candidates[idx].to_string());
+        self.completion = Some((prefix, idx));
+    }
+
+    fn start_search(&mut self) {
+        self.input.reset();
+        self.input_mode = InputMode::Search
+    }
+
+    fn start_tag_filter(&mut self) {
+        self.input.reset();
+        self.input_mode = InputMode::TagFilter
+    }
+
+    fn filter_by_tag(&mut self) {
+        let tag = self.input.value_and_reset();
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        self.tag_filter = (!tag.is_empty()).then_some(tag);
+        if self.tag_filter.is_none() {
+            self.project_filter = None;
+        }
+        self.stop_editing();
+    }
+
+    fn search(&mut self) {
+        let query = self.input.value().trim().to_string();
+        if query.is_empty() {
+            self.input_invalid = true;
+            return;
+        }
+
+        self.input.reset();
+        self.stop_editing();
+
+        let (db, timezone) = (self.db.clone(), self.settings.timezone);
+        self.spawn(async move {
+            match search_checkpoints(&db, &query, timezone).await {
+                Ok(results) => Outcome::SearchFinished(results),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Select the date and checkpoint of the highlighted search result
+    fn jump_to_search_result(&mut self) {
+        let Some(result) = self
+            .search_popup_state
+            .selected()
+            .and_then(|idx| self.search_results.get(idx))
+            .cloned()
+        else {
+            return;
+        };
+        self.jump_to_checkpoint(&result);
+    }
+
+    /// Select the date and the checkpoint itself
+    fn jump_to_checkpoint(&mut self, result: &Checkpoint) {
+        let date = result.time.date_naive();
+        // Weekends are only part of the week view when they are shown
+        if !self.layout.show_weekend && matches!(date.weekday(), Weekday::Sat | Weekday::Sun) {
+            return;
+        }
+        self.go_to_date(
+            date,
+            Selection::Checkpoint {
+                weekday: date.weekday(),
+                id: result.id.clone(),
+            },
+        );
+    }
+
+    fn start_jump_date(&mut self) {
+        self.input = Input::new(self.selected_date().format("%Y-%m-%d").to_string());
+        self.input_mode = InputMode::JumpDate
+    }
+
+    fn start_insert_time(&mut self) {
+        self.input.reset();
+        self.input_mode = InputMode::InsertTime
+    }
+
+    fn start_split_time(&mut self) {
+        if let Some((start_time, end_time)) = self.selected_span() {
+            // Check if duration is positive
+            if end_time - start_time <= TimeDelta::zero() {
+                return;
+            }
+            let mid_time = start_time + (end_time - start_time) / 2;
+            self.input = Input::new(mid_time.format("%H:%M").to_string());
+            self.input_mode = InputMode::SplitTime
+        }
+    }
+
+    /// Asks for the date to move the selected checkpoint to, offering the previous day
+    fn start_move_date(&mut self) {
+        if let Some(selected) = self.week.selected_checkpoint() {
+            let previous_day = selected.time - Days::new(1);
+            self.input = Input::new(previous_day.format("%d.%m.%Y").to_string());
+            self.input_mode = InputMode::MoveDate
+        }
+    }
+
+    fn start_edit_time(&mut self) {
+        if let Some(selected) = self.week.selected_checkpoint() {
+            self.input = Input::new(selected.time.format("%H:%M").to_string());
+            self.input_mode = InputMode::EditTime
+        }
+    }
+
+    /// Date of the day currently selected in the week view
+    fn selected_date(&self) -> NaiveDate {
+        self.settings.locale.date_in_week(
+            self.mondays[self.selected_mon_idx],
+            self.week.selected_weekday,
+        )
+    }
+
+    /// Applies the change to the layout and persists it in the config file
+    fn toggle_weekend(&mut self) {
+        self.change_layout(|l| l.show_weekend = !l.show_weekend);
+        self.week.show_weekend = self.layout.show_weekend;
+        // The minimap was loaded without the weekends
+        if self.week.show_weekend {
+            self.load_minimap();
+        } else if matches!(self.week.selected_weekday, Weekday::Sat | Weekday::Sun) {
+            self.week.selected_weekday = Weekday::Fri;
+            self.week.select_max_checkpoint_idx();
+        }
+    }
+
+    fn change_layout(&mut self, change: impl FnOnce(&mut LayoutConfig)) {
+        change(&mut self.layout);
+        if let Err(err) = save_layout(&self.config_path, &self.layout) {
+            self.show_toast(format!("The layout was not saved: {}", err));
+        }
+        self.layout_saved = modified(&self.config_path);
+    }
+
+    /// Starts watching the config files next to `config.toml`
+    fn watch_config(&mut self) {
+        let Some(dir) = self.config_path.parent() else {
+            return;
+        };
+        match ConfigWatcher::new(dir, self.profile.clone()) {
+            Ok(watcher) => self.config_watcher = Some(watcher),
+            Err(err) => self.show_toast(format!("Config changes won't be reloaded: {}", err)),
+        }
+    }
+
+    /// Applies the changes of the watched files, the layout stays as it is in this session
+    fn reload_changed_files(&mut self) {
+        let Some(watcher) = &self.config_watcher else {
+            return;
+        };
+        for file in watcher.changed() {
+            let name = file.name(self.profile.as_deref());
+            let reloaded = match file {
+                ConfigFile::Config if modified(&self.config_path) == self.layout_saved => continue,
+                ConfigFile::Config => Config::read(&self.config_path, self.profile.as_deref())
+                    .map(|config| self.apply_config(config))
+                    .map_err(|err| err.to_string()),
+                ConfigFile::Projects => {
+                    projects::load_projects(self.config_path.with_file_name(&name))
+                        .map(|projects| self.projects = projects)
+                        .map_err(|err| err.to_string())
+                }
+            };
+            match reloaded {
+                Ok(()) => self.show_toast(format!("Reloaded {}", name)),
+                Err(err) => self.show_toast(format!("{} not reloaded: {}", name, err)),
+            }
+        }
+    }
+
+    /// Takes over the settings of a reloaded config that can change while the week is open
+    fn apply_config(&mut self, config: Config) {
+        self.settings.rounding = config.rounding;
+        self.settings.locale = config.locale;
+        self.week.first_day_of_week = config.locale.first_day_of_week;
+        self.theme = config.theme.theme();
+        self.message_templates = config.messages.templates;
+        self.git = config.git;
+        self.calendar = config.calendar;
+        self.goals = config.goals;
+        self.lunch_break = config.lunch_break;
+        self.idle_minutes = config.idle_minutes;
+        self.slack = config.slack;
+        self.webhooks = config.webhooks;
+        self.remind = config.remind;
+        self.task_url_prefix = config.task_url_prefix;
+        self.currency = config.currency;
+        // The keyring is asked only when the tasks are fetched, unless the login stays the same
+        if config.auth.password.is_empty() && config.auth.username == self.auth_config.username {
+            self.auth_config.login_url = config.auth.login_url;
+        } else {
+            self.auth_config = config.auth;
+        }
+    }
+
+    fn open_template_popup(&mut self) {
+        if self.message_templates.is_empty() {
+            return;
+        }
+        self.open(Screen::Templates);
+        self.template_popup_state.select(Some(0));
+    }
+
+    /// Set the message of the selected checkpoint, or of the visual selection, to a template
+    async fn apply_template(&mut self, idx: usize) {
+        let Some(template) = self.message_templates.get(idx).cloned() else {
+            return;
+        };
+        self.set_selected_messages(template);
+    }
+
+    /// Looks up the commits made during the selected span in the background, they are offered
+    /// in a popup once found
+    fn find_commits(&mut self) {
+        if self.git.repositories.is_empty() {
+            self.show_toast("No repositories in [git] of config.toml".to_string());
+            return;
+        }
+        let Some((start, end)) = self.selected_span() else {
+            return;
+        };
+        let home_dir = self
+            .config_path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        let repositories: Vec<PathBuf> = self
+            .git
+            .repositories
+            .iter()
+            .map(|repository| home_dir.join(repository))
+            .collect();
+        let author = self.git.author.clone();
+        self.spawn(async move {
+            let found = tokio::task::spawn_blocking(move || {
+                git::commits(&repositories, author.as_deref(), start, end)
+            })
+            .await;
+            match found {
+                Ok(Ok(commits)) => Outcome::CommitsFound(commits),
+                Ok(Err(err)) => Outcome::Failed(format!("Failed to read the commits: {}", err)),
+                Err(err) => Outcome::Failed(format!("Failed to read the commits: {}", err)),
+            }
+        });
+    }
+
+    /// Fetches the meetings of the selected day in the background, they are offered in a popup
+    /// once fetched
+    fn fetch_meetings(&mut self) {
+        let Some(config) = self.calendar.clone() else {
+            self.show_toast("No [calendar] in config.toml".to_string());
+            return;
+        };
+        let date = self.selected_date();
+        let (timezone, profile) = (self.settings.timezone, self.profile.clone());
+        self.spawn(async move {
+            match calendar::meetings(&config, date, timezone, profile.as_deref()).await {
+                Ok(meetings) => Outcome::MeetingsFetched(meetings),
+                Err(err) => Outcome::Failed(format!("Failed to fetch the meetings: {}", err)),
+            }
+        });
+    }
+
+    /// The checkpoints adding the meeting to its day, `None` when it is tracked already
+    fn meeting_checkpoints(&self, meeting: &Meeting) -> Option<Vec<Checkpoint>> {
+        let project = self.calendar.as_ref()?.project.as_deref();
+        let date = meeting.start.date_naive();
+        let (_, day) = self.week.days().into_iter().find(|(weekday, _)| {
+            self.settings
+                .locale
+                .date_in_week(self.mondays[self.selected_mon_idx], *weekday)
+                == date
+        })?;
+        meeting_checkpoints(meeting, project, day)
+    }
+
+    /// Adds the meetings as spans of the project of the calendar, those tracked already are
+    /// skipped
+    fn import_meetings(&mut self, meetings: Vec<Meeting>) {
+        let (mut added, mut skipped) = (0, 0);
+        for meeting in meetings {
+            match self.meeting_checkpoints(&meeting) {
+                Some(checkpoints) if !self.refuse_locked(meeting.start.date_naive()) => {
+                    self.add_checkpoints(checkpoints, true);
+                    added += 1;
+                }
+                _ => skipped += 1,
+            }
+        }
+        match skipped {
+            0 => self.show_toast(format!("Added {} meetings", added)),
+            _ => self.show_toast(format!(
+                "Added {} meetings, {} tracked already",
+                added, skipped
+            )),
+        }
+    }
+
+    /// Set the message of the selected checkpoint, or of the visual selection, to the subject of
+    /// a commit
+    fn apply_commit(&mut self, idx: usize) {
+        let Some(commit) = self.commits.get(idx) else {
+            return;
+        };
+        self.set_selected_messages(commit.subject.clone());
+    }
+
+    /// Set the message of the selected checkpoint, or of all checkpoints in the visual selection
+    fn set_selected_messages(&mut self, message: String) {
+        if let Some(range) = self.visual_range() {
+            self.visual_anchor = None;
+            let rollback = self.week.clone();
+            let selection = &mut self.week.active_day_mut()[range];
+            for checkpoint in selection.iter_mut() {
+                checkpoint.set_message(message.clone());
+            }
+            let selection = selection.to_vec();
+            self.persist(rollback, vec![Write::Update(selection)]);
+            return;
+        }
+
+        self.update_selected(|selected| selected.set_message(message));
     }
 
     async fn push_message(&mut self) {
+        let message = self.input.value_and_reset();
+        self.update_selected(|selected| selected.set_message(message));
+    }
+
+    /// Applies the change to the selected checkpoint and stores it
+    fn update_selected(&mut self, change: impl FnOnce(&mut Checkpoint)) {
+        let rollback = self.week.clone();
         if let Some(selected) = self.week.selected_checkpoint_mut() {
-            selected.message = Some(self.input.value_and_reset());
+            change(selected);
+            let selected = selected.clone();
+            self.persist(rollback, vec![Write::Update(vec![selected])]);
+        }
+    }
+
+    /// Copy the checkpoints of the closest previous day with any within a week to the selected
+    /// date, read in the background
+    fn copy_previous_day(&mut self) {
+        let (db, timezone) = (self.db.clone(), self.settings.timezone);
+        let date = self.selected_date();
+        self.spawn(async move {
+            let (first, last) = (date - Days::new(7), date - Days::new(1));
+            match find_checkpoints_between(&db, &first, &last, timezone).await {
+                Ok(checkpoints) => {
+                    let previous = checkpoints
+                        .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+                        .last()
+                        .unwrap_or_default();
+                    Outcome::PreviousDayCopied(copy_to_date(previous, date))
+                }
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Switch the selected span between work, break and absence
+    async fn cycle_kind(&mut self) {
+        self.update_selected(|selected| selected.kind = selected.kind.next());
+    }
 
-            if let Err(err) = update_checkpoint(&self.db, selected).await {
-                eprintln!("{}", err);
+    /// Lists the unregistered spans of the whole history
+    fn open_review(&mut self) {
+        let (db, settings) = (self.db.clone(), self.settings);
+        self.spawn(async move {
+            match find_all_checkpoints(&db, settings.timezone).await {
+                Ok(checkpoints) => {
+                    Outcome::ReviewLoaded(unregistered_spans(&checkpoints, &settings.rounding))
+                }
+                Err(err) => Outcome::Failed(err.to_string()),
             }
+        });
+    }
+
+    fn jump_to_review_item(&mut self) {
+        let Some((checkpoint, _)) = self
+            .review_state
+            .selected()
+            .and_then(|idx| self.review_items.get(idx))
+            .cloned()
+        else {
+            return;
         };
+        self.jump_to_checkpoint(&checkpoint);
+    }
+
+    /// Mark the highlighted span registered and drop it from the review list
+    async fn register_review_item(&mut self) {
+        let Some(idx) = self
+            .review_state
+            .selected()
+            .filter(|idx| *idx < self.review_items.len())
+        else {
+            return;
+        };
+        if self.refuse_locked(self.review_items[idx].0.time.date_naive()) {
+            return;
+        }
+        let (mut checkpoint, _) = self.review_items.remove(idx);
+        checkpoint.registered = true;
+
+        let rollback = self.week.clone();
+        if let Some(shown) = self
+            .week
+            .days_mut()
+            .into_iter()
+            .flat_map(|day| day.iter_mut())
+            .find(|ch| ch.id == checkpoint.id)
+        {
+            shown.registered = true;
+        }
+        self.persist(rollback, vec![Write::Update(vec![checkpoint])]);
     }
 
     async fn mark_registered(&mut self) {
-        if let Some(selected) = self.week.selected_checkpoint_mut() {
-            selected.registered = !selected.registered;
+        self.update_selected(|selected| selected.registered = !selected.registered);
+    }
 
-            if let Err(err) = update_checkpoint(&self.db, selected).await {
-                eprintln!("{}", err);
-            }
+    /// Shows a toast and returns true when the day is locked
+    fn refuse_locked(&mut self, date: NaiveDate) -> bool {
+        if !self.locked_days.contains(&date) {
+            return false;
+        }
+        self.show_toast(format!(
+            "{} {} is locked, unlock it to make changes",
+            date.format("%a"),
+            self.settings.locale.day(date)
+        ));
+        true
+    }
+
+    /// Locks the selected day right away, unlocking has to be confirmed
+    fn toggle_lock(&mut self) {
+        let date = self.selected_date();
+        if self.locked_days.contains(&date) {
+            self.confirm(ConfirmAction::UnlockDay);
+        } else {
+            self.set_day_locked(date, true);
+        }
+    }
+
+    /// First day the flex balance is counted from
+    fn flex_since(&self) -> NaiveDate {
+        self.goals.flex_since.unwrap_or_else(|| {
+            let today = self.settings.now().date_naive();
+            NaiveDate::from_ymd_opt(today.year(), 1, 1).unwrap_or(today)
+        })
+    }
+
+    /// Tracked minus expected minutes up to yesterday, today counts once it is over
+    fn flex_balance(&self) -> Option<i64> {
+        let expected_hours = self.goals.expected_hours_per_day?;
+        Some(flex_balance(
+            &self.flex_days,
+            self.flex_since(),
+            self.settings.now().date_naive(),
+            (expected_hours * 60.0).round() as u32,
+            |date| self.day_off(date).is_some(),
+        ))
+    }
+
+    /// Why the date has no hours to track, public holidays count as marked holidays
+    fn day_off(&self, date: NaiveDate) -> Option<DayType> {
+        self.day_types.get(&date).copied().or(self
+            .holidays
+            .contains_key(&date)
+            .then_some(DayType::Holiday))
+    }
+
+    fn set_day_type(&mut self, kind: Option<DayType>) {
+        let date = self.selected_date();
+        match kind {
+            Some(kind) => self.day_types.insert(date, kind),
+            None => self.day_types.remove(&date),
         };
+        let db = self.db.clone();
+        self.spawn(async move {
+            match set_day_type(&db, &date, kind).await {
+                Ok(()) => Outcome::Saved,
+                Err(err) => Outcome::Failed(format!("Day type was not saved: {}", err)),
+            }
+        });
+    }
+
+    fn set_day_locked(&mut self, date: NaiveDate, locked: bool) {
+        if locked {
+            self.locked_days.insert(date);
+        } else {
+            self.locked_days.remove(&date);
+        }
+        let db = self.db.clone();
+        self.spawn(async move {
+            match set_day_locked(&db, &date, locked).await {
+                Ok(()) => Outcome::Saved,
+                Err(err) => Outcome::Failed(format!("Lock was not saved: {}", err)),
+            }
+        });
+    }
+
+    fn toggle_billable(&mut self) {
+        self.update_selected(|selected| selected.billable = !selected.billable);
     }
 
     // async fn migrate(&mut self) {
@@ -743,6 +2901,26 @@ impl App {
     // }
 }
 
+/// When the file was last written, `None` when that can't be told
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
+/// Bottom right corner of the area, above the status and help lines
+fn toast_rect(message: &str, area: Rect) -> Rect {
+    // Add 4 for the borders and padding
+    let width = (message.chars().count() as u16 + 4).min(area.width);
+    let height = 3.min(area.height);
+    Rect {
+        x: area.right() - width,
+        y: area.bottom().saturating_sub(height + 2).max(area.y),
+        width,
+        height,
+    }
+}
+
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::vertical([
         Constraint::Percentage((100 - percent_y) / 2),
@@ -759,10 +2937,58 @@ fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     .split(popup_layout[1])[1]
 }
 
+/// Messages from history starting with the prefix, ignoring case
+fn completions<'a>(history: &'a [String], prefix: &str) -> Vec<&'a str> {
+    let prefix = prefix.to_lowercase();
+    history
+        .iter()
+        .filter(|message| message.to_lowercase().starts_with(&prefix))
+        .map(String::as_str)
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_tags() {
+        assert_eq!(
+            parse_tags("Fixed #Invoicing bug, see #ops-42. #invoicing again"),
+            vec!["invoicing", "ops-42"]
+        );
+        assert!(parse_tags("no tags # here").is_empty());
+    }
+
+    #[test]
+    fn test_completions() {
+        let history = vec![
+            "Code review".to_string(),
+            "code cleanup".to_string(),
+            "deploy".to_string(),
+        ];
+
+        assert_eq!(
+            completions(&history, "co"),
+            vec!["Code review", "code cleanup"]
+        );
+        assert_eq!(completions(&history, "").len(), 3);
+        assert!(completions(&history, "x").is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_matches() {
+        let checkpoint = Checkpoint {
+            project: Some("123".to_string()),
+            message: Some("Fixed the Invoicing bug".to_string()),
+            ..Checkpoint::new()
+        };
+
+        assert!(checkpoint.matches("invoicing"));
+        assert!(checkpoint.matches("12"));
+        assert!(!checkpoint.matches("deploy"));
+    }
+
     #[test]
     fn test_checkpoint_color_generation() {
         let mut checkpoint = Checkpoint::new();
@@ -770,24 +2996,32 @@ mod tests {
 
         // Test with a task ID that should generate a color
         checkpoint.project = Some("12345".to_string());
-        let color1 = checkpoint.color();
+        let color1 = checkpoint.color(&Theme::default());
 
         // Test with another task ID
         checkpoint.project = Some("67890".to_string());
-        let color2 = checkpoint.color();
+        let color2 = checkpoint.color(&Theme::default());
 
         // Colors should be different (highly likely, but collisions are possible, so maybe test multiple)
         // With only 2, collision is possible but unlikely if hash is good.
         // Let's verify they are not White or Red
 
         if let Color::Indexed(c) = color1 {
-            assert!(c >= 16 && c <= 231, "Color {} is out of range 16-231", c);
+            assert!(
+                (16..=231).contains(&c),
+                "Color {} is out of range 16-231",
+                c
+            );
         } else {
             panic!("Expected Color::Indexed, got {:?}", color1);
         }
 
         if let Color::Indexed(c) = color2 {
-            assert!(c >= 16 && c <= 231, "Color {} is out of range 16-231", c);
+            assert!(
+                (16..=231).contains(&c),
+                "Color {} is out of range 16-231",
+                c
+            );
         } else {
             panic!("Expected Color::Indexed, got {:?}", color2);
         }
@@ -797,9 +3031,9 @@ mod tests {
         assert_ne!(color1, Color::Red);
         assert_ne!(color2, Color::Red);
 
-        // Test with no message -> Red
+        // Test with no message -> DarkGray
         checkpoint.message = None;
-        let color_no_msg = checkpoint.color();
-        assert_eq!(color_no_msg, Color::Red);
+        let color_no_msg = checkpoint.color(&Theme::default());
+        assert_eq!(color_no_msg, Color::DarkGray);
     }
 }
@@ -0,0 +1,579 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::future::Future;
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+use firestore::{errors::FirestoreError, FirestoreDb};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use uuid::Uuid;
+
+use super::{App, Checkpoint, DayType, Screen};
+use crate::{
+    calendar::Meeting,
+    firestore::{
+        delete_checkpoints, find_checkpoints_between, find_day_types, find_locked_days,
+        insert_checkpoint, refresh_tracking, update_checkpoints,
+    },
+    git::Commit,
+    note_editor::NoteEditor,
+    pbs::PbsTask,
+    slack,
+    stats::{daily_stats, PeriodStats},
+    time::{
+        count_overlaps, dominant_project, overnight_splits, unregistered_spans, Time, TimeSettings,
+        Week,
+    },
+    webhook::{self, Event, WebhookConfig},
+};
+
+/// Result of a store or PBS call made in the background, applied to the [`App`] once it arrives.
+pub enum Outcome {
+    /// Checkpoints of the week starting on `monday`, ordered by time
+    WeekLoaded {
+        monday: NaiveDate,
+        checkpoints: Vec<Checkpoint>,
+        selection: Selection,
+    },
+    TasksFetched(Vec<PbsTask>),
+    /// Commits made during the selected span
+    CommitsFound(Vec<Commit>),
+    /// Meetings of the selected day in the calendar
+    MeetingsFetched(Vec<Meeting>),
+    SearchFinished(Vec<Checkpoint>),
+    /// Unregistered spans across all dates with their duration in minutes
+    ReviewLoaded(Vec<(Checkpoint, u32)>),
+    StatsLoaded {
+        week: Vec<Checkpoint>,
+        month: Vec<Checkpoint>,
+        heatmap: Vec<Checkpoint>,
+    },
+    /// Messages used with the project of the checkpoint being edited
+    MessagesLoaded(Vec<String>),
+    /// Checkpoints of all the loaded weeks
+    MinimapLoaded(Vec<Checkpoint>),
+    NoteLoaded {
+        date: NaiveDate,
+        text: String,
+    },
+    LockedDaysLoaded(BTreeSet<NaiveDate>),
+    DayTypesLoaded(BTreeMap<NaiveDate, DayType>),
+    /// Checkpoints of the days the flex balance is counted over
+    FlexDaysLoaded(Vec<Checkpoint>),
+    /// Checkpoints of the closest previous day moved to the selected date, not stored yet
+    PreviousDayCopied(Vec<Checkpoint>),
+    /// Writes already shown in the week reached the store
+    Saved,
+    /// Start of the idleness the running span goes on through, `None` when there is none
+    IdleChecked(Option<Time>),
+    /// The Slack status follows the running span
+    SlackStatusSet(slack::Status),
+    /// A webhook called after a write failed, not the answer to a call counted as pending
+    WebhookFailed(String),
+
+    /// Writes did not reach the store, the week is restored to how it was before them
+    SaveFailed {
+        monday: NaiveDate,
+        rollback: Box<Week>,
+        error: String,
+    },
+    Failed(String),
+}
+
+/// Change of the stored checkpoints
+pub enum Write {
+    Insert(Vec<Checkpoint>),
+    Update(Vec<Checkpoint>),
+    Delete(Vec<Checkpoint>),
+}
+
+/// Writes of a single edit together with the week as it was before the edit
+pub struct PendingWrite {
+    monday: NaiveDate,
+    rollback: Week,
+    writes: Vec<Write>,
+    /// Called once the writes reached the store
+    webhooks: Vec<WebhookConfig>,
+    /// Tells the day whose running span is cached
+    settings: TimeSettings,
+    /// Profile whose status cache is refreshed
+    profile: Option<String>,
+}
+
+impl PendingWrite {
+    async fn run(&self, db: &FirestoreDb) -> Result<(), FirestoreError> {
+        for write in &self.writes {
+            match write {
+                Write::Insert(checkpoints) => {
+                    for checkpoint in checkpoints {
+                        insert_checkpoint(db, checkpoint.clone()).await?;
+                    }
+                }
+                Write::Update(checkpoints) => update_checkpoints(db, checkpoints).await?,
+                Write::Delete(checkpoints) => delete_checkpoints(db, checkpoints).await?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Days whose checkpoints the writes change, including the days checkpoints moved away from
+    fn dates(&self) -> BTreeSet<NaiveDate> {
+        let before: Vec<&Checkpoint> = self
+            .rollback
+            .days()
+            .into_iter()
+            .flat_map(|(_, day)| day)
+            .collect();
+        let mut dates = BTreeSet::new();
+        for write in &self.writes {
+            let (Write::Insert(checkpoints)
+            | Write::Update(checkpoints)
+            | Write::Delete(checkpoints)) = write;
+            for checkpoint in checkpoints {
+                dates.insert(checkpoint.time.date_naive());
+                let moved = before
+                    .iter()
+                    .find(|old| checkpoint.id.is_some() && old.id == checkpoint.id);
+                if let Some(old) = moved {
+                    dates.insert(old.time.date_naive());
+                }
+            }
+        }
+        dates
+    }
+
+    /// The written checkpoints by the event of their webhooks, an update setting `registered`
+    /// registers the checkpoint
+    fn events(&self) -> Vec<(Event, Vec<Checkpoint>)> {
+        let was_registered = |checkpoint: &Checkpoint| {
+            self.rollback
+                .days()
+                .into_iter()
+                .flat_map(|(_, day)| day)
+                .any(|old| old.id == checkpoint.id && old.registered)
+        };
+        let mut events = vec![];
+        for write in &self.writes {
+            match write {
+                Write::Insert(checkpoints) => events.push((Event::Insert, checkpoints.clone())),
+                Write::Update(checkpoints) => {
+                    let (registered, updated) = checkpoints
+                        .iter()
+                        .cloned()
+                        .partition(|ch| ch.registered && !was_registered(ch));
+                    events.push((Event::Register, registered));
+                    events.push((Event::Update, updated));
+                }
+                Write::Delete(_) => {}
+            }
+        }
+        events.retain(|(_, checkpoints)| !checkpoints.is_empty());
+        events
+    }
+
+    /// Calls the webhooks in a task of their own, so that the writes after this one don't wait
+    /// for them
+    fn spawn_webhooks(&self, outcomes: &UnboundedSender<Outcome>) {
+        if self.webhooks.is_empty() {
+            return;
+        }
+        let (hooks, events, outcomes) = (self.webhooks.clone(), self.events(), outcomes.clone());
+        tokio::spawn(async move {
+            for (event, checkpoints) in events {
+                for err in webhook::fire(&hooks, event, &checkpoints).await {
+                    let _ = outcomes.send(Outcome::WebhookFailed(format!("{:#}", err)));
+                }
+            }
+        });
+    }
+
+    /// Caches what runs today when the writes changed today
+    async fn refresh_tracking(&self, db: &FirestoreDb) -> Result<(), FirestoreError> {
+        for date in self.dates() {
+            refresh_tracking(db, &date, &self.settings, self.profile.as_deref()).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the writes one after another, so they reach the store in the order they were made
+pub async fn write_checkpoints(
+    db: FirestoreDb,
+    mut writes: UnboundedReceiver<PendingWrite>,
+    outcomes: UnboundedSender<Outcome>,
+) {
+    while let Some(pending) = writes.recv().await {
+        let outcome = match pending.run(&db).await {
+            Ok(()) => {
+                pending.spawn_webhooks(&outcomes);
+                match pending.refresh_tracking(&db).await {
+                    Ok(()) => Outcome::Saved,
+                    Err(err) => Outcome::Failed(format!("The status was not cached: {}", err)),
+                }
+            }
+            Err(err) => Outcome::SaveFailed {
+                monday: pending.monday,
+                rollback: Box::new(pending.rollback),
+                error: err.to_string(),
+            },
+        };
+        // the receiver only goes away when the app quits
+        let _ = outcomes.send(outcome);
+    }
+}
+
+/// What to select once a week is loaded
+pub enum Selection {
+    /// The first checkpoint of the first shown day of the week
+    Start,
+    /// The same day and checkpoint as before, as far as it still exists
+    Keep { weekday: Weekday, idx: usize },
+    /// The first checkpoint of the day
+    Day(Weekday),
+    /// The checkpoint with the id, on its day
+    Checkpoint {
+        weekday: Weekday,
+        id: Option<String>,
+    },
+}
+
+impl App {
+    /// Runs the future in the background, the spinner is shown until its outcome is applied
+    pub(super) fn spawn<F>(&mut self, task: F)
+    where
+        F: Future<Output = Outcome> + Send + 'static,
+    {
+        self.pending += 1;
+        let outcomes = self.outcomes_tx.clone();
+        tokio::spawn(async move {
+            // the receiver only goes away when the app quits
+            let _ = outcomes.send(task.await);
+        });
+    }
+
+    /// Stores the writes of an edit already applied to the week, `rollback` is the week before it
+    pub(super) fn persist(&mut self, rollback: Week, mut writes: Vec<Write>) {
+        if !self.unsaved_splits.is_empty() {
+            let splits: Vec<Checkpoint> = rollback
+                .days()
+                .into_iter()
+                .flat_map(|(_, day)| day)
+                .filter(|ch| {
+                    ch.id
+                        .as_ref()
+                        .is_some_and(|id| self.unsaved_splits.contains(id))
+                })
+                .cloned()
+                .collect();
+            self.unsaved_splits.clear();
+            // Inserted as they were before the edit, which is applied on top of them
+            writes.insert(0, Write::Insert(splits));
+        }
+        self.pending += 1;
+        self.queued_writes += 1;
+        let pending = PendingWrite {
+            monday: self.mondays[self.selected_mon_idx],
+            rollback,
+            writes,
+            webhooks: self.webhooks.clone(),
+            settings: self.settings,
+            profile: self.profile.clone(),
+        };
+        if self.writes_tx.send(pending).is_err() {
+            self.pending -= 1;
+            self.queued_writes -= 1;
+            self.show_toast("Changes can't be saved anymore".to_string());
+        }
+    }
+
+    /// Applies the outcomes of finished background calls
+    pub(super) fn apply_outcomes(&mut self) {
+        while let Ok(outcome) = self.outcomes_rx.try_recv() {
+            if !matches!(outcome, Outcome::WebhookFailed(_)) {
+                self.pending = self.pending.saturating_sub(1);
+            }
+            self.apply_outcome(outcome);
+        }
+    }
+
+    fn apply_outcome(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::WeekLoaded {
+                monday,
+                checkpoints,
+                selection,
+            } => {
+                // Drop weeks the user already moved away from
+                if self.mondays[self.selected_mon_idx] != monday {
+                    return;
+                }
+                // The store does not have the shown edits yet, the week would lose them
+                if self.queued_writes > 0 {
+                    self.deferred_load = Some((monday, selection));
+                    return;
+                }
+                if self.recent_projects.is_empty() {
+                    // Start from the projects used in the first loaded week
+                    let projects = checkpoints.iter().filter_map(|ch| ch.project.clone());
+                    for project in projects {
+                        self.note_recent_project(&project);
+                    }
+                }
+                let mut checkpoints = checkpoints;
+                // Shown right away, but only an edit of the user writes to the store
+                self.unsaved_splits.clear();
+                for mut split in overnight_splits(&checkpoints) {
+                    if self.locked_days.contains(&split.time.date_naive()) {
+                        continue;
+                    }
+                    let id = Uuid::new_v4().simple().to_string();
+                    self.unsaved_splits.insert(id.clone());
+                    split.id = Some(id);
+                    checkpoints.push(split);
+                }
+                checkpoints.sort_by_key(|ch| ch.time);
+                self.set_week(checkpoints, selection);
+                let overlaps = self
+                    .week
+                    .days()
+                    .iter()
+                    .map(|(_, day)| count_overlaps(day))
+                    .sum();
+                self.warn_overlaps(overlaps);
+            }
+            Outcome::TasksFetched(tasks) => {
+                self.tasks = tasks;
+                self.open(Screen::Tasks);
+                self.task_popup_state.select(Some(0));
+            }
+            Outcome::CommitsFound(commits) if commits.is_empty() => {
+                self.show_toast("No commits during the span".to_string());
+            }
+            Outcome::CommitsFound(commits) => {
+                self.commits = commits;
+                self.open(Screen::Commits);
+                self.commit_popup_state.select(Some(0));
+            }
+            Outcome::MeetingsFetched(meetings) if meetings.is_empty() => {
+                self.show_toast("No meetings on the day".to_string());
+            }
+            Outcome::MeetingsFetched(meetings) => {
+                self.meetings = meetings;
+                self.open(Screen::Meetings);
+                self.meeting_popup_state.select(Some(0));
+            }
+            Outcome::SearchFinished(results) => {
+                self.search_results = results;
+                self.open(Screen::Search);
+                self.search_popup_state.select(Some(0));
+            }
+            Outcome::ReviewLoaded(items) => {
+                self.review_items = items;
+                self.open(Screen::Review);
+                self.review_state.select(Some(0));
+            }
+            Outcome::StatsLoaded {
+                week,
+                month,
+                heatmap,
+            } => {
+                self.week_stats = PeriodStats::from_checkpoints(&week, &self.settings.rounding);
+                self.month_stats = PeriodStats::from_checkpoints(&month, &self.settings.rounding);
+                self.daily_stats = daily_stats(&heatmap, &self.settings.rounding);
+                self.open(Screen::Stats);
+            }
+            Outcome::MessagesLoaded(messages) => self.message_history = messages,
+            Outcome::MinimapLoaded(checkpoints) => {
+                self.minimap = checkpoints
+                    .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+                    .filter_map(|day| {
+                        Some((
+                            day[0].time.date_naive(),
+                            dominant_project(day, &self.settings.rounding)?,
+                        ))
+                    })
+                    .collect();
+            }
+            Outcome::NoteLoaded { date, text } => {
+                self.note_date = date;
+                self.note_editor = NoteEditor::new(&text);
+                self.open(Screen::Notes);
+            }
+            Outcome::LockedDaysLoaded(days) => self.locked_days = days,
+            Outcome::DayTypesLoaded(days) => self.day_types = days,
+            Outcome::FlexDaysLoaded(checkpoints) => {
+                self.flex_days = daily_stats(&checkpoints, &self.settings.rounding)
+            }
+            Outcome::PreviousDayCopied(checkpoints) => {
+                self.add_checkpoints(checkpoints, false);
+            }
+            Outcome::Saved => self.write_done(),
+            Outcome::IdleChecked(since) => self.idle_checked(since),
+            Outcome::SlackStatusSet(status) => self.slack_status = Some(status),
+            Outcome::SaveFailed {
+                monday,
+                rollback,
+                error,
+            } => {
+                self.write_done();
+                if self.mondays[self.selected_mon_idx] == monday {
+                    self.week = *rollback;
+                    // Later edits may have been saved on top of the failed one
+                    self.reload_week();
+                }
+                self.show_toast(format!("Changes were not saved: {}", error));
+            }
+            Outcome::Failed(err) | Outcome::WebhookFailed(err) => self.show_toast(err),
+        }
+    }
+
+    /// Loads the week deferred while edits were queued, once the last of them reached the store
+    fn write_done(&mut self) {
+        self.queued_writes = self.queued_writes.saturating_sub(1);
+        if self.queued_writes > 0 {
+            return;
+        }
+        if let Some((monday, selection)) = self.deferred_load.take() {
+            if self.mondays[self.selected_mon_idx] == monday {
+                self.load_week(selection);
+            }
+        }
+    }
+
+    /// Loads the selected week in the background and selects a checkpoint once it arrives
+    pub(super) fn load_week(&mut self, selection: Selection) {
+        let (db, timezone) = (self.db.clone(), self.settings.timezone);
+        let monday = self.mondays[self.selected_mon_idx]; // must be mondays in a month
+        self.spawn(async move {
+            // The neighbouring days show the spans running past midnight into and out of the week
+            let (sunday, next_monday) = (monday - Days::new(1), monday + Days::new(7));
+            match find_checkpoints_between(&db, &sunday, &next_monday, timezone).await {
+                Ok(checkpoints) => Outcome::WeekLoaded {
+                    monday,
+                    checkpoints,
+                    selection,
+                },
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Loads the dominant projects of the days of all the loaded weeks in the background
+    pub(super) fn load_minimap(&mut self) {
+        let (Some(first), Some(last)) = (self.mondays.first(), self.mondays.last()) else {
+            return;
+        };
+        let Some(&(last_weekday, _)) = self.week.visible_days().last() else {
+            return;
+        };
+        let (db, timezone) = (self.db.clone(), self.settings.timezone);
+        let (first, last) = (
+            *first,
+            self.settings.locale.date_in_week(*last, last_weekday),
+        );
+        self.spawn(async move {
+            match find_checkpoints_between(&db, &first, &last, timezone).await {
+                Ok(checkpoints) => Outcome::MinimapLoaded(checkpoints),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    pub(super) fn load_locked_days(&mut self) {
+        let db = self.db.clone();
+        self.spawn(async move {
+            match find_locked_days(&db).await {
+                Ok(days) => Outcome::LockedDaysLoaded(days),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    pub(super) fn load_day_types(&mut self) {
+        let db = self.db.clone();
+        self.spawn(async move {
+            match find_day_types(&db).await {
+                Ok(days) => Outcome::DayTypesLoaded(days),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    pub(super) fn load_flex_days(&mut self) {
+        if self.goals.expected_hours_per_day.is_none() {
+            return;
+        }
+        let (db, settings) = (self.db.clone(), self.settings);
+        let since = self.flex_since();
+        self.spawn(async move {
+            let today = settings.now().date_naive();
+            match find_checkpoints_between(&db, &since, &today, settings.timezone).await {
+                Ok(checkpoints) => Outcome::FlexDaysLoaded(checkpoints),
+                Err(err) => Outcome::Failed(err.to_string()),
+            }
+        });
+    }
+
+    /// Reload the week from the store, keeping the selected day and checkpoint in bounds
+    pub(super) fn reload_week(&mut self) {
+        self.load_week(Selection::Keep {
+            weekday: self.week.selected_weekday,
+            idx: self.week.selected_checkpoint_idx,
+        });
+    }
+
+    fn set_week(&mut self, checkpoints: Vec<Checkpoint>, selection: Selection) {
+        let monday = self.mondays[self.selected_mon_idx];
+        let mut days: [Vec<Checkpoint>; 7] = Default::default();
+        for checkpoint in checkpoints {
+            // The neighbouring weeks' days are loaded for the spans running past midnight
+            let date = checkpoint.time.date_naive();
+            if (0..7).contains(&(date - monday).num_days()) {
+                days[date.weekday().num_days_from_monday() as usize].push(checkpoint);
+            }
+        }
+        let unregistered = days
+            .iter()
+            .flat_map(|day| unregistered_spans(day, &self.settings.rounding))
+            .collect();
+        let [mon, tue, wed, thu, fri, sat, sun] = days;
+
+        self.week = Week {
+            mon,
+            tue,
+            wed,
+            thu,
+            fri,
+            sat,
+            sun,
+            show_weekend: self.layout.show_weekend,
+            first_day_of_week: self.settings.locale.first_day_of_week,
+            unregistered_checkpoints: unregistered,
+            selected_weekday: self.settings.locale.first_day_of_week,
+            selected_checkpoint_idx: 0,
+        };
+
+        match selection {
+            Selection::Start => {
+                // The first day of the week is hidden with the weekend when the week starts on it
+                if let Some(&(weekday, _)) = self.week.visible_days().first() {
+                    self.week.selected_weekday = weekday;
+                }
+            }
+            Selection::Keep { weekday, idx } => {
+                self.week.selected_weekday = weekday;
+                self.week.selected_checkpoint_idx = idx;
+                self.week.select_max_checkpoint_idx();
+            }
+            Selection::Day(weekday) => {
+                self.week.selected_weekday = weekday;
+                self.week.select_max_checkpoint_idx();
+            }
+            Selection::Checkpoint { weekday, id } => {
+                self.week.selected_weekday = weekday;
+                self.week.select_max_checkpoint_idx();
+                if let Some(idx) = self.week.active_day().iter().position(|ch| ch.id == id) {
+                    self.week.selected_checkpoint_idx = idx;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,610 @@
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use super::{App, ConfirmAction, Screen};
+
+/// Actions of the week screen, run by their keys or from the command palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    AddCheckpoint,
+    ToggleClock,
+    InsertAtTime,
+    EditTime,
+    MoveToDate,
+    SplitSpan,
+    EditMessage,
+    ApplyTemplate,
+    SuggestFromCommits,
+    ImportMeetings,
+    AssignTask,
+    AssignProject,
+    AssignLastProject,
+    ToggleRegistered,
+    RegisterDay,
+    ToggleBillable,
+    ToggleLock,
+    SetDayType,
+    FixOverlaps,
+    CycleKind,
+    DeleteCheckpoint,
+    MergeSpans,
+    MergeDuplicates,
+    CopyPreviousDay,
+    CopyDaySummary,
+    YankSpan,
+    PutSpan,
+    EditNotes,
+    StartSelection,
+    RegisterSelection,
+    DeleteSelection,
+    EndSelection,
+    MoveLater,
+    MoveEarlier,
+    MoveEndLater,
+    MoveEndEarlier,
+    NextCheckpoint,
+    PreviousCheckpoint,
+    NextDay,
+    PreviousDay,
+    NextWeek,
+    PreviousWeek,
+    FirstWeek,
+    LastWeek,
+    JumpToToday,
+    JumpToDate,
+    Search,
+    FilterByTag,
+    ShowWarnings,
+    ShowStats,
+    ReviewUnregistered,
+    ToggleTotals,
+    ToggleDetail,
+    ToggleInput,
+    NarrowTotals,
+    WidenTotals,
+    ToggleBraille,
+    ToggleRoundedTimes,
+    ToggleWeekend,
+    ZoomIn,
+    ZoomOut,
+    OpenPalette,
+    Quit,
+}
+
+/// Key that runs a command, `ctrl` tells whether Control has to be held
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub ctrl: bool,
+}
+
+impl KeyBinding {
+    const fn key(code: KeyCode) -> Self {
+        Self { code, ctrl: false }
+    }
+
+    const fn ctrl(code: KeyCode) -> Self {
+        Self { code, ctrl: true }
+    }
+
+    const fn char(c: char) -> Self {
+        Self::key(KeyCode::Char(c))
+    }
+
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && self.ctrl == key.modifiers.contains(KeyModifiers::CONTROL)
+    }
+
+    /// Short text of the key as shown to the user
+    pub fn label(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "<space>".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Tab => "<tab>".to_string(),
+            KeyCode::BackTab => "<s-tab>".to_string(),
+            KeyCode::Esc => "<esc>".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            code => code.to_string(),
+        };
+        if self.ctrl {
+            format!("C-{}", key)
+        } else {
+            key
+        }
+    }
+}
+
+/// Keys of the week screen, the first key of a command is the one shown to the user
+pub const KEYMAP: &[(KeyBinding, Command)] = &[
+    (KeyBinding::char(' '), Command::AddCheckpoint),
+    (KeyBinding::char('S'), Command::ToggleClock),
+    (KeyBinding::char('i'), Command::InsertAtTime),
+    (KeyBinding::char('T'), Command::EditTime),
+    (KeyBinding::char('M'), Command::MoveToDate),
+    (KeyBinding::char('s'), Command::SplitSpan),
+    (KeyBinding::char('m'), Command::EditMessage),
+    (KeyBinding::char('c'), Command::ApplyTemplate),
+    (KeyBinding::char('C'), Command::SuggestFromCommits),
+    (KeyBinding::char('E'), Command::ImportMeetings),
+    (KeyBinding::char('p'), Command::AssignTask),
+    (KeyBinding::char('f'), Command::AssignProject),
+    (KeyBinding::char('.'), Command::AssignLastProject),
+    (KeyBinding::char('r'), Command::ToggleRegistered),
+    (KeyBinding::char('R'), Command::RegisterDay),
+    (KeyBinding::char('$'), Command::ToggleBillable),
+    (KeyBinding::char('L'), Command::ToggleLock),
+    (KeyBinding::char('V'), Command::SetDayType),
+    (KeyBinding::char('O'), Command::FixOverlaps),
+    (KeyBinding::char('b'), Command::CycleKind),
+    (KeyBinding::char('d'), Command::DeleteCheckpoint),
+    (KeyBinding::char('J'), Command::MergeSpans),
+    (KeyBinding::char('X'), Command::MergeDuplicates),
+    (KeyBinding::char('y'), Command::CopyPreviousDay),
+    (KeyBinding::char('Y'), Command::CopyDaySummary),
+    (KeyBinding::ctrl(KeyCode::Char('y')), Command::YankSpan),
+    (KeyBinding::ctrl(KeyCode::Char('p')), Command::PutSpan),
+    (KeyBinding::char('N'), Command::EditNotes),
+    (KeyBinding::char('v'), Command::StartSelection),
+    (KeyBinding::char('l'), Command::MoveLater),
+    (KeyBinding::char('h'), Command::MoveEarlier),
+    (KeyBinding::ctrl(KeyCode::Char('l')), Command::MoveEndLater),
+    (
+        KeyBinding::ctrl(KeyCode::Char('h')),
+        Command::MoveEndEarlier,
+    ),
+    (KeyBinding::key(KeyCode::Right), Command::NextCheckpoint),
+    (KeyBinding::key(KeyCode::Left), Command::PreviousCheckpoint),
+    (KeyBinding::key(KeyCode::Down), Command::NextDay),
+    (KeyBinding::key(KeyCode::Up), Command::PreviousDay),
+    (KeyBinding::key(KeyCode::Tab), Command::NextWeek),
+    (KeyBinding::key(KeyCode::BackTab), Command::PreviousWeek),
+    (KeyBinding::char('g'), Command::FirstWeek),
+    (KeyBinding::char('G'), Command::LastWeek),
+    (KeyBinding::char('t'), Command::JumpToToday),
+    (KeyBinding::char('o'), Command::JumpToDate),
+    (KeyBinding::char('/'), Command::Search),
+    (KeyBinding::char('#'), Command::FilterByTag),
+    (KeyBinding::char('w'), Command::ShowWarnings),
+    (KeyBinding::char('a'), Command::ShowStats),
+    (KeyBinding::char('u'), Command::ReviewUnregistered),
+    (KeyBinding::char('P'), Command::ToggleTotals),
+    (KeyBinding::char('D'), Command::ToggleDetail),
+    (KeyBinding::char('I'), Command::ToggleInput),
+    (KeyBinding::char('['), Command::NarrowTotals),
+    (KeyBinding::char(']'), Command::WidenTotals),
+    (KeyBinding::char('B'), Command::ToggleBraille),
+    (KeyBinding::char('~'), Command::ToggleRoundedTimes),
+    (KeyBinding::char('W'), Command::ToggleWeekend),
+    (KeyBinding::char('+'), Command::ZoomIn),
+    (KeyBinding::char('='), Command::ZoomIn),
+    (KeyBinding::char('-'), Command::ZoomOut),
+    (KeyBinding::char(':'), Command::OpenPalette),
+    (KeyBinding::char('q'), Command::Quit),
+    (KeyBinding::key(KeyCode::Esc), Command::Quit),
+    (KeyBinding::ctrl(KeyCode::Char('c')), Command::Quit),
+    (KeyBinding::ctrl(KeyCode::Char('C')), Command::Quit),
+];
+
+/// Keys while a visual selection is made, the commands act on all of its checkpoints
+pub const VISUAL_KEYMAP: &[(KeyBinding, Command)] = &[
+    (KeyBinding::char('p'), Command::AssignTask),
+    (KeyBinding::char('f'), Command::AssignProject),
+    (KeyBinding::char('c'), Command::ApplyTemplate),
+    (KeyBinding::char('r'), Command::RegisterSelection),
+    (KeyBinding::char('d'), Command::DeleteSelection),
+    (KeyBinding::char('l'), Command::NextCheckpoint),
+    (KeyBinding::key(KeyCode::Right), Command::NextCheckpoint),
+    (KeyBinding::char('h'), Command::PreviousCheckpoint),
+    (KeyBinding::key(KeyCode::Left), Command::PreviousCheckpoint),
+    (KeyBinding::char('v'), Command::EndSelection),
+    (KeyBinding::key(KeyCode::Esc), Command::EndSelection),
+];
+
+/// Keys of the week screen, or of the visual selection when `visual` is set
+fn keymap(visual: bool) -> &'static [(KeyBinding, Command)] {
+    if visual {
+        VISUAL_KEYMAP
+    } else {
+        KEYMAP
+    }
+}
+
+/// Commands described in the help line, the most important first
+const HELP: &[(&str, &[Command])] = &[
+    ("Add", &[Command::AddCheckpoint]),
+    ("Start/Stop", &[Command::ToggleClock]),
+    ("Commands", &[Command::OpenPalette]),
+    ("Message", &[Command::EditMessage, Command::ApplyTemplate]),
+    ("Tasks", &[Command::AssignTask]),
+    ("Projects", &[Command::AssignProject]),
+    ("Last project", &[Command::AssignLastProject]),
+    ("Insert", &[Command::InsertAtTime]),
+    ("Time", &[Command::EditTime]),
+    ("Move to date", &[Command::MoveToDate]),
+    ("Del", &[Command::DeleteCheckpoint]),
+    ("Split", &[Command::SplitSpan]),
+    ("Merge", &[Command::MergeSpans]),
+    ("Merge duplicates", &[Command::MergeDuplicates]),
+    ("Move", &[Command::MoveEarlier, Command::MoveLater]),
+    (
+        "Move end",
+        &[Command::MoveEndEarlier, Command::MoveEndLater],
+    ),
+    (
+        "Registered",
+        &[Command::ToggleRegistered, Command::RegisterDay],
+    ),
+    ("Billable", &[Command::ToggleBillable]),
+    ("Lock", &[Command::ToggleLock]),
+    ("Day off", &[Command::SetDayType]),
+    ("Break", &[Command::CycleKind]),
+    ("Select", &[Command::StartSelection]),
+    ("Weeks", &[Command::NextWeek, Command::PreviousWeek]),
+    ("Today", &[Command::JumpToToday]),
+    ("Go to", &[Command::JumpToDate]),
+    ("Search", &[Command::Search]),
+    ("Tag", &[Command::FilterByTag]),
+    ("Warnings", &[Command::ShowWarnings]),
+    ("Fix overlaps", &[Command::FixOverlaps]),
+    ("Unregistered", &[Command::ReviewUnregistered]),
+    ("Stats", &[Command::ShowStats]),
+    ("Copy day", &[Command::CopyDaySummary]),
+    ("Yank/Put", &[Command::YankSpan, Command::PutSpan]),
+    ("Copy prev. day", &[Command::CopyPreviousDay]),
+    ("Notes", &[Command::EditNotes]),
+    (
+        "Panes",
+        &[
+            Command::ToggleTotals,
+            Command::ToggleDetail,
+            Command::ToggleInput,
+        ],
+    ),
+    ("Zoom", &[Command::ZoomIn, Command::ZoomOut]),
+    ("Raw/rounded", &[Command::ToggleRoundedTimes]),
+    ("Weekend", &[Command::ToggleWeekend]),
+    ("Quit", &[Command::Quit]),
+];
+
+/// Commands described in the help line during a visual selection
+const VISUAL_HELP: &[(&str, &[Command])] = &[
+    ("Projects", &[Command::AssignProject]),
+    ("Tasks", &[Command::AssignTask]),
+    ("Message", &[Command::ApplyTemplate]),
+    ("Registered", &[Command::RegisterSelection]),
+    ("Del", &[Command::DeleteSelection]),
+    (
+        "Extend",
+        &[Command::PreviousCheckpoint, Command::NextCheckpoint],
+    ),
+    ("End", &[Command::EndSelection]),
+];
+
+/// Entries of the help line with the keys currently bound to their commands, those of the
+/// visual selection when `visual` is set
+pub fn help_entries(visual: bool) -> Vec<(&'static str, String)> {
+    let help = if visual { VISUAL_HELP } else { HELP };
+    help.iter()
+        .filter_map(|(description, commands)| {
+            let keys: Vec<String> = commands
+                .iter()
+                .filter_map(|command| command.binding_in(keymap(visual)))
+                .map(|binding| binding.label())
+                .collect();
+            (!keys.is_empty()).then(|| (*description, keys.join("/")))
+        })
+        .collect()
+}
+
+impl Command {
+    /// Whether the command changes the checkpoints of the selected day, which locked days
+    /// refuse. Commands adding checkpoints are checked against the days they add to.
+    fn edits_selected_day(self) -> bool {
+        matches!(
+            self,
+            Command::InsertAtTime
+                | Command::EditTime
+                | Command::MoveToDate
+                | Command::SplitSpan
+                | Command::EditMessage
+                | Command::ApplyTemplate
+                | Command::SuggestFromCommits
+                | Command::ImportMeetings
+                | Command::AssignTask
+                | Command::AssignProject
+                | Command::AssignLastProject
+                | Command::ToggleRegistered
+                | Command::RegisterDay
+                | Command::ToggleBillable
+                | Command::CycleKind
+                | Command::DeleteCheckpoint
+                | Command::MergeSpans
+                | Command::CopyPreviousDay
+                | Command::RegisterSelection
+                | Command::DeleteSelection
+                | Command::SetDayType
+                | Command::FixOverlaps
+                | Command::MoveLater
+                | Command::MoveEarlier
+                | Command::MoveEndLater
+                | Command::MoveEndEarlier
+        )
+    }
+
+    /// Every command, in the order they are listed in the palette
+    pub fn all() -> impl Iterator<Item = Command> {
+        let mut commands: Vec<Command> = vec![];
+        for (_, command) in KEYMAP {
+            if !commands.contains(command) {
+                commands.push(*command);
+            }
+        }
+        commands.into_iter()
+    }
+
+    /// The command of the key in the week screen, or in the visual selection when `visual` is set
+    pub fn for_key(key: &KeyEvent, visual: bool) -> Option<Command> {
+        keymap(visual)
+            .iter()
+            .find(|(binding, _)| binding.matches(key))
+            .map(|(_, command)| *command)
+    }
+
+    /// The key shown next to the command
+    pub fn binding(self) -> Option<KeyBinding> {
+        self.binding_in(KEYMAP)
+    }
+
+    fn binding_in(self, keymap: &[(KeyBinding, Command)]) -> Option<KeyBinding> {
+        keymap
+            .iter()
+            .find(|(_, command)| *command == self)
+            .map(|(binding, _)| *binding)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::AddCheckpoint => "Add checkpoint now",
+            Command::ToggleClock => "Start or stop the clock",
+            Command::InsertAtTime => "Insert checkpoint at time",
+            Command::EditTime => "Change checkpoint time",
+            Command::MoveToDate => "Move checkpoint to another day",
+            Command::SplitSpan => "Split span at time",
+            Command::EditMessage => "Edit message",
+            Command::ApplyTemplate => "Apply message template",
+            Command::SuggestFromCommits => "Comment with a git commit message",
+            Command::ImportMeetings => "Add meetings from the calendar",
+            Command::AssignTask => "Assign PBS task",
+            Command::AssignProject => "Assign project from projects.toml",
+            Command::AssignLastProject => "Assign last used project",
+            Command::ToggleRegistered => "Toggle registered",
+            Command::RegisterDay => "Mark whole day registered",
+            Command::ToggleBillable => "Toggle billable",
+            Command::ToggleLock => "Lock or unlock day",
+            Command::SetDayType => "Mark vacation, sick day or holiday",
+            Command::FixOverlaps => "Put overlapping checkpoints in order",
+            Command::CycleKind => "Switch work, break and absence",
+            Command::DeleteCheckpoint => "Delete checkpoint",
+            Command::MergeSpans => "Merge with next span",
+            Command::MergeDuplicates => "Merge near-duplicate checkpoints of the week",
+            Command::CopyPreviousDay => "Copy previous day",
+            Command::CopyDaySummary => "Copy day summary to clipboard",
+            Command::YankSpan => "Yank span",
+            Command::PutSpan => "Put yanked span now",
+            Command::EditNotes => "Edit day notes",
+            Command::StartSelection => "Start visual selection",
+            Command::RegisterSelection => "Mark selection registered",
+            Command::DeleteSelection => "Delete selection",
+            Command::EndSelection => "End visual selection",
+            Command::MoveLater => "Move checkpoint later",
+            Command::MoveEarlier => "Move checkpoint earlier",
+            Command::MoveEndLater => "Move span end later",
+            Command::MoveEndEarlier => "Move span end earlier",
+            Command::NextCheckpoint => "Next checkpoint",
+            Command::PreviousCheckpoint => "Previous checkpoint",
+            Command::NextDay => "Next day",
+            Command::PreviousDay => "Previous day",
+            Command::NextWeek => "Next week",
+            Command::PreviousWeek => "Previous week",
+            Command::FirstWeek => "First week",
+            Command::LastWeek => "Last week",
+            Command::JumpToToday => "Jump to today",
+            Command::JumpToDate => "Jump to date",
+            Command::Search => "Search messages",
+            Command::FilterByTag => "Filter by tag",
+            Command::ShowWarnings => "Show warnings",
+            Command::ShowStats => "Show stats",
+            Command::ReviewUnregistered => "Review unregistered spans",
+            Command::ToggleTotals => "Toggle project totals",
+            Command::ToggleDetail => "Toggle checkpoint detail",
+            Command::ToggleInput => "Toggle input",
+            Command::NarrowTotals => "Narrow project totals",
+            Command::WidenTotals => "Widen project totals",
+            Command::ToggleBraille => "Toggle braille timeline",
+            Command::ToggleRoundedTimes => "Toggle raw and rounded times",
+            Command::ToggleWeekend => "Show or hide the weekend",
+            Command::ZoomIn => "Zoom in",
+            Command::ZoomOut => "Zoom out",
+            Command::OpenPalette => "Command palette",
+            Command::Quit => "Quit",
+        }
+    }
+}
+
+/// Label of the key running the command, for hints
+pub fn key_hint(command: Command) -> String {
+    command
+        .binding()
+        .map_or_else(|| "the command palette".to_string(), |b| b.label())
+}
+
+/// Commands whose name fuzzily matches the query, the best matches first
+pub fn matching_commands(query: &str) -> Vec<Command> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut matches: Vec<(i64, Command)> = Command::all()
+        .filter_map(|command| {
+            let score = matcher.fuzzy_match(command.name(), query.trim())?;
+            Some((score, command))
+        })
+        .collect();
+    // The sort is stable, equally good matches stay in the palette order
+    matches.sort_by_key(|(score, _)| -score);
+    matches.into_iter().map(|(_, command)| command).collect()
+}
+
+impl App {
+    pub(super) async fn run_command(&mut self, command: Command) {
+        if command.edits_selected_day() && self.refuse_locked(self.selected_date()) {
+            return;
+        }
+        match command {
+            Command::AddCheckpoint => self.append_checkpoint().await,
+            Command::ToggleClock => self.toggle_clock(),
+            Command::InsertAtTime => self.start_insert_time(),
+            Command::EditTime => self.start_edit_time(),
+            Command::MoveToDate => self.start_move_date(),
+            Command::SplitSpan => self.start_split_time(),
+            Command::EditMessage => self.start_editing().await,
+            Command::ApplyTemplate => self.open_template_popup(),
+            Command::SuggestFromCommits => self.find_commits(),
+            Command::ImportMeetings => self.fetch_meetings(),
+            Command::AssignTask => self.fetch_tasks(),
+            Command::AssignProject => self.open_project_popup(),
+            Command::AssignLastProject => self.assign_last_project(),
+            Command::ToggleRegistered => self.mark_registered().await,
+            Command::RegisterDay => self.confirm(ConfirmAction::RegisterDay),
+            Command::ToggleBillable => self.toggle_billable(),
+            Command::ToggleLock => self.toggle_lock(),
+            Command::SetDayType => self.confirm(ConfirmAction::SetDayType),
+            Command::FixOverlaps => self.confirm(ConfirmAction::FixOverlaps),
+            Command::CycleKind => self.cycle_kind().await,
+            Command::DeleteCheckpoint => self.confirm(ConfirmAction::DeleteCheckpoint),
+            Command::MergeSpans => self.confirm(ConfirmAction::MergeSpans),
+            Command::MergeDuplicates => self.merge_week_duplicates(),
+            Command::CopyPreviousDay => {
+                if self.week.active_day().is_empty() {
+                    self.copy_previous_day()
+                } else {
+                    self.confirm(ConfirmAction::CopyPreviousDay)
+                }
+            }
+            Command::CopyDaySummary => self.confirm(ConfirmAction::CopyDaySummary),
+            Command::YankSpan => self.yank_span(),
+            Command::PutSpan => self.put_span(),
+            Command::EditNotes => self.open_note(),
+            Command::StartSelection => self.start_visual_selection(),
+            Command::RegisterSelection => self.mark_selection_registered().await,
+            Command::DeleteSelection => self.confirm(ConfirmAction::DeleteSelection),
+            Command::EndSelection => self.visual_anchor = None,
+            Command::MoveLater => self.lenghten_r().await,
+            Command::MoveEarlier => self.lenghten_l().await,
+            Command::MoveEndLater => self.lenghten_ctrl_r().await,
+            Command::MoveEndEarlier => self.lenghten_ctrl_l().await,
+            Command::NextCheckpoint => self.move_right().await,
+            Command::PreviousCheckpoint => self.move_left().await,
+            Command::NextDay => self.move_down().await,
+            Command::PreviousDay => self.move_up().await,
+            Command::NextWeek => self.shift_week(1),
+            Command::PreviousWeek => self.shift_week(-1),
+            Command::FirstWeek => {
+                if !self.mondays.is_empty() {
+                    self.select_week(0);
+                }
+            }
+            Command::LastWeek => {
+                if let Some(last) = self.mondays.len().checked_sub(1) {
+                    self.select_week(last);
+                }
+            }
+            Command::JumpToToday => self.jump_to_today(),
+            Command::JumpToDate => self.start_jump_date(),
+            Command::Search => self.start_search(),
+            Command::FilterByTag => self.start_tag_filter(),
+            Command::ShowWarnings => self.open(Screen::Warnings),
+            Command::ShowStats => self.open_stats(),
+            Command::ReviewUnregistered => self.open_review(),
+            Command::ToggleTotals => self.change_layout(|l| l.show_totals = !l.show_totals),
+            Command::ToggleDetail => self.change_layout(|l| l.show_detail = !l.show_detail),
+            Command::ToggleInput => self.change_layout(|l| l.show_input = !l.show_input),
+            Command::NarrowTotals => self.change_layout(|l| l.resize_totals(-4)),
+            Command::WidenTotals => self.change_layout(|l| l.resize_totals(4)),
+            Command::ToggleBraille => self.change_layout(|l| l.braille = !l.braille),
+            Command::ToggleRoundedTimes => {
+                self.change_layout(|l| l.rounded_times = !l.rounded_times)
+            }
+            Command::ToggleWeekend => self.toggle_weekend(),
+            Command::ZoomIn => self.zoom_in(),
+            Command::ZoomOut => self.zoom_out(),
+            Command::OpenPalette => self.open_palette(),
+            Command::Quit => self.quit().await,
+        }
+    }
+
+    fn open_palette(&mut self) {
+        self.palette_input.reset();
+        self.palette_state.select(Some(0));
+        self.open(Screen::Palette);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_for_key() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('l'), KeyModifiers::NONE), false),
+            Some(Command::MoveLater)
+        );
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('l'), KeyModifiers::CONTROL), false),
+            Some(Command::MoveEndLater)
+        );
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('S'), KeyModifiers::SHIFT), false),
+            Some(Command::ToggleClock)
+        );
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('x'), KeyModifiers::NONE), false),
+            None
+        );
+        // The selection extends with the keys that move checkpoints otherwise
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('l'), KeyModifiers::NONE), true),
+            Some(Command::NextCheckpoint)
+        );
+        assert_eq!(
+            Command::for_key(&key(KeyCode::Char('S'), KeyModifiers::SHIFT), true),
+            None
+        );
+    }
+
+    #[test]
+    fn test_help_entries() {
+        let entries = help_entries(false);
+
+        assert_eq!(entries[0], ("Add", "<space>".to_string()));
+        assert!(entries.contains(&("Message", "m/c".to_string())));
+        assert!(entries.contains(&("Move end", "C-h/C-l".to_string())));
+
+        let visual = help_entries(true);
+        assert!(visual.contains(&("Registered", "r".to_string())));
+        assert!(visual.contains(&("Extend", "h/l".to_string())));
+    }
+
+    #[test]
+    fn test_matching_commands() {
+        assert_eq!(
+            matching_commands("jmpdat").first(),
+            Some(&Command::JumpToDate)
+        );
+        assert_eq!(matching_commands("").len(), Command::all().count());
+        assert!(matching_commands("xyzzy").is_empty());
+    }
+}
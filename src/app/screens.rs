@@ -0,0 +1,754 @@
+use tui_input::backend::crossterm::EventHandler;
+
+use ratatui::{
+    crossterm::event::{Event, KeyCode, KeyEvent},
+    layout::{Constraint, Layout},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Clear, List, ListItem, Paragraph, Wrap},
+    Frame,
+};
+
+use super::{centered_rect, commands::matching_commands, App};
+use crate::{
+    earnings::Rates,
+    projects::{active, by_recency},
+    widgets::StatsScreen,
+};
+
+/// Views of the application. They are kept on a stack in [`App`], the topmost one receives the
+/// keys and closing it returns to the one below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Screen {
+    /// Timeline of the selected week, always at the bottom of the stack
+    Week,
+    Stats,
+    Tasks,
+    Search,
+    Review,
+    Templates,
+    /// Commits made during the selected span
+    Commits,
+    /// Meetings of the selected day in the calendar
+    Meetings,
+    Warnings,
+    /// Notes of a day being edited
+    Notes,
+    /// Commands matching the typed query
+    Palette,
+    /// Projects from `projects.toml` with their hotkeys
+    Projects,
+    /// Activities of the project picked in the project popup
+    Activities,
+}
+
+impl Screen {
+    /// Full screens cover the whole terminal, the others are drawn over the screen below them
+    pub fn is_full(&self) -> bool {
+        matches!(self, Screen::Week | Screen::Stats)
+    }
+}
+
+impl App {
+    /// The screen receiving the keys
+    pub(super) fn screen(&self) -> Screen {
+        self.screens.last().copied().unwrap_or(Screen::Week)
+    }
+
+    pub(super) fn open(&mut self, screen: Screen) {
+        self.screens.push(screen);
+    }
+
+    /// Returns to the previous screen, the week is never closed
+    pub(super) fn close(&mut self) {
+        if self.screens.len() > 1 {
+            self.screens.pop();
+        }
+    }
+
+    pub(super) fn draw_screen(&mut self, screen: Screen, frame: &mut Frame) {
+        match screen {
+            Screen::Week => self.draw_week(frame),
+            Screen::Stats => self.draw_stats(frame),
+            Screen::Tasks => self.draw_tasks(frame),
+            Screen::Search => self.draw_search(frame),
+            Screen::Review => self.draw_review(frame),
+            Screen::Templates => self.draw_templates(frame),
+            Screen::Commits => self.draw_commits(frame),
+            Screen::Meetings => self.draw_meetings(frame),
+            Screen::Warnings => self.draw_warnings(frame),
+            Screen::Notes => self.draw_notes(frame),
+            Screen::Palette => self.draw_palette(frame),
+            Screen::Projects => self.draw_projects(frame),
+            Screen::Activities => self.draw_activities(frame),
+        }
+    }
+
+    /// Handles the keys of all screens but the week
+    pub(super) async fn on_screen_key(&mut self, screen: Screen, key: KeyEvent) {
+        match screen {
+            Screen::Week => {}
+            Screen::Stats => self.on_stats_key(key),
+            Screen::Tasks => self.on_tasks_key(key).await,
+            Screen::Search => self.on_search_key(key).await,
+            Screen::Review => self.on_review_key(key).await,
+            Screen::Templates => self.on_templates_key(key).await,
+            Screen::Commits => self.on_commits_key(key),
+            Screen::Meetings => self.on_meetings_key(key),
+            Screen::Warnings => self.on_warnings_key(key),
+            Screen::Notes => self.on_notes_key(key),
+            Screen::Palette => self.on_palette_key(key).await,
+            Screen::Projects => self.on_projects_key(key),
+            Screen::Activities => self.on_activities_key(key),
+        }
+    }
+
+    /// Any of the keys opening the stats closes them
+    fn on_stats_key(&mut self, key: KeyEvent) {
+        if matches!(
+            key.code,
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('a')
+        ) {
+            self.close();
+        }
+    }
+
+    /// Assigns the highlighted task, right arrow toggles the task URLs
+    async fn on_tasks_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => {
+                self.task_popup_state.select_next();
+            }
+            KeyCode::Up => {
+                self.task_popup_state.select_previous();
+            }
+            KeyCode::Right => {
+                self.show_task_url = !self.show_task_url;
+            }
+            KeyCode::Enter => {
+                self.assign_selected_task().await;
+                self.close();
+            }
+            _ => {}
+        }
+    }
+
+    /// Jumps to the highlighted search result
+    async fn on_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.search_popup_state.select_next(),
+            KeyCode::Up => self.search_popup_state.select_previous(),
+            KeyCode::Enter => {
+                self.close();
+                self.jump_to_search_result();
+            }
+            _ => {}
+        }
+    }
+
+    /// Jumps to or registers the highlighted span
+    async fn on_review_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.review_state.select_next(),
+            KeyCode::Up => self.review_state.select_previous(),
+            KeyCode::Enter => {
+                self.close();
+                self.jump_to_review_item();
+            }
+            KeyCode::Char('r') => self.register_review_item().await,
+            _ => {}
+        }
+    }
+
+    /// Applies the highlighted template, or the one with the typed number
+    async fn on_templates_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.template_popup_state.select_next(),
+            KeyCode::Up => self.template_popup_state.select_previous(),
+            KeyCode::Enter => {
+                self.close();
+                if let Some(idx) = self.template_popup_state.selected() {
+                    self.apply_template(idx).await;
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') => {
+                self.close();
+                self.apply_template(c as usize - '1' as usize).await;
+            }
+            _ => {}
+        }
+    }
+
+    /// Comments the selected span with the highlighted commit message
+    fn on_commits_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.commit_popup_state.select_next(),
+            KeyCode::Up => self.commit_popup_state.select_previous(),
+            KeyCode::Enter => {
+                self.close();
+                if let Some(idx) = self.commit_popup_state.selected() {
+                    self.apply_commit(idx);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Adds the highlighted meeting, or all of them with `a`
+    fn on_meetings_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.meeting_popup_state.select_next(),
+            KeyCode::Up => self.meeting_popup_state.select_previous(),
+            KeyCode::Enter => {
+                self.close();
+                let meeting = self
+                    .meeting_popup_state
+                    .selected()
+                    .and_then(|idx| self.meetings.get(idx).cloned());
+                if let Some(meeting) = meeting {
+                    self.import_meetings(vec![meeting]);
+                }
+            }
+            KeyCode::Char('a') => {
+                self.close();
+                self.import_meetings(self.meetings.clone());
+            }
+            _ => {}
+        }
+    }
+
+    /// Filters the commands by the typed query and runs the highlighted one
+    async fn on_palette_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.close(),
+            KeyCode::Down => self.palette_state.select_next(),
+            KeyCode::Up => self.palette_state.select_previous(),
+            KeyCode::Enter => {
+                let commands = matching_commands(self.palette_input.value());
+                let selected = self.palette_state.selected().unwrap_or(0);
+                self.close();
+                if let Some(command) = commands.get(selected) {
+                    self.run_command(*command).await;
+                }
+            }
+            _ => {
+                self.palette_input.handle_event(&Event::Key(key));
+                self.palette_state.select(Some(0));
+            }
+        }
+    }
+
+    /// Assigns the project with the pressed hotkey, or the highlighted one
+    fn on_projects_key(&mut self, key: KeyEvent) {
+        let project = match key.code {
+            KeyCode::Esc => {
+                self.close();
+                return;
+            }
+            KeyCode::Down => {
+                self.project_popup_state.select_next();
+                return;
+            }
+            KeyCode::Up => {
+                self.project_popup_state.select_previous();
+                return;
+            }
+            KeyCode::Enter => self.project_popup_state.selected().and_then(|idx| {
+                by_recency(&self.projects, &self.recent_projects)
+                    .get(idx)
+                    .copied()
+            }),
+            KeyCode::Char(c) => active(&self.projects).find(|p| p.hotkey == Some(c)),
+            _ => None,
+        };
+        let Some(project) = project.cloned() else {
+            return;
+        };
+        self.close();
+        if project.activities.is_empty() {
+            self.assign_project(project.id, None);
+        } else {
+            self.activity_project = Some(project);
+            self.open(Screen::Activities);
+            self.activity_popup_state.select(Some(0));
+        }
+    }
+
+    /// Assigns the picked project with the highlighted activity, the first item assigns it
+    /// without one
+    fn on_activities_key(&mut self, key: KeyEvent) {
+        let Some(project) = self.activity_project.clone() else {
+            self.close();
+            return;
+        };
+        let idx = match key.code {
+            KeyCode::Esc => {
+                self.activity_project = None;
+                self.close();
+                return;
+            }
+            KeyCode::Down => {
+                self.activity_popup_state.select_next();
+                return;
+            }
+            KeyCode::Up => {
+                self.activity_popup_state.select_previous();
+                return;
+            }
+            KeyCode::Enter => self.activity_popup_state.selected(),
+            KeyCode::Char(c) => c.to_digit(10).map(|digit| digit as usize),
+            _ => None,
+        };
+        let Some(idx) = idx.filter(|idx| *idx <= project.activities.len()) else {
+            return;
+        };
+        self.activity_project = None;
+        self.close();
+        let activity = idx
+            .checked_sub(1)
+            .map(|idx| project.activities[idx].clone());
+        self.assign_project(project.id, activity);
+    }
+
+    /// Edits the notes, leaving them saves them
+    fn on_notes_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => {
+                self.save_note();
+                self.close();
+            }
+            _ => self.note_editor.handle_key(key),
+        }
+    }
+
+    /// The warnings are only read, any of the keys opening them closes them
+    fn on_warnings_key(&mut self, key: KeyEvent) {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Char('w')) {
+            self.close();
+        }
+    }
+
+    /// Summary of the selected week and month with the heatmap of recent days
+    fn draw_stats(&mut self, frame: &mut Frame) {
+        let monday = self.mondays[self.selected_mon_idx];
+        let stats = StatsScreen {
+            week: &self.week_stats,
+            month: &self.month_stats,
+            days: &self.daily_stats,
+            today: self.settings.now().date_naive(),
+            titles: (
+                format!(
+                    "Week {} ({})",
+                    self.settings.locale.week_number(monday),
+                    self.settings.locale.day(monday)
+                ),
+                self.selected_date().format("%B %Y").to_string(),
+            ),
+            flex: self.flex_balance(),
+            rates: Rates::new(&self.projects, self.currency.clone()),
+            theme: self.theme,
+            settings: self.settings,
+        };
+        frame.render_widget(stats, frame.area());
+    }
+
+    /// Tasks fetched from PBS to assign to the selected checkpoints
+    fn draw_tasks(&mut self, frame: &mut Frame) {
+        let area = centered_rect(60, 80, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .tasks
+            .iter()
+            .map(|t| {
+                let mut header_spans = vec![];
+
+                if let (Some(spent), Some(total)) = (&t.time_spent, &t.time_total) {
+                    let parse_time = |time_str: &str| -> Option<i32> {
+                        let parts: Vec<&str> = time_str.split(':').collect();
+                        if parts.len() == 2 {
+                            let hours: i32 = parts[0].parse().ok()?;
+                            let minutes: i32 = parts[1].parse().ok()?;
+                            Some(hours * 60 + minutes)
+                        } else {
+                            None
+                        }
+                    };
+
+                    if let (Some(spent_mins), Some(total_mins)) =
+                        (parse_time(spent), parse_time(total))
+                    {
+                        let left_mins = total_mins - spent_mins;
+                        let sign = if left_mins < 0 { "-" } else { "" };
+                        let abs_minutes = left_mins.abs();
+                        let h = abs_minutes / 60;
+                        let m = abs_minutes % 60;
+                        let color = if left_mins < 0 {
+                            self.theme.bad
+                        } else {
+                            self.theme.good
+                        };
+                        header_spans
+                            .push(Span::from(format!("[{}{}:{:02}] ", sign, h, m)).fg(color));
+                    }
+                }
+
+                header_spans.push(Span::from(format!("{} - {}", t.id, t.name)));
+
+                match (&t.time_spent, &t.time_total) {
+                    (Some(s), Some(total)) => {
+                        header_spans.push(Span::from(" ["));
+                        header_spans.push(Span::from(s.to_string()).fg(Color::Green));
+                        header_spans.push(Span::from(" / "));
+                        header_spans.push(Span::from(total.to_string()).fg(Color::Blue));
+                        header_spans.push(Span::from("]"));
+                    }
+                    (Some(s), None) => {
+                        header_spans.push(Span::from(" ["));
+                        header_spans.push(Span::from(s.to_string()).fg(Color::Green));
+                        header_spans.push(Span::from("]"));
+                    }
+                    _ => {}
+                }
+
+                let header = Line::from(header_spans);
+
+                if self.show_task_url {
+                    if let Some(prefix) = &self.task_url_prefix {
+                        let url = format!("{}{}", prefix, t.id);
+                        let lines = vec![header, Line::from(Span::from(url).fg(Color::Blue))];
+                        ListItem::new(lines)
+                    } else {
+                        ListItem::new(header)
+                    }
+                } else {
+                    ListItem::new(header)
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.theme.block().title("Select Task"))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.task_popup_state);
+    }
+
+    /// Checkpoints matching the search query
+    fn draw_search(&mut self, frame: &mut Frame) {
+        let area = centered_rect(60, 80, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .search_results
+            .iter()
+            .map(|ch| {
+                ListItem::new(Line::from(vec![
+                    Span::from(format!("{} ", self.settings.locale.date_time(ch.time))),
+                    Span::from(ch.project.as_deref().unwrap_or("-").to_string()).bold(),
+                    Span::from(" "),
+                    Span::from(ch.message.as_deref().unwrap_or("")).fg(self.theme.comment),
+                ]))
+            })
+            .collect();
+        let title = format!("Search results ({})", self.search_results.len());
+        let list = List::new(items)
+            .block(self.theme.block().title(title))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.search_popup_state);
+    }
+
+    /// Unregistered spans across all dates
+    fn draw_review(&mut self, frame: &mut Frame) {
+        let area = centered_rect(80, 80, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .review_items
+            .iter()
+            .map(|(ch, minutes)| {
+                ListItem::new(Line::from(vec![
+                    Span::from(format!(
+                        "{} {} ",
+                        ch.time.format("%a"),
+                        self.settings.locale.date_time(ch.time)
+                    )),
+                    Span::from(format!("({}) ", self.settings.duration(*minutes)))
+                        .fg(self.theme.accent),
+                    Span::from(ch.project.as_deref().unwrap_or("-").to_string()).bold(),
+                    Span::from(" "),
+                    Span::from(ch.message.as_deref().unwrap_or("")).fg(self.theme.comment),
+                ]))
+            })
+            .collect();
+        let total: u32 = self.review_items.iter().map(|(_, minutes)| minutes).sum();
+        let title = format!(
+            "Unregistered ({}, {}) - <enter> go to | r register | <esc> close",
+            self.review_items.len(),
+            self.settings.duration(total)
+        );
+        let list = List::new(items)
+            .block(self.theme.block().title(title))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.review_state);
+    }
+
+    /// Message templates from the config
+    fn draw_templates(&mut self, frame: &mut Frame) {
+        let area = centered_rect(40, 50, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .message_templates
+            .iter()
+            .enumerate()
+            .map(|(idx, template)| {
+                let key = if idx < 9 {
+                    format!("{} ", idx + 1)
+                } else {
+                    "  ".to_string()
+                };
+                ListItem::new(Line::from(vec![
+                    Span::from(key).fg(self.theme.help),
+                    Span::from(template.as_str()).fg(self.theme.comment),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.theme.block().title("Message Templates"))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.template_popup_state);
+    }
+
+    /// Commits made during the selected span with their time and repository
+    fn draw_commits(&mut self, frame: &mut Frame) {
+        let area = centered_rect(60, 50, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .commits
+            .iter()
+            .map(|commit| {
+                ListItem::new(Line::from(vec![
+                    Span::from(format!(
+                        "{} ",
+                        self.settings.locale.time(commit.time.time())
+                    )),
+                    Span::from(format!("{} ", commit.repository)).fg(self.theme.help),
+                    Span::from(commit.subject.as_str()).fg(self.theme.comment),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.theme.block().title("Commits"))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.commit_popup_state);
+    }
+
+    /// Meetings of the calendar, those tracked already are dimmed
+    fn draw_meetings(&mut self, frame: &mut Frame) {
+        let area = centered_rect(60, 50, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = self
+            .meetings
+            .iter()
+            .map(|meeting| {
+                let line = Line::from(vec![
+                    Span::from(format!(
+                        "{}-{} ",
+                        self.settings.locale.time(meeting.start.time()),
+                        self.settings.locale.time(meeting.end.time())
+                    )),
+                    Span::from(meeting.title.as_str()).fg(self.theme.comment),
+                ]);
+                match self.meeting_checkpoints(meeting) {
+                    Some(_) => ListItem::new(line),
+                    None => ListItem::new(line.dim()),
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(
+                self.theme
+                    .block()
+                    .title("Meetings")
+                    .title_bottom(Line::from(" Enter: add | a: add all ").fg(self.theme.help)),
+            )
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.meeting_popup_state);
+    }
+
+    fn draw_palette(&mut self, frame: &mut Frame) {
+        let area = centered_rect(50, 50, frame.area());
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(area);
+
+        let width = input_area.width.max(3) - 3;
+        let scroll = self.palette_input.visual_scroll(width as usize);
+        let input = Paragraph::new(self.palette_input.value())
+            .scroll((0, scroll as u16))
+            .block(self.theme.block().title("Command"));
+
+        let items: Vec<ListItem> = matching_commands(self.palette_input.value())
+            .into_iter()
+            .map(|command| {
+                let key = command
+                    .binding()
+                    .map(|binding| format!(" {}", binding.label()))
+                    .unwrap_or_default();
+                ListItem::new(Line::from(vec![
+                    Span::from(command.name()),
+                    Span::from(key).fg(self.theme.help),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.theme.block())
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(input, input_area);
+        frame.render_stateful_widget(list, list_area, &mut self.palette_state);
+        let cursor = self.palette_input.visual_cursor().max(scroll) - scroll;
+        frame.set_cursor_position((input_area.x + cursor as u16 + 1, input_area.y + 1));
+    }
+
+    fn draw_projects(&mut self, frame: &mut Frame) {
+        let area = centered_rect(40, 50, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = by_recency(&self.projects, &self.recent_projects)
+            .into_iter()
+            .map(|project| {
+                let key = match project.hotkey {
+                    Some(hotkey) => format!("{} ", hotkey),
+                    None => "  ".to_string(),
+                };
+                let mut spans = vec![Span::from(key).fg(self.theme.help)];
+                match &project.name {
+                    Some(name) => spans.extend([
+                        Span::from(name.as_str()),
+                        Span::from(format!(" {}", project.id)).fg(self.theme.help),
+                    ]),
+                    None => spans.push(Span::from(project.id.as_str())),
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.theme.block().title("Projects"))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.project_popup_state);
+    }
+
+    fn draw_activities(&mut self, frame: &mut Frame) {
+        let Some(project) = &self.activity_project else {
+            return;
+        };
+        let area = centered_rect(40, 50, frame.area());
+        frame.render_widget(Clear, area);
+        let items: Vec<ListItem> = std::iter::once("no activity")
+            .chain(project.activities.iter().map(String::as_str))
+            .enumerate()
+            .map(|(idx, activity)| {
+                ListItem::new(Line::from(vec![
+                    Span::from(format!("{} ", idx)).fg(self.theme.help),
+                    Span::from(activity.to_string()),
+                ]))
+            })
+            .collect();
+        let title = format!(
+            "Activity of {}",
+            project.name.as_deref().unwrap_or(&project.id)
+        );
+        let list = List::new(items)
+            .block(self.theme.block().title(title))
+            .highlight_style(Style::default().fg(self.theme.accent))
+            .highlight_symbol(self.theme.symbols.highlight);
+
+        frame.render_stateful_widget(list, area, &mut self.activity_popup_state);
+    }
+
+    fn draw_notes(&self, frame: &mut Frame) {
+        let area = centered_rect(60, 50, frame.area());
+        let block = self
+            .theme
+            .block()
+            .title(format!(
+                "Notes {} {}",
+                self.note_date.format("%a"),
+                self.settings.locale.date(self.note_date)
+            ))
+            .title_bottom(Line::from(" Esc: save and close ").fg(self.theme.help));
+        let inner = block.inner(area);
+
+        // Keep the cursor in view
+        let (row, col) = self.note_editor.cursor();
+        let scroll = row.saturating_sub(inner.height.saturating_sub(1) as usize) as u16;
+        let lines: Vec<Line> = self
+            .note_editor
+            .lines()
+            .iter()
+            .map(|line| Line::from(line.as_str()))
+            .collect();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)).block(block), area);
+        frame.set_cursor_position((
+            inner.x + (col as u16).min(inner.width.saturating_sub(1)),
+            inner.y + row as u16 - scroll,
+        ));
+    }
+
+    /// Explains the problems with the spans of the selected day and how to fix them
+    fn draw_warnings(&self, frame: &mut Frame) {
+        let area = centered_rect(60, 40, frame.area());
+        let checkpoints = self.week.active_day();
+        let mut lines: Vec<Line> = self
+            .settings
+            .rounding
+            .spans(checkpoints)
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, span)| {
+                let issue = span.issue?;
+                Some(Line::from(vec![
+                    Span::from(format!(
+                        "{}-{} ",
+                        self.settings.locale.time(checkpoints[idx].time.time()),
+                        self.settings.locale.time(checkpoints[idx + 1].time.time())
+                    ))
+                    .bold(),
+                    Span::from(issue.description()).fg(self.theme.bad),
+                    Span::from(", ").fg(self.theme.help),
+                    Span::from(issue.hint()).fg(self.theme.help),
+                ]))
+            })
+            .collect();
+        if lines.is_empty() {
+            lines.push(Line::from("No problems with the spans of this day").fg(self.theme.good));
+        }
+
+        frame.render_widget(Clear, area);
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true }).block(
+            self.theme
+                .block()
+                .title("Span Warnings")
+                .fg(self.theme.accent),
+        );
+        frame.render_widget(paragraph, area);
+    }
+}
@@ -0,0 +1,387 @@
+use std::time::Duration as StdDuration;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Result,
+};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::credentials;
+use crate::time::Time;
+
+const DEVICE_CODE_URL: &str = "https://oauth2.googleapis.com/device/code";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const CALENDARS_URL: &str = "https://www.googleapis.com/calendar/v3/calendars";
+const SCOPE: &str = "https://www.googleapis.com/auth/calendar.readonly";
+
+/// Google Calendar whose meetings are offered as checkpoints. The OAuth client has to be of
+/// the "TVs and Limited Input devices" type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    /// Id of the calendar, the primary one of the account when left out
+    #[serde(default = "default_calendar_id")]
+    pub calendar_id: String,
+    /// Project of the meetings, e.g. an internal meetings project
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+fn default_calendar_id() -> String {
+    "primary".to_string()
+}
+
+/// Timed event of the calendar, all-day events are no meetings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Meeting {
+    pub title: String,
+    pub start: Time,
+    pub end: Time,
+}
+
+/// Code the user enters at the verification URL to grant access to the calendar
+#[derive(Debug, Deserialize)]
+pub struct DeviceCode {
+    device_code: String,
+    pub user_code: String,
+    pub verification_url: String,
+    /// Seconds the code can be entered for
+    expires_in: u64,
+    /// Seconds to wait between polls for the token
+    interval: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
+}
+
+/// Starts the device flow, the code has to be entered by the user before [`wait_for_login`]
+/// returns
+pub async fn request_device_code(config: &CalendarConfig) -> Result<DeviceCode> {
+    let response = Client::new()
+        .post(DEVICE_CODE_URL)
+        .form(&[("client_id", config.client_id.as_str()), ("scope", SCOPE)])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!(
+            "Google refused the device code with {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        );
+    }
+    Ok(response.json().await?)
+}
+
+/// Polls until the user granted access and stores the refresh token of the profile in the keyring
+pub async fn wait_for_login(
+    config: &CalendarConfig,
+    code: &DeviceCode,
+    profile: Option<&str>,
+) -> Result<()> {
+    let client = Client::new();
+    let mut interval = StdDuration::from_secs(code.interval.max(1));
+    let mut waited = StdDuration::ZERO;
+    loop {
+        if waited.as_secs() > code.expires_in {
+            bail!("The code expired before access was granted");
+        }
+        tokio::time::sleep(interval).await;
+        waited += interval;
+        let token: TokenResponse = client
+            .post(TOKEN_URL)
+            .form(&[
+                ("client_id", config.client_id.as_str()),
+                ("client_secret", config.client_secret.as_str()),
+                ("device_code", code.device_code.as_str()),
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ])
+            .send()
+            .await?
+            .json()
+            .await?;
+        if let Some(refresh_token) = &token.refresh_token {
+            return credentials::save_password(&keyring_entry(profile), refresh_token)
+                .wrap_err("The token could not be stored in the keyring");
+        }
+        match token.error.as_deref() {
+            Some("authorization_pending") => {}
+            Some("slow_down") => interval += StdDuration::from_secs(5),
+            _ => bail!("Access was not granted: {}", token_error(&token)),
+        }
+    }
+}
+
+/// Forgets the refresh token of the profile, `false` when there was none
+pub fn logout(profile: Option<&str>) -> Result<bool> {
+    credentials::delete_password(&keyring_entry(profile))
+        .wrap_err("The keyring could not be reached")
+}
+
+/// Meetings of the day in the timezone in the calendar, ordered by their start
+pub async fn meetings(
+    config: &CalendarConfig,
+    date: NaiveDate,
+    timezone: Tz,
+    profile: Option<&str>,
+) -> Result<Vec<Meeting>> {
+    let refresh_token = credentials::load_password(&keyring_entry(profile))
+        .wrap_err("The keyring could not be reached")?
+        .ok_or_else(|| eyre!("Log in to Google Calendar with `tcheater calendar login` first"))?;
+    let client = Client::new();
+    let access_token = access_token(&client, config, &refresh_token).await?;
+
+    let day_start = |date: NaiveDate| {
+        timezone
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+            .ok_or_else(|| eyre!("{} has no midnight", date))
+    };
+    let (start, end) = (day_start(date)?, day_start(date + Days::new(1))?);
+    let response = client
+        .get(format!("{}/{}/events", CALENDARS_URL, config.calendar_id))
+        .bearer_auth(access_token)
+        .query(&[
+            ("timeMin", start.to_rfc3339()),
+            ("timeMax", end.to_rfc3339()),
+            ("singleEvents", "true".to_string()),
+            ("orderBy", "startTime".to_string()),
+        ])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        bail!(
+            "Google Calendar failed with {}: {}",
+            response.status(),
+            response.text().await.unwrap_or_default()
+        );
+    }
+    parse_events(&response.text().await?, timezone)
+}
+
+async fn access_token(
+    client: &Client,
+    config: &CalendarConfig,
+    refresh_token: &str,
+) -> Result<String> {
+    let token: TokenResponse = client
+        .post(TOKEN_URL)
+        .form(&[
+            ("client_id", config.client_id.as_str()),
+            ("client_secret", config.client_secret.as_str()),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ])
+        .send()
+        .await?
+        .json()
+        .await?;
+    let error = token_error(&token);
+    token
+        .access_token
+        .ok_or_else(|| eyre!("Google Calendar refused the token: {}", error))
+}
+
+fn token_error(token: &TokenResponse) -> String {
+    token
+        .error_description
+        .clone()
+        .or_else(|| token.error.clone())
+        .unwrap_or_else(|| "no token in the response".to_string())
+}
+
+/// The refresh token of each profile is kept separately
+fn keyring_entry(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("google-calendar-{}", profile),
+        None => "google-calendar".to_string(),
+    }
+}
+
+#[derive(Deserialize)]
+struct Events {
+    #[serde(default)]
+    items: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    status: Option<String>,
+    start: EventTime,
+    end: EventTime,
+    #[serde(default)]
+    attendees: Vec<Attendee>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EventTime {
+    /// Left out by all-day events
+    date_time: Option<DateTime<chrono::FixedOffset>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attendee {
+    #[serde(default, rename = "self")]
+    is_self: bool,
+    #[serde(default)]
+    response_status: Option<String>,
+}
+
+/// Meetings of the events list of the Calendar API. Cancelled and declined events and all-day
+/// events are left out.
+fn parse_events(json: &str, timezone: Tz) -> Result<Vec<Meeting>> {
+    let events: Events = serde_json::from_str(json)?;
+    Ok(events
+        .items
+        .into_iter()
+        .filter(|event| event.status.as_deref() != Some("cancelled"))
+        .filter(|event| {
+            !event.attendees.iter().any(|attendee| {
+                attendee.is_self && attendee.response_status.as_deref() == Some("declined")
+            })
+        })
+        .filter_map(|event| {
+            Some(Meeting {
+                title: event.summary.unwrap_or_else(|| "Meeting".to_string()),
+                start: event.start.date_time?.with_timezone(&timezone),
+                end: event.end.date_time?.with_timezone(&timezone),
+            })
+        })
+        .filter(|meeting| meeting.end > meeting.start)
+        .collect())
+}
+
+/// The checkpoints of the meeting among the existing ones of its day, ordered by time: a span
+/// with the title as its message, after which the span running before it continues or the
+/// work stops.
+///
+/// `None` when a checkpoint of the day lies within the meeting, it is tracked already.
+pub fn meeting_checkpoints(
+    meeting: &Meeting,
+    project: Option<&str>,
+    existing: &[Checkpoint],
+) -> Option<Vec<Checkpoint>> {
+    if existing
+        .iter()
+        .any(|ch| ch.time >= meeting.start && ch.time < meeting.end)
+    {
+        return None;
+    }
+
+    let mut start = Checkpoint {
+        time: meeting.start,
+        project: project.map(str::to_string),
+        ..Checkpoint::new()
+    };
+    start.set_message(meeting.title.clone());
+    let mut checkpoints = vec![start];
+    if existing.iter().any(|ch| ch.time == meeting.end) {
+        return Some(checkpoints);
+    }
+    let before = existing.iter().rev().find(|ch| ch.time < meeting.start);
+    let continues_after = existing.iter().any(|ch| ch.time > meeting.end);
+    let end = match before {
+        Some(before) if before.kind != CheckpointKind::Stop && continues_after => Checkpoint {
+            id: None,
+            time: meeting.end,
+            registered: false,
+            toggl_id: None,
+            ..before.clone()
+        },
+        _ => Checkpoint {
+            time: meeting.end,
+            kind: CheckpointKind::Stop,
+            ..Checkpoint::new()
+        },
+    };
+    checkpoints.push(end);
+    Some(checkpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    fn meeting(start: Time, end: Time) -> Meeting {
+        Meeting {
+            title: "Planning #sprint".to_string(),
+            start,
+            end,
+        }
+    }
+
+    #[test]
+    fn test_parse_events() {
+        let json = r#"{"items": [
+            {"summary": "Standup", "start": {"dateTime": "2025-01-06T09:00:00Z"},
+             "end": {"dateTime": "2025-01-06T09:15:00Z"}},
+            {"summary": "Holiday", "start": {"date": "2025-01-06"}, "end": {"date": "2025-01-07"}},
+            {"summary": "Cancelled", "status": "cancelled",
+             "start": {"dateTime": "2025-01-06T10:00:00Z"}, "end": {"dateTime": "2025-01-06T11:00:00Z"}},
+            {"summary": "Declined", "attendees": [{"self": true, "responseStatus": "declined"}],
+             "start": {"dateTime": "2025-01-06T12:00:00Z"}, "end": {"dateTime": "2025-01-06T13:00:00Z"}}
+        ]}"#;
+
+        let meetings = parse_events(json, chrono_tz::UTC).unwrap();
+
+        assert_eq!(meetings.len(), 1);
+        assert_eq!(meetings[0].title, "Standup");
+        assert_eq!(
+            meetings[0].end - meetings[0].start,
+            chrono::Duration::minutes(15)
+        );
+    }
+
+    #[test]
+    fn test_meeting_checkpoints() {
+        let work = Checkpoint {
+            time: at(9, 0),
+            project: Some("ABC-1".to_string()),
+            message: Some("importer".to_string()),
+            registered: true,
+            ..Checkpoint::new()
+        };
+        let stop = Checkpoint {
+            time: at(17, 0),
+            kind: CheckpointKind::Stop,
+            ..Checkpoint::new()
+        };
+        let existing = vec![work, stop];
+
+        let checkpoints =
+            meeting_checkpoints(&meeting(at(10, 0), at(11, 0)), Some("MEET"), &existing).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].project.as_deref(), Some("MEET"));
+        assert_eq!(checkpoints[0].message.as_deref(), Some("Planning #sprint"));
+        assert_eq!(checkpoints[0].tags, vec!["sprint"]);
+        // the interrupted work continues after the meeting
+        assert_eq!(checkpoints[1].time, at(11, 0));
+        assert_eq!(checkpoints[1].project.as_deref(), Some("ABC-1"));
+        assert!(!checkpoints[1].registered);
+
+        let checkpoints =
+            meeting_checkpoints(&meeting(at(17, 30), at(18, 0)), None, &existing).unwrap();
+        assert_eq!(checkpoints[1].kind, CheckpointKind::Stop);
+
+        let checkpoints =
+            meeting_checkpoints(&meeting(at(16, 0), at(17, 0)), None, &existing).unwrap();
+        assert_eq!(checkpoints.len(), 1);
+
+        assert!(meeting_checkpoints(&meeting(at(8, 30), at(9, 30)), None, &existing).is_none());
+    }
+}
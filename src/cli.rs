@@ -0,0 +1,399 @@
+use std::fmt;
+
+use ::firestore::errors::FirestoreError;
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use clap::{Args, Parser, Subcommand};
+use color_eyre::eyre::Report;
+use serde::Serialize;
+
+use crate::{profile, time};
+
+mod add;
+mod auth;
+mod calendar;
+mod completions;
+mod doctor;
+mod dump;
+mod edit;
+mod export;
+mod import;
+mod invoice;
+mod log;
+mod migrate;
+mod remind;
+mod report;
+mod status;
+mod submit;
+mod sync;
+mod toggl;
+mod verify;
+
+pub use add::{add, AddArgs};
+pub use auth::{auth, AuthArgs};
+pub use calendar::{calendar, CalendarArgs};
+pub use completions::{completions, CompletionsArgs, COMPLETE_VAR};
+pub use doctor::doctor;
+pub use edit::{edit, EditArgs};
+pub use export::{export, ExportArgs};
+pub use import::{import, ImportArgs};
+pub use invoice::{invoice, InvoiceArgs};
+pub use log::{log, LogArgs};
+pub use migrate::{migrate, MigrateArgs};
+pub use remind::{remind, RemindArgs};
+pub use report::{report, ReportArgs, ReportFormat};
+pub use status::{status, StatusArgs};
+pub use submit::{submit, SubmitArgs};
+pub use sync::{sync, SyncArgs};
+pub use verify::{verify, VerifyArgs};
+
+/// Track time in checkpoints and register it in PBS
+#[derive(Debug, Parser)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    /// Profile with its own config, projects and store, e.g. `personal` reads
+    /// config.personal.toml and projects.personal.toml
+    #[arg(long, global = true, value_parser = profile::parse_profile)]
+    pub profile: Option<String>,
+    /// Arguments of the week view when no command is given
+    #[command(flatten)]
+    pub tui: TuiArgs,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Open the week view, the default without a command
+    Tui(TuiArgs),
+    /// Add a checkpoint without opening the week view, e.g. from a global hotkey
+    Add(AddArgs),
+    /// Add a span by its start and end, e.g. `log 9:00-11:30 ABC-1 "fixed the importer"`
+    Log(LogArgs),
+    /// Edit the spans of a day as TOML in $EDITOR
+    Edit(EditArgs),
+    /// Print the tracked time per project over a week, a month or a range of days
+    Report(ReportArgs),
+    /// Write the tracked spans of a range of days to a file or the standard output
+    Export(ExportArgs),
+    /// Write a timesheet of a project's month as HTML or PDF, to attach to the invoice
+    Invoice(InvoiceArgs),
+    /// Store checkpoints read from a file
+    Import(ImportArgs),
+    /// Register the unregistered work spans of a range of days in PBS or Jira, e.g. from cron
+    Submit(SubmitArgs),
+    /// Check a range of days for incomplete, unregistered or missing time before submitting
+    Verify(VerifyArgs),
+    /// Print the running project and how long it runs, or idle, fast enough for a shell prompt
+    Status(StatusArgs),
+    /// Fail, or notify, when nothing was tracked for a while during working time, e.g. from cron
+    Remind(RemindArgs),
+    /// Store the PBS password in the keyring of the system instead of config.toml
+    Auth(AuthArgs),
+    /// Log in to Google Calendar and add its meetings as spans
+    Calendar(CalendarArgs),
+    /// Push the work spans of a range of days to Toggl Track and pull in the entries started
+    /// there, e.g. on the phone
+    Sync(SyncArgs),
+    /// Check the configuration, the connection to the store and the PBS login
+    Doctor,
+    /// Store the times of old checkpoints in UTC like the new ones, once after upgrading
+    Migrate(MigrateArgs),
+    /// Print the script completing commands and project ids in bash, zsh or fish
+    Completions(CompletionsArgs),
+}
+
+#[derive(Debug, Default, Clone, Args)]
+pub struct TuiArgs {
+    /// Month whose weeks are loaded, 1 to 12, the current one when left out
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=12))]
+    pub month: Option<u32>,
+    /// Year of the month, the current one when left out
+    pub year: Option<i32>,
+    /// Month like 2024-12, instead of the month and year arguments
+    #[arg(long = "month", id = "year_month", value_name = "YYYY-MM", value_parser = parse_month,
+          conflicts_with_all = ["month", "year"])]
+    pub year_month: Option<NaiveDate>,
+    /// Date to open on, its month is loaded unless another one is given
+    #[arg(long, value_parser = parse_date)]
+    pub date: Option<NaiveDate>,
+    /// Project whose spans stay highlighted while the others are dimmed
+    #[arg(long, add = completions::project_ids())]
+    pub project: Option<String>,
+}
+
+impl TuiArgs {
+    /// Year and month whose weeks are loaded
+    pub fn year_and_month(&self, today: NaiveDate) -> (i32, u32) {
+        let today = self.date.unwrap_or(today);
+        match self.year_month {
+            Some(first) => (first.year(), first.month()),
+            None => (
+                self.year.unwrap_or(today.year()),
+                self.month.unwrap_or(today.month()),
+            ),
+        }
+    }
+}
+
+impl Cli {
+    /// The command to run, the week view when none was given
+    pub fn into_command(self) -> Command {
+        self.command.unwrap_or(Command::Tui(self.tui))
+    }
+}
+
+/// Why a command failed, told by its exit code. Clap exits with 2 on invalid arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Failure {
+    /// Anything else, e.g. a file that can't be read
+    Other,
+    /// The store could not be read or written
+    Store,
+    /// PBS or Jira refused the login or some of the entries
+    Pbs,
+    /// The command would change a locked day
+    Locked,
+    /// `verify` found issues
+    Unverified,
+    /// `remind` found no recent checkpoint
+    Untracked,
+}
+
+impl Failure {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Failure::Other => 1,
+            Failure::Store => 3,
+            Failure::Pbs => 4,
+            Failure::Locked => 5,
+            Failure::Unverified => 6,
+            Failure::Untracked => 7,
+        }
+    }
+
+    /// The failure of a command's error, errors of the store count as [`Failure::Store`]
+    pub fn of(err: &Report) -> Failure {
+        if let Some(failed) = err.downcast_ref::<Failed>() {
+            failed.failure
+        } else if err.downcast_ref::<FirestoreError>().is_some() {
+            Failure::Store
+        } else {
+            Failure::Other
+        }
+    }
+}
+
+/// Error of a command with the failure it exits with
+#[derive(Debug)]
+pub struct Failed {
+    pub failure: Failure,
+    pub message: String,
+}
+
+impl fmt::Display for Failed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failed {}
+
+fn failed(failure: Failure, message: impl Into<String>) -> Report {
+    Report::new(Failed {
+        failure,
+        message: message.into(),
+    })
+}
+
+/// Prints the output of a command given `--json`
+fn print_json(value: &impl Serialize) -> color_eyre::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+fn parse_time(input: &str) -> Result<NaiveTime, String> {
+    time::parse_hh_mm(input).ok_or_else(|| format!("`{}` is not a time like 9:00", input))
+}
+
+fn parse_date(input: &str) -> Result<NaiveDate, String> {
+    time::parse_date(input).ok_or_else(|| format!("`{}` is not a date like 2025-01-31", input))
+}
+
+/// First day of a month like `2025-01`
+fn parse_month(input: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", input.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("`{}` is not a month like 2025-01", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Command {
+        Cli::try_parse_from(["tcheater"].iter().chain(args))
+            .unwrap()
+            .into_command()
+    }
+
+    fn tui_month(args: &[&str]) -> (Option<u32>, Option<i32>) {
+        let args = tui_args(args);
+        (args.month, args.year)
+    }
+
+    fn tui_args(args: &[&str]) -> TuiArgs {
+        match parse(args) {
+            Command::Tui(args) => args,
+            command => panic!("{:?} is not the week view", command),
+        }
+    }
+
+    #[test]
+    fn test_week_view_is_the_default() {
+        assert_eq!(tui_month(&["3", "2025"]), (Some(3), Some(2025)));
+        assert_eq!(tui_month(&["tui", "12"]), (Some(12), None));
+        assert_eq!(tui_month(&[]), (None, None));
+
+        assert!(Cli::try_parse_from(["tcheater", "13"]).is_err());
+    }
+
+    #[test]
+    fn test_week_view_month() {
+        let today = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
+        assert_eq!(
+            tui_args(&["--month", "2026-01"]).year_and_month(today),
+            (2026, 1)
+        );
+        assert_eq!(tui_args(&["1", "2026"]).year_and_month(today), (2026, 1));
+        assert_eq!(tui_args(&["3"]).year_and_month(today), (2025, 3));
+        assert_eq!(tui_args(&[]).year_and_month(today), (2025, 12));
+
+        assert!(Cli::try_parse_from(["tcheater", "--month", "2026-01", "3"]).is_err());
+    }
+
+    #[test]
+    fn test_week_view_filters() {
+        let today = NaiveDate::from_ymd_opt(2025, 12, 15).unwrap();
+        let args = tui_args(&["--date", "2025-01-15", "--project", "ABC-1"]);
+
+        assert_eq!(args.date, NaiveDate::from_ymd_opt(2025, 1, 15));
+        assert_eq!(args.project.as_deref(), Some("ABC-1"));
+        assert_eq!(args.year_and_month(today), (2025, 1));
+        assert_eq!(
+            tui_args(&["tui", "--date", "2025-01-15", "3"]).year_and_month(today),
+            (2025, 3)
+        );
+    }
+
+    #[test]
+    fn test_add_args() {
+        let Command::Add(args) = parse(&["add", "--at", "9:00", "-p", "ABC-1", "-m", "deploy"])
+        else {
+            panic!("not the add command");
+        };
+        assert_eq!(args.at, chrono::NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(args.project.as_deref(), Some("ABC-1"));
+        assert_eq!(args.message.as_deref(), Some("deploy"));
+    }
+
+    #[test]
+    fn test_completions_args() {
+        let Command::Completions(args) = parse(&["completions", "zsh"]) else {
+            panic!("not the completions command");
+        };
+        assert_eq!(args.shell, completions::Shell::Zsh);
+        assert!(Cli::try_parse_from(["tcheater", "completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn test_profile() {
+        let cli = Cli::try_parse_from(["tcheater", "--profile", "personal", "3"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("personal"));
+        let cli = Cli::try_parse_from(["tcheater", "status", "--profile", "personal"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("personal"));
+        assert!(Cli::try_parse_from(["tcheater", "--profile", "a/b"]).is_err());
+    }
+
+    #[test]
+    fn test_auth_args() {
+        let Command::Auth(args) = parse(&["auth", "set"]) else {
+            panic!("not the auth command");
+        };
+        assert!(matches!(args.command, auth::AuthCommand::Set));
+        assert!(Cli::try_parse_from(["tcheater", "auth"]).is_err());
+    }
+
+    #[test]
+    fn test_calendar_args() {
+        let Command::Calendar(args) = parse(&["calendar", "import", "--date", "2025-01-06"]) else {
+            panic!("not the calendar command");
+        };
+        let calendar::CalendarCommand::Import(import) = args.command else {
+            panic!("not the import");
+        };
+        assert_eq!(import.date, NaiveDate::from_ymd_opt(2025, 1, 6));
+        assert!(!import.dry_run);
+    }
+
+    #[test]
+    fn test_report_args() {
+        let Command::Report(args) = parse(&["report", "--month", "2024-12", "--by-day"]) else {
+            panic!("not the report command");
+        };
+        assert_eq!(args.month, Some(NaiveDate::from_ymd_opt(2024, 12, 1)));
+        assert!(args.by_day);
+
+        let Command::Report(args) = parse(&["report", "--week"]) else {
+            panic!("not the report command");
+        };
+        assert_eq!(args.week, Some(None));
+        assert_eq!(args.format(), ReportFormat::Text);
+
+        let Command::Report(args) = parse(&["report", "--format", "md"]) else {
+            panic!("not the report command");
+        };
+        assert_eq!(args.format(), ReportFormat::Md);
+        let Command::Report(args) = parse(&["report", "--json"]) else {
+            panic!("not the report command");
+        };
+        assert_eq!(args.format(), ReportFormat::Json);
+        assert!(Cli::try_parse_from(["tcheater", "report", "--json", "--format", "md"]).is_err());
+
+        let conflicting = ["tcheater", "report", "--week", "--from", "2025-01-01"];
+        assert!(Cli::try_parse_from(conflicting).is_err());
+        assert!(parse_month("2024-13").is_err());
+    }
+
+    #[test]
+    fn test_export_args() {
+        let Command::Export(args) =
+            parse(&["export", "csv", "--from", "2025-01-01", "-o", "a.csv"])
+        else {
+            panic!("not the export command");
+        };
+        let export::ExportFormat::Csv(range) = args.format else {
+            panic!("not the csv export");
+        };
+        assert_eq!(range.from, NaiveDate::from_ymd_opt(2025, 1, 1));
+        assert_eq!(range.output, Some("a.csv".into()));
+
+        assert!(Cli::try_parse_from(["tcheater", "export"]).is_err());
+    }
+
+    #[test]
+    fn test_failures() {
+        let locked = failed(Failure::Locked, "2025-01-06 is locked");
+        assert_eq!(Failure::of(&locked), Failure::Locked);
+        assert_eq!(locked.to_string(), "2025-01-06 is locked");
+        assert_eq!(
+            Failure::of(&color_eyre::eyre::eyre!("oops")),
+            Failure::Other
+        );
+    }
+
+    #[test]
+    fn test_cli_is_consistent() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+}
@@ -0,0 +1,107 @@
+use ::firestore::FirestoreDb;
+use chrono::{NaiveTime, TimeZone};
+use clap::Args;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use uuid::Uuid;
+
+use super::completions::project_ids;
+use super::{failed, parse_time, Failure};
+use crate::app::Checkpoint;
+use crate::firestore::{find_locked_days, insert_checkpoint, refresh_tracking};
+use crate::time::{Time, TimeSettings};
+use crate::webhook::{self, Event, WebhookConfig};
+
+#[derive(Debug, Clone, Args)]
+pub struct AddArgs {
+    /// Time of the checkpoint today, e.g. 9:00, now when left out
+    #[arg(long, value_parser = parse_time)]
+    pub at: Option<NaiveTime>,
+    /// Project or PBS task id of the span the checkpoint starts
+    #[arg(long, short, add = project_ids())]
+    pub project: Option<String>,
+    /// Message of the span, the #tags in it are picked up
+    #[arg(long, short)]
+    pub message: Option<String>,
+}
+
+impl AddArgs {
+    /// The checkpoint to add when it is `now`
+    fn checkpoint(&self, now: Time) -> Option<Checkpoint> {
+        let time = match self.at {
+            Some(at) => now
+                .timezone()
+                .from_local_datetime(&now.date_naive().and_time(at))
+                .earliest()?,
+            None => now,
+        };
+        let mut checkpoint = Checkpoint {
+            id: Some(Uuid::new_v4().simple().to_string()),
+            time,
+            project: self.project.clone(),
+            ..Checkpoint::new()
+        };
+        if let Some(message) = &self.message {
+            checkpoint.set_message(message.clone());
+        }
+        Some(checkpoint)
+    }
+}
+
+/// Stores a checkpoint without opening the week view
+pub async fn add(
+    db: &FirestoreDb,
+    webhooks: &[WebhookConfig],
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: AddArgs,
+) -> Result<()> {
+    let checkpoint = args
+        .checkpoint(settings.now())
+        .ok_or_else(|| eyre!("The time does not exist today"))?;
+    let date = checkpoint.time.date_naive();
+    if find_locked_days(db).await?.contains(&date) {
+        return Err(failed(
+            Failure::Locked,
+            format!("{} is locked, unlock it in the week view first", date),
+        ));
+    }
+
+    let summary = format!(
+        "Added {} {} {}",
+        checkpoint.time.format("%a"),
+        settings.locale.date_time(checkpoint.time),
+        checkpoint.project_label().unwrap_or_default()
+    );
+    insert_checkpoint(db, checkpoint.clone()).await?;
+    refresh_tracking(db, &date, settings, profile).await?;
+    println!("{}", summary.trim_end());
+    for err in webhook::fire(webhooks, Event::Insert, &[checkpoint]).await {
+        eprintln!("{:#}", err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_checkpoint_from_args() {
+        let now = at(14, 0);
+        let args = AddArgs {
+            at: parse_time("9:30").ok(),
+            project: Some("ABC-1".to_string()),
+            message: Some("review #pr".to_string()),
+        };
+
+        let checkpoint = args.checkpoint(now).unwrap();
+
+        assert_eq!(checkpoint.time, now - chrono::Duration::minutes(270));
+        assert_eq!(checkpoint.project.as_deref(), Some("ABC-1"));
+        assert_eq!(checkpoint.tags, vec!["pr".to_string()]);
+        assert!(checkpoint.id.is_some());
+        assert!(parse_time("25:00").is_err());
+    }
+}
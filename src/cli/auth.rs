@@ -0,0 +1,54 @@
+use clap::{Args, Subcommand};
+use color_eyre::eyre::{bail, WrapErr};
+use color_eyre::Result;
+
+use super::{failed, Failure};
+use crate::credentials;
+use crate::pbs::{AuthConfig, Session};
+
+#[derive(Debug, Clone, Args)]
+pub struct AuthArgs {
+    #[command(subcommand)]
+    pub command: AuthCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum AuthCommand {
+    /// Ask for the PBS password, check it by logging in and store it in the keyring
+    Set,
+    /// Remove the PBS password from the keyring
+    Delete,
+}
+
+/// Keeps the PBS password of the username in config.toml in the keyring of the system
+pub async fn auth(config: &AuthConfig, args: AuthArgs) -> Result<()> {
+    let username = &config.username;
+    match args.command {
+        AuthCommand::Set => {
+            let password = rpassword::prompt_password(format!("PBS password of {}: ", username))?;
+            if password.is_empty() {
+                bail!("The password is empty, nothing was stored");
+            }
+            let auth = AuthConfig {
+                password: password.clone(),
+                ..config.clone()
+            };
+            Session::login(&auth)
+                .await
+                .map_err(|err| failed(Failure::Pbs, format!("PBS login failed: {}", err)))?;
+            credentials::save_password(username, &password)
+                .wrap_err("The password could not be stored in the keyring")?;
+            println!("The password of {} is stored in the keyring", username);
+        }
+        AuthCommand::Delete => {
+            let deleted = credentials::delete_password(username)
+                .wrap_err("The keyring could not be reached")?;
+            if deleted {
+                println!("The password of {} is removed from the keyring", username);
+            } else {
+                println!("No password of {} is in the keyring", username);
+            }
+        }
+    }
+    Ok(())
+}
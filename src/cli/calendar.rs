@@ -0,0 +1,128 @@
+use chrono::NaiveDate;
+use clap::{Args, Subcommand};
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use uuid::Uuid;
+
+use super::{failed, parse_date, Failure};
+use crate::calendar::{self, meeting_checkpoints, CalendarConfig, Meeting};
+use crate::config::Config;
+use crate::firestore::{
+    connect, find_checkpoints, find_locked_days, insert_checkpoint, refresh_tracking,
+};
+use crate::time::Locale;
+
+#[derive(Debug, Clone, Args)]
+pub struct CalendarArgs {
+    #[command(subcommand)]
+    pub command: CalendarCommand,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CalendarCommand {
+    /// Grant read access to the calendar of [calendar] in config.toml and keep the token in the
+    /// keyring
+    Login,
+    /// Remove the token from the keyring
+    Logout,
+    /// Add the meetings of a day as spans of the project of [calendar]
+    Import(ImportMeetingsArgs),
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ImportMeetingsArgs {
+    /// Day of the meetings, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub date: Option<NaiveDate>,
+    /// Only print the meetings that would be added
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Logs in to Google Calendar or imports its meetings
+pub async fn calendar(config: &Config, profile: Option<&str>, args: CalendarArgs) -> Result<()> {
+    let calendar = config
+        .calendar
+        .as_ref()
+        .ok_or_else(|| eyre!("No [calendar] in config.toml"))?;
+    match args.command {
+        CalendarCommand::Login => {
+            let code = calendar::request_device_code(calendar).await?;
+            println!(
+                "Open {} and enter the code {}",
+                code.verification_url, code.user_code
+            );
+            calendar::wait_for_login(calendar, &code, profile).await?;
+            println!("Access to the calendar is granted, the token is stored in the keyring");
+        }
+        CalendarCommand::Logout => {
+            if calendar::logout(profile)? {
+                println!("The token of the calendar is removed from the keyring");
+            } else {
+                println!("No token of the calendar is in the keyring");
+            }
+        }
+        CalendarCommand::Import(args) => import(config, calendar, profile, args).await?,
+    }
+    Ok(())
+}
+
+async fn import(
+    config: &Config,
+    calendar: &CalendarConfig,
+    profile: Option<&str>,
+    args: ImportMeetingsArgs,
+) -> Result<()> {
+    let settings = config.time_settings();
+    let date = args.date.unwrap_or_else(|| settings.now().date_naive());
+    let db = connect(&config.firestore)
+        .await
+        .map_err(|err| failed(Failure::Store, format!("Failed to connect: {}", err)))?;
+    if find_locked_days(&db).await?.contains(&date) {
+        return Err(failed(
+            Failure::Locked,
+            format!("{} is locked, unlock it in the week view first", date),
+        ));
+    }
+    let meetings = calendar::meetings(calendar, date, settings.timezone, profile).await?;
+    if meetings.is_empty() {
+        println!("No meetings on {}", settings.locale.date(date));
+        return Ok(());
+    }
+
+    let mut existing = find_checkpoints(&db, &date, settings.timezone).await?;
+    for meeting in meetings {
+        let Some(checkpoints) =
+            meeting_checkpoints(&meeting, calendar.project.as_deref(), &existing)
+        else {
+            println!(
+                "Skipped {}, it is tracked already",
+                describe(&meeting, &settings.locale)
+            );
+            continue;
+        };
+        println!("Added {}", describe(&meeting, &settings.locale));
+        for mut checkpoint in checkpoints {
+            checkpoint.id = Some(Uuid::new_v4().simple().to_string());
+            if !args.dry_run {
+                insert_checkpoint(&db, checkpoint.clone()).await?;
+            }
+            // The following meetings are placed among the added spans
+            existing.push(checkpoint);
+        }
+        existing.sort_by_key(|ch| ch.time);
+    }
+    if !args.dry_run {
+        refresh_tracking(&db, &date, &settings, profile).await?;
+    }
+    Ok(())
+}
+
+fn describe(meeting: &Meeting, locale: &Locale) -> String {
+    format!(
+        "{}-{} {}",
+        locale.time(meeting.start.time()),
+        locale.time(meeting.end.time()),
+        meeting.title
+    )
+}
@@ -0,0 +1,99 @@
+use std::io::Write;
+
+use clap::{Args, ValueEnum};
+use clap_complete::engine::{ArgValueCandidates, CompletionCandidate};
+use clap_complete::env::Shells;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use directories::UserDirs;
+
+use crate::profile;
+use crate::projects::{self, Project};
+
+/// Variable the registered script sets to ask the binary for completions
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+#[derive(Debug, Clone, Args)]
+pub struct CompletionsArgs {
+    /// Shell whose completion script is printed
+    pub shell: Shell,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Prints the script registering the completions in the shell, e.g.
+/// `source <(tcheater completions bash)` in `.bashrc`.
+///
+/// The script asks the binary for the candidates on every <Tab>, so the project ids are read
+/// from `projects.toml` as it is at that moment.
+pub fn completions(args: CompletionsArgs) -> Result<()> {
+    let name = args.shell.to_possible_value().expect("no shell is skipped");
+    let shells = Shells::builtins();
+    let shell = shells
+        .completer(name.get_name())
+        .ok_or_else(|| eyre!("{} is not supported", name.get_name()))?;
+    let bin = env!("CARGO_PKG_NAME");
+    let mut out = std::io::stdout().lock();
+    shell.write_registration(COMPLETE_VAR, bin, bin, bin, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Completes the ids of the projects in `projects.toml` of the home directory
+pub fn project_ids() -> ArgValueCandidates {
+    ArgValueCandidates::new(|| {
+        let projects = UserDirs::new()
+            .and_then(|dirs| {
+                let path = profile::path_in(dirs.home_dir(), None, "projects.toml");
+                projects::load_projects(path).ok()
+            })
+            .unwrap_or_default();
+        candidates(&projects)
+    })
+}
+
+fn candidates(projects: &[Project]) -> Vec<CompletionCandidate> {
+    projects
+        .iter()
+        .map(|project| {
+            CompletionCandidate::new(&project.id)
+                .help(project.name.clone().map(Into::into))
+                .hide(project.archived)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_candidates() {
+        let projects = projects::parse_projects(
+            "[[projects]]\nid = \"ABC-1\"\nname = \"Importer\"\n[[projects]]\nid = \"XYZ-2\"",
+        )
+        .unwrap();
+
+        let candidates: Vec<_> = candidates(&projects)
+            .iter()
+            .map(|candidate| {
+                (
+                    candidate.get_value().to_string_lossy().into_owned(),
+                    candidate.get_help().map(ToString::to_string),
+                )
+            })
+            .collect();
+        assert_eq!(
+            candidates,
+            vec![
+                ("ABC-1".to_string(), Some("Importer".to_string())),
+                ("XYZ-2".to_string(), None),
+            ]
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::path::Path;
+
+use color_eyre::Result;
+use directories::UserDirs;
+
+use super::{failed, Failure};
+use crate::config::{Config, FirestoreConfig};
+use crate::firestore::{connect, find_locked_days};
+use crate::pbs::Session;
+use crate::{holidays, profile, projects};
+
+/// Outcome of one check, with what to do about a failure
+struct Check {
+    name: &'static str,
+    result: Result<String, Problem>,
+}
+
+struct Problem {
+    error: String,
+    fix: String,
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            result: Ok(detail.into()),
+        }
+    }
+
+    fn failed(name: &'static str, error: impl ToString, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            result: Err(Problem {
+                error: error.to_string(),
+                fix: fix.into(),
+            }),
+        }
+    }
+}
+
+/// Checks the configuration files, the store and the PBS login, telling how to fix what fails
+pub async fn doctor(profile: Option<&str>) -> Result<()> {
+    let checks = run_checks(profile).await;
+    print!("{}", render(&checks));
+    let failures = checks.iter().filter(|check| check.result.is_err()).count();
+    if failures > 0 {
+        return Err(failed(
+            Failure::Other,
+            format!("{} of {} checks failed", failures, checks.len()),
+        ));
+    }
+    Ok(())
+}
+
+async fn run_checks(profile: Option<&str>) -> Vec<Check> {
+    let Some(user_dirs) = UserDirs::new() else {
+        return vec![Check::failed(
+            "home directory",
+            "it could not be found",
+            "Set the HOME environment variable",
+        )];
+    };
+    let home_dir = user_dirs.home_dir();
+    let (config_check, config) =
+        check_config(&profile::path_in(home_dir, profile, "config.toml"), profile);
+    let mut checks = vec![
+        config_check,
+        check_projects(&profile::path_in(home_dir, profile, "projects.toml")),
+    ];
+    if let Some(config) = &config {
+        checks.push(check_holidays(config, home_dir));
+    }
+    let firestore = config
+        .as_ref()
+        .map_or_else(FirestoreConfig::default, |config| config.firestore.clone());
+    checks.push(check_store(&firestore).await);
+    if let Some(config) = &config {
+        checks.push(check_pbs(config).await);
+    }
+    checks
+}
+
+fn check_config(path: &Path, profile: Option<&str>) -> (Check, Option<Config>) {
+    if !path.exists() {
+        let check = Check::failed(
+            "config.toml",
+            format!("{} does not exist", path.display()),
+            "Copy config.toml of the repository to the home directory and fill in [auth]",
+        );
+        return (check, None);
+    }
+    match Config::from_toml_file(path, profile) {
+        Ok(config) => (
+            Check::ok("config.toml", path.display().to_string()),
+            Some(config),
+        ),
+        Err(err) => (
+            Check::failed(
+                "config.toml",
+                err,
+                "Compare the reported line with config.toml of the repository",
+            ),
+            None,
+        ),
+    }
+}
+
+fn check_projects(path: &Path) -> Check {
+    match projects::load_projects(path) {
+        Ok(projects) if projects.is_empty() => Check::ok(
+            "projects.toml",
+            "no projects, add [[projects]] to offer them in the project popup",
+        ),
+        Ok(projects) => {
+            let active = projects::active(&projects).count();
+            Check::ok(
+                "projects.toml",
+                format!("{} projects, {} archived", active, projects.len() - active),
+            )
+        }
+        Err(err) => Check::failed(
+            "projects.toml",
+            err,
+            "Every [[projects]] needs an id and hotkeys must be unique",
+        ),
+    }
+}
+
+fn check_holidays(config: &Config, home_dir: &Path) -> Check {
+    match holidays::load_holidays(&config.holidays, home_dir) {
+        Ok(_) => Check::ok("holidays", "loaded"),
+        Err(err) => Check::failed(
+            "holidays",
+            err,
+            "Check the file named in [holidays] of config.toml",
+        ),
+    }
+}
+
+async fn check_store(config: &FirestoreConfig) -> Check {
+    let fix = "Set GOOGLE_APPLICATION_CREDENTIALS to a service account key, \
+               or run `gcloud auth application-default login`";
+    let db = match connect(config).await {
+        Ok(db) => db,
+        Err(err) => return Check::failed("Firestore", err, fix),
+    };
+    match find_locked_days(&db).await {
+        Ok(_) => Check::ok(
+            "Firestore",
+            format!("connected to {}/{}", config.project_id, config.database_id),
+        ),
+        Err(err) => Check::failed("Firestore", err, fix),
+    }
+}
+
+async fn check_pbs(config: &Config) -> Check {
+    if config.auth.login_url.is_empty() {
+        return Check::ok("PBS", "not used, config.toml has no [auth]");
+    }
+    match Session::login(&config.auth).await {
+        Ok(_) => Check::ok("PBS", format!("logged in as {}", config.auth.username)),
+        Err(err) => Check::failed(
+            "PBS",
+            err,
+            "Check login_url and username in [auth] of config.toml, \
+             and store the password with `tcheater auth set`",
+        ),
+    }
+}
+
+fn render(checks: &[Check]) -> String {
+    let width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or_default();
+    let mut out = String::new();
+    for check in checks {
+        match &check.result {
+            Ok(detail) => out.push_str(&format!("ok    {:<width$}  {}\n", check.name, detail)),
+            Err(problem) => {
+                out.push_str(&format!(
+                    "FAIL  {:<width$}  {}\n",
+                    check.name, problem.error
+                ));
+                out.push_str(&format!("      {:<width$}  fix: {}\n", "", problem.fix));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_config() {
+        let (check, config) = check_config(Path::new("/nonexistent/config.toml"), None);
+
+        assert!(config.is_none());
+        assert_eq!(
+            render(&[check, Check::ok("PBS", "logged in as me")]),
+            [
+                "FAIL  config.toml  /nonexistent/config.toml does not exist",
+                "                   fix: Copy config.toml of the repository to the home \
+                 directory and fill in [auth]",
+                "ok    PBS          logged in as me",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
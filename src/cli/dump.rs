@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::app::Checkpoint;
+
+/// Raised when the meaning of a field changes, fields are only added within a version
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON of the checkpoints of the days `from` to `to`, written by `export json` and read by
+/// `import json`
+#[derive(Serialize, Deserialize)]
+pub struct Dump {
+    pub version: u32,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub checkpoints: Vec<Checkpoint>,
+}
+
+impl Dump {
+    pub fn new(from: NaiveDate, to: NaiveDate, checkpoints: Vec<Checkpoint>) -> Self {
+        Self {
+            version: SCHEMA_VERSION,
+            from,
+            to,
+            checkpoints,
+        }
+    }
+}
+
+/// Writes needed to bring the stored checkpoints in line with imported ones
+#[derive(Default)]
+pub struct ImportPlan {
+    pub inserts: Vec<Checkpoint>,
+    /// The imported checkpoint with the stored one it replaces
+    pub updates: Vec<(Checkpoint, Checkpoint)>,
+    pub unchanged: usize,
+}
+
+impl ImportPlan {
+    /// Imported checkpoints replace the stored ones of the same id, the rest is inserted
+    pub fn new(stored: &[Checkpoint], imported: Vec<Checkpoint>) -> Self {
+        let stored: HashMap<_, _> = stored
+            .iter()
+            .filter_map(|checkpoint| Some((checkpoint.id.as_deref()?, checkpoint)))
+            .collect();
+        let mut plan = ImportPlan::default();
+        for checkpoint in imported {
+            match checkpoint.id.as_deref().and_then(|id| stored.get(id)) {
+                Some(&old) if same(old, &checkpoint) => plan.unchanged += 1,
+                Some(&old) => plan.updates.push((checkpoint, old.clone())),
+                None => plan.inserts.push(checkpoint),
+            }
+        }
+        plan
+    }
+}
+
+/// Checkpoints are compared as they are written out
+fn same(a: &Checkpoint, b: &Checkpoint) -> bool {
+    serde_json::to_value(a).ok() == serde_json::to_value(b).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    fn with_id(id: &str, hour: u32, project: &str) -> Checkpoint {
+        Checkpoint {
+            id: Some(id.to_string()),
+            time: at(hour, 0),
+            project: Some(project.to_string()),
+            ..Checkpoint::new()
+        }
+    }
+
+    #[test]
+    fn test_dump_round_trip() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let dump = Dump::new(date, date, vec![with_id("a", 8, "ABC-1")]);
+
+        let json = serde_json::to_string(&dump).unwrap();
+        let read: Dump = serde_json::from_str(&json).unwrap();
+
+        assert!(json.contains(r#""version":1"#));
+        assert!(json.contains(r#""id":"a""#));
+        assert_eq!(read.from, date);
+        assert!(same(&read.checkpoints[0], &dump.checkpoints[0]));
+    }
+
+    #[test]
+    fn test_import_plan() {
+        let stored = vec![with_id("a", 8, "ABC-1"), with_id("b", 10, "ABC-2")];
+        let imported = vec![
+            with_id("a", 8, "ABC-1"),
+            with_id("b", 10, "ABC-3"),
+            with_id("c", 12, "ABC-3"),
+        ];
+
+        let plan = ImportPlan::new(&stored, imported);
+
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.updates.len(), 1);
+        assert_eq!(plan.updates[0].1.project.as_deref(), Some("ABC-2"));
+        assert_eq!(plan.inserts.len(), 1);
+        assert_eq!(plan.inserts[0].id.as_deref(), Some("c"));
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::BTreeSet;
+use std::io::BufRead;
+use std::process;
+
+use ::firestore::FirestoreDb;
+use chrono::{NaiveDate, TimeZone};
+use chrono_tz::Tz;
+use clap::Args;
+use color_eyre::eyre::{bail, eyre, WrapErr};
+use color_eyre::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::dump::ImportPlan;
+use super::{failed, parse_date, Failure};
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::firestore::{
+    delete_checkpoints, find_checkpoints, find_locked_days, insert_checkpoint, refresh_tracking,
+    update_checkpoints,
+};
+use crate::time::{parse_hh_mm, Time, TimeSettings};
+
+#[derive(Debug, Clone, Args)]
+pub struct EditArgs {
+    /// The day to edit
+    #[arg(value_parser = parse_date)]
+    pub date: NaiveDate,
+}
+
+/// The day as it is edited, a list of spans
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DayFile {
+    #[serde(default, rename = "span")]
+    spans: Vec<EditedSpan>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct EditedSpan {
+    /// Id of the stored checkpoint starting the span, left out for new spans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    /// `HH:MM`
+    start: String,
+    /// `HH:MM`, left out for the span still running at the end of the day
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    end: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    activity: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(default)]
+    kind: CheckpointKind,
+    #[serde(default)]
+    registered: bool,
+    #[serde(default = "default_billable")]
+    billable: bool,
+}
+
+fn default_billable() -> bool {
+    true
+}
+
+const HEADER: &str = "\
+# Spans of the day ordered by time. Times are HH:MM, the last span may have no end.
+# A span without an id is added, a removed span is deleted. kind is work, break or absence.
+";
+
+impl DayFile {
+    /// The spans of the ordered checkpoints of a day, stops only end them
+    fn new(checkpoints: &[Checkpoint]) -> Self {
+        let spans = checkpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, ch)| ch.kind != CheckpointKind::Stop)
+            .map(|(idx, ch)| EditedSpan {
+                id: ch.id.clone(),
+                start: ch.time.format("%H:%M").to_string(),
+                end: checkpoints
+                    .get(idx + 1)
+                    .map(|next| next.time.format("%H:%M").to_string()),
+                project: ch.project.clone(),
+                activity: ch.activity.clone(),
+                message: ch.message.clone(),
+                kind: ch.kind,
+                registered: ch.registered,
+                billable: ch.billable,
+            })
+            .collect();
+        Self { spans }
+    }
+
+    /// The checkpoints of the spans on the date, reusing the stops following the spans among
+    /// `existing` and keeping the Toggl entries of the spans. Times whose minute is unchanged
+    /// keep their seconds, so that an unchanged day is not written.
+    fn checkpoints(
+        &self,
+        date: NaiveDate,
+        timezone: Tz,
+        existing: &[Checkpoint],
+    ) -> Result<Vec<Checkpoint>> {
+        let at = |time: &str| -> Result<Time> {
+            let time = parse_hh_mm(time).ok_or_else(|| eyre!("`{}` is not a time", time))?;
+            timezone
+                .from_local_datetime(&date.and_time(time))
+                .earliest()
+                .ok_or_else(|| eyre!("{} does not exist", time))
+        };
+        let stored = |id: &Option<String>| {
+            id.as_ref()
+                .and_then(|id| existing.iter().position(|ch| ch.id.as_ref() == Some(id)))
+        };
+        let keep_seconds = |time: Time, stored: Option<&Checkpoint>| match stored {
+            Some(stored) if same_minute(stored.time, time) => stored.time,
+            _ => time,
+        };
+        let mut checkpoints: Vec<Checkpoint> = vec![];
+        for (idx, span) in self.spans.iter().enumerate() {
+            let start = at(&span.start)?;
+            if span.kind == CheckpointKind::Stop {
+                bail!("The span at {} is a stop, remove it instead", span.start);
+            }
+            if checkpoints
+                .last()
+                .is_some_and(|last| last.time > start && !same_minute(last.time, start))
+            {
+                bail!(
+                    "The span at {} starts before the previous one ends",
+                    span.start
+                );
+            }
+            let stored_idx = stored(&span.id);
+            let mut checkpoint = Checkpoint {
+                id: span.id.clone(),
+                time: keep_seconds(start, stored_idx.map(|idx| &existing[idx])),
+                project: span.project.clone().filter(|p| !p.is_empty()),
+                activity: span.activity.clone().filter(|a| !a.is_empty()),
+                registered: span.registered,
+                kind: span.kind,
+                billable: span.billable,
+                toggl_id: stored_idx.and_then(|idx| existing[idx].toggl_id),
+                ..Checkpoint::new()
+            };
+            if let Some(message) = span.message.clone().filter(|m| !m.is_empty()) {
+                checkpoint.set_message(message);
+            }
+            checkpoints.push(checkpoint);
+
+            let next_start = self.spans.get(idx + 1).map(|next| at(&next.start));
+            let end = match (&span.end, next_start) {
+                (Some(end), next) => Some((at(end)?, next.transpose()?)),
+                (None, None) => None,
+                (None, Some(_)) => bail!(
+                    "Only the last span may have no end, {} has none",
+                    span.start
+                ),
+            };
+            let Some((end, next_start)) = end else {
+                continue;
+            };
+            if end <= start {
+                bail!("The span at {} ends before it starts", span.start);
+            }
+            // A span ending before the next one starts stops the clock
+            if next_start != Some(end) {
+                let stop = stored_idx
+                    .and_then(|idx| existing.get(idx + 1))
+                    .filter(|next| next.kind == CheckpointKind::Stop);
+                checkpoints.push(Checkpoint {
+                    id: stop.and_then(|stop| stop.id.clone()),
+                    time: keep_seconds(end, stop),
+                    kind: CheckpointKind::Stop,
+                    ..Checkpoint::new()
+                });
+            }
+        }
+        Ok(checkpoints)
+    }
+}
+
+/// Both times are within the same minute, as the day file writes them
+fn same_minute(a: Time, b: Time) -> bool {
+    a.timestamp().div_euclid(60) == b.timestamp().div_euclid(60)
+}
+
+/// Opens the day as TOML in `$VISUAL` or `$EDITOR` and stores the changes
+pub async fn edit(
+    db: &FirestoreDb,
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: EditArgs,
+) -> Result<()> {
+    let date = args.date;
+    if find_locked_days(db).await?.contains(&date) {
+        return Err(failed(
+            Failure::Locked,
+            format!("{} is locked, unlock it in the week view first", date),
+        ));
+    }
+    let existing = find_checkpoints(db, &date, settings.timezone).await?;
+    let file = tempfile::Builder::new()
+        .prefix(&format!("tcheater-{}-", date))
+        .suffix(".toml")
+        .tempfile()?;
+    let path = file.path();
+    let content = format!("{}{}", HEADER, toml::to_string(&DayFile::new(&existing))?);
+    std::fs::write(path, content)?;
+
+    let edited = loop {
+        open_editor(path)?;
+        let content = std::fs::read_to_string(path)?;
+        let checkpoints = toml::from_str::<DayFile>(&content)
+            .wrap_err("The file is not valid")
+            .and_then(|file| file.checkpoints(date, settings.timezone, &existing));
+        match checkpoints {
+            Ok(checkpoints) => break checkpoints,
+            Err(err) => {
+                eprintln!("{:#}", err);
+                eprint!("Edit again? [Y/n] ");
+                let mut answer = String::new();
+                std::io::stdin().lock().read_line(&mut answer)?;
+                if answer.trim().eq_ignore_ascii_case("n") {
+                    bail!("Nothing was changed");
+                }
+            }
+        }
+    };
+    drop(file);
+
+    let kept: BTreeSet<_> = edited.iter().filter_map(|ch| ch.id.clone()).collect();
+    let deletes: Vec<_> = existing
+        .iter()
+        .filter(|ch| ch.id.as_ref().is_some_and(|id| !kept.contains(id)))
+        .cloned()
+        .collect();
+    let plan = ImportPlan::new(&existing, edited);
+    let updates: Vec<_> = plan.updates.into_iter().map(|(new, _)| new).collect();
+
+    if !deletes.is_empty() {
+        delete_checkpoints(db, &deletes).await?;
+    }
+    if !updates.is_empty() {
+        update_checkpoints(db, &updates).await?;
+    }
+    let inserted = plan.inserts.len();
+    for mut checkpoint in plan.inserts {
+        checkpoint
+            .id
+            .get_or_insert_with(|| Uuid::new_v4().simple().to_string());
+        insert_checkpoint(db, checkpoint).await?;
+    }
+    refresh_tracking(db, &date, settings, profile).await?;
+    println!(
+        "{}: {} added, {} changed, {} deleted",
+        date,
+        inserted,
+        updates.len(),
+        deletes.len()
+    );
+    Ok(())
+}
+
+fn open_editor(path: &std::path::Path) -> Result<()> {
+    let editor = std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string());
+    // The variable may carry arguments, e.g. `code --wait`
+    let mut words = editor.split_whitespace();
+    let program = words.next().ok_or_else(|| eyre!("$EDITOR is empty"))?;
+    let status = process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .wrap_err_with(|| format!("Could not start {}", program))?;
+    if !status.success() {
+        bail!("{} exited with {}", program, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_day_file_round_trip() {
+        // Checkpoints of the week view keep the seconds they were added at
+        let stored = |id: &str, hour, kind| Checkpoint {
+            id: Some(id.to_string()),
+            time: at(hour, 0) + chrono::Duration::seconds(17),
+            kind,
+            project: (kind == CheckpointKind::Work).then(|| "ABC-1".to_string()),
+            ..Checkpoint::new()
+        };
+        let day = vec![
+            stored("a", 8, CheckpointKind::Work),
+            stored("b", 12, CheckpointKind::Stop),
+            stored("c", 13, CheckpointKind::Work),
+        ];
+
+        let content = toml::to_string(&DayFile::new(&day)).unwrap();
+        assert!(content.contains("[[span]]\nid = \"a\"\nstart = \"08:00\"\nend = \"12:00\"\n"));
+
+        let edited = content.replace("end = \"12:00\"", "end = \"12:30\"");
+        let file: DayFile = toml::from_str(&edited).unwrap();
+        let checkpoints = file
+            .checkpoints(at(8, 0).date_naive(), at(8, 0).timezone(), &day)
+            .unwrap();
+        let summary: Vec<_> = checkpoints
+            .iter()
+            .map(|ch| (ch.id.as_deref(), ch.time, ch.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some("a"), day[0].time, CheckpointKind::Work),
+                (
+                    Some("b"),
+                    at(12, 0) + chrono::Duration::minutes(30),
+                    CheckpointKind::Stop
+                ),
+                (Some("c"), day[2].time, CheckpointKind::Work),
+            ]
+        );
+
+        let unchanged: DayFile = toml::from_str(&content).unwrap();
+        let checkpoints = unchanged
+            .checkpoints(at(8, 0).date_naive(), at(8, 0).timezone(), &day)
+            .unwrap();
+        let plan = ImportPlan::new(&day, checkpoints);
+        assert_eq!(plan.unchanged, 3);
+        assert!(plan.updates.is_empty() && plan.inserts.is_empty());
+    }
+
+    #[test]
+    fn test_invalid_day_file() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let file: DayFile = toml::from_str(
+            "[[span]]\nstart = \"10:00\"\n[[span]]\nstart = \"11:00\"\nend = \"12:00\"",
+        )
+        .unwrap();
+        assert!(file.checkpoints(date, chrono_tz::UTC, &[]).is_err());
+
+        let file: DayFile = toml::from_str("[[span]]\nstart = \"10:00\"\nend = \"9:00\"").unwrap();
+        assert!(file.checkpoints(date, chrono_tz::UTC, &[]).is_err());
+    }
+}
@@ -0,0 +1,435 @@
+use std::path::{Path, PathBuf};
+
+use ::firestore::FirestoreDb;
+use chrono::{Datelike, NaiveDate};
+use clap::{Args, Subcommand};
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::dump::Dump;
+use super::{parse_date, print_json};
+use crate::app::CheckpointKind;
+use crate::firestore::find_checkpoints_between;
+use crate::projects::{billing_code, find, Project};
+use crate::span::Span;
+use crate::time::{Time, TimeSettings};
+
+#[derive(Debug, Clone, Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ExportFormat {
+    /// One row per span, for spreadsheets and payroll imports
+    Csv(RangeArgs),
+    /// Calendar with an event per span, for reviewing the tracked time in a calendar app
+    Ical(RangeArgs),
+    /// Work spans in the CSV layout of Clockify's bulk import, its dates are YYYY-MM-DD
+    Clockify(RangeArgs),
+    /// Checkpoints with their ids in a versioned schema, readable by `import json`
+    Json(RangeArgs),
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct RangeArgs {
+    /// First exported day, the first of this month when left out
+    #[arg(long, value_parser = parse_date)]
+    pub from: Option<NaiveDate>,
+    /// Last exported day, inclusive, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub to: Option<NaiveDate>,
+    /// File to write, the standard output when left out
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+    /// Print a summary of the written file as JSON
+    #[arg(long, requires = "output")]
+    pub json: bool,
+}
+
+impl RangeArgs {
+    /// First and last exported day
+    pub fn period(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        let last = self.to.unwrap_or(today);
+        let first = self.from.unwrap_or_else(|| last.with_day(1).unwrap());
+        (first, last)
+    }
+
+    /// Writes the export to the output file or prints it
+    fn write(&self, content: &str) -> Result<()> {
+        match &self.output {
+            Some(path) => std::fs::write(path, content)?,
+            None => print!("{}", content),
+        }
+        Ok(())
+    }
+}
+
+impl ExportArgs {
+    pub fn json(&self) -> bool {
+        self.format.range().json
+    }
+}
+
+impl ExportFormat {
+    fn range(&self) -> &RangeArgs {
+        match self {
+            ExportFormat::Csv(range)
+            | ExportFormat::Ical(range)
+            | ExportFormat::Clockify(range)
+            | ExportFormat::Json(range) => range,
+        }
+    }
+}
+
+/// What `--json` prints once the file is written
+#[derive(Serialize)]
+struct ExportJson<'a> {
+    path: &'a Path,
+    from: NaiveDate,
+    to: NaiveDate,
+    /// Spans, or checkpoints of the JSON export
+    count: usize,
+}
+
+/// Writes the tracked spans of the period in the chosen format
+pub async fn export(
+    db: &FirestoreDb,
+    projects: &[Project],
+    settings: &TimeSettings,
+    args: ExportArgs,
+) -> Result<()> {
+    let range = args.format.range();
+    let (first, last) = range.period(settings.now().date_naive());
+    let checkpoints = find_checkpoints_between(db, &first, &last, settings.timezone).await?;
+    let (content, count) = match &args.format {
+        ExportFormat::Csv(_) => {
+            let spans = Span::from_days(&checkpoints, &settings.rounding);
+            (render_csv(&spans, projects)?, spans.len())
+        }
+        ExportFormat::Ical(_) => {
+            let spans = Span::from_days(&checkpoints, &settings.rounding);
+            (render_ical(&spans, projects, settings.now()), spans.len())
+        }
+        ExportFormat::Clockify(_) => {
+            let spans: Vec<_> = Span::from_days(&checkpoints, &settings.rounding)
+                .into_iter()
+                .filter(|span| span.kind == CheckpointKind::Work)
+                .collect();
+            (render_clockify(&spans, projects)?, spans.len())
+        }
+        ExportFormat::Json(_) => {
+            let count = checkpoints.len();
+            let dump = Dump::new(first, last, checkpoints);
+            (serde_json::to_string_pretty(&dump)? + "\n", count)
+        }
+    };
+    range.write(&content)?;
+
+    match (&range.output, range.json) {
+        (Some(path), true) => print_json(&ExportJson {
+            path,
+            from: first,
+            to: last,
+            count,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    date: String,
+    start: String,
+    end: String,
+    /// Rounded as in the week view, `H:MM`
+    duration: String,
+    project: &'a str,
+    billing_code: &'a str,
+    message: &'a str,
+    registered: bool,
+    kind: CheckpointKind,
+}
+
+fn render_csv(spans: &[Span], projects: &[Project]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for span in spans {
+        writer.serialize(CsvRow {
+            date: span.start.format("%Y-%m-%d").to_string(),
+            start: span.start.format("%H:%M").to_string(),
+            end: span.end.format("%H:%M").to_string(),
+            duration: format!("{}:{:02}", span.minutes / 60, span.minutes % 60),
+            project: span.project.as_deref().unwrap_or_default(),
+            billing_code: billing_code(projects, span.project.as_deref()).unwrap_or_default(),
+            message: span.message.as_deref().unwrap_or_default(),
+            registered: span.registered,
+            kind: span.kind,
+        })?;
+    }
+    // The header is written with the first row, an empty export still gets one
+    if spans.is_empty() {
+        writer.write_record([
+            "date",
+            "start",
+            "end",
+            "duration",
+            "project",
+            "billing_code",
+            "message",
+            "registered",
+            "kind",
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// A row of Clockify's bulk import, its columns are matched by these names
+#[derive(Serialize)]
+struct ClockifyRow<'a> {
+    #[serde(rename = "Project")]
+    project: &'a str,
+    #[serde(rename = "Description")]
+    description: &'a str,
+    #[serde(rename = "Start Date")]
+    start_date: String,
+    #[serde(rename = "Start Time")]
+    start_time: String,
+    #[serde(rename = "End Date")]
+    end_date: String,
+    #[serde(rename = "End Time")]
+    end_time: String,
+    /// `Yes` or `No`
+    #[serde(rename = "Billable")]
+    billable: &'static str,
+    #[serde(rename = "Tags")]
+    tags: String,
+}
+
+/// The spans under the names of their projects, Clockify creates the projects it doesn't know
+fn render_clockify(spans: &[Span], projects: &[Project]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for span in spans {
+        let project = span.project.as_deref().unwrap_or_default();
+        writer.serialize(ClockifyRow {
+            project: find(projects, project)
+                .and_then(|found| found.name.as_deref())
+                .unwrap_or(project),
+            description: span.message.as_deref().unwrap_or_default(),
+            start_date: span.start.format("%Y-%m-%d").to_string(),
+            start_time: span.start.format("%H:%M").to_string(),
+            end_date: span.end.format("%Y-%m-%d").to_string(),
+            end_time: span.end.format("%H:%M").to_string(),
+            billable: if span.billable { "Yes" } else { "No" },
+            tags: span.tags.join(", "),
+        })?;
+    }
+    if spans.is_empty() {
+        writer.write_record([
+            "Project",
+            "Description",
+            "Start Date",
+            "Start Time",
+            "End Date",
+            "End Time",
+            "Billable",
+            "Tags",
+        ])?;
+    }
+    Ok(String::from_utf8(writer.into_inner()?)?)
+}
+
+/// An iCalendar file, `stamp` is when it was created. The billing codes are the categories.
+fn render_ical(spans: &[Span], projects: &[Project], stamp: Time) -> String {
+    let utc = |time: Time| time.to_utc().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//tcheater//tcheater//EN".to_string(),
+    ];
+    for span in spans {
+        let summary = match &span.project {
+            Some(project) if !project.is_empty() => project.clone(),
+            _ => format!("{:?}", span.kind),
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            // A span starts where its checkpoint is, no two of them start at once
+            format!("UID:{}@tcheater", utc(span.start)),
+            format!("DTSTAMP:{}", utc(stamp)),
+            format!("DTSTART:{}", utc(span.start)),
+            format!("DTEND:{}", utc(span.end)),
+            format!("SUMMARY:{}", escape_text(&summary)),
+        ]);
+        if let Some(message) = span.message.as_deref().filter(|m| !m.is_empty()) {
+            lines.push(format!("DESCRIPTION:{}", escape_text(message)));
+        }
+        if let Some(code) = billing_code(projects, span.project.as_deref()) {
+            lines.push(format!("CATEGORIES:{}", escape_text(code)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold_line(line) + "\r\n").collect()
+}
+
+/// Escapes a TEXT value of iCalendar
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a content line into lines of at most 75 bytes, continued by a leading space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Checkpoint;
+    use crate::projects::parse_projects;
+    use crate::time::test_util::{at, checkpoint, noted};
+    use crate::time::Rounding;
+
+    #[test]
+    fn test_render_csv() {
+        let checkpoints = vec![
+            noted(at(8, 0), CheckpointKind::Work, "ABC-1", "review, deploy"),
+            noted(at(9, 45), CheckpointKind::Break, "", ""),
+            noted(at(10, 0), CheckpointKind::Stop, "", ""),
+        ];
+
+        let projects =
+            parse_projects("[[projects]]\nid = \"ABC-1\"\nbilling_code = \"B-100\"").unwrap();
+
+        let csv = render_csv(
+            &Span::from_days(&checkpoints, &Rounding::DEFAULT),
+            &projects,
+        )
+        .unwrap();
+
+        assert_eq!(
+            csv,
+            [
+                "date,start,end,duration,project,billing_code,message,registered,kind",
+                "2025-01-06,08:00,09:45,1:45,ABC-1,B-100,\"review, deploy\",false,work",
+                "2025-01-06,09:45,10:00,0:15,,,,false,break",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render_csv(&[], &[]).unwrap().lines().next(),
+            csv.lines().next()
+        );
+    }
+
+    #[test]
+    fn test_render_clockify() {
+        let mut review = checkpoint(at(8, 0), CheckpointKind::Work, "ABC-1");
+        review.set_message("review #backend".to_string());
+        let checkpoints = vec![
+            review,
+            Checkpoint {
+                time: at(9, 45),
+                project: Some("internal".to_string()),
+                billable: false,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(10, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+        let projects = parse_projects("[[projects]]\nid = \"ABC-1\"\nname = \"Website\"").unwrap();
+
+        let csv = render_clockify(
+            &Span::from_days(&checkpoints, &Rounding::DEFAULT),
+            &projects,
+        )
+        .unwrap();
+
+        assert_eq!(
+            csv,
+            [
+                "Project,Description,Start Date,Start Time,End Date,End Time,Billable,Tags",
+                "Website,review #backend,2025-01-06,08:00,2025-01-06,09:45,Yes,backend",
+                "internal,,2025-01-06,09:45,2025-01-06,10:00,No,",
+                "",
+            ]
+            .join("\n")
+        );
+        assert_eq!(
+            render_clockify(&[], &[]).unwrap().lines().next(),
+            csv.lines().next()
+        );
+    }
+
+    #[test]
+    fn test_render_ical() {
+        let checkpoints = vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("ABC-1".to_string()),
+                message: Some("review; deploy, then\nrelease".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(9, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+
+        let ical = render_ical(
+            &Span::from_days(&checkpoints, &Rounding::DEFAULT),
+            &[],
+            at(8, 0),
+        );
+
+        let start = at(8, 0).to_utc().format("DTSTART:%Y%m%dT%H%M%SZ");
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ical.contains(&format!("\r\n{}\r\n", start)));
+        assert!(ical.contains("\r\nSUMMARY:ABC-1\r\n"));
+        assert!(ical.contains("\r\nDESCRIPTION:review\\; deploy\\, then\\nrelease\r\n"));
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 1);
+        assert!(ical.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("DESCRIPTION:{}", "x".repeat(100));
+        let folded = fold_line(&line);
+        let parts: Vec<_> = folded.split("\r\n").collect();
+        assert_eq!(parts[0].len(), 75);
+        assert_eq!(parts[1], format!(" {}", "x".repeat(37)));
+    }
+
+    #[test]
+    fn test_export_period() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let today = date(15);
+        assert_eq!(RangeArgs::default().period(today), (date(1), today));
+
+        let args = RangeArgs {
+            to: Some(date(20)),
+            ..RangeArgs::default()
+        };
+        assert_eq!(args.period(today), (date(1), date(20)));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use ::firestore::FirestoreDb;
+use chrono::NaiveDate;
+use clap::{Args, Subcommand};
+use color_eyre::eyre::{bail, WrapErr};
+use color_eyre::Result;
+use uuid::Uuid;
+
+use super::dump::{Dump, ImportPlan, SCHEMA_VERSION};
+use super::{failed, toggl, Failure};
+use crate::app::Checkpoint;
+use crate::firestore::{
+    find_checkpoints_between, find_locked_days, insert_checkpoint, refresh_tracking,
+    update_checkpoints,
+};
+use crate::projects::Project;
+use crate::time::TimeSettings;
+
+#[derive(Debug, Clone, Args)]
+pub struct ImportArgs {
+    #[command(subcommand)]
+    pub format: ImportFormat,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum ImportFormat {
+    /// Checkpoints written by `export json`, those with a stored id replace it
+    Json {
+        /// The exported file
+        file: PathBuf,
+    },
+    /// Time entries of Toggl's detailed report in CSV, the Toggl projects are mapped to project
+    /// ids on the way. Days which already have checkpoints are skipped.
+    Toggl {
+        /// The downloaded report
+        file: PathBuf,
+    },
+}
+
+/// Stores checkpoints read from a file
+pub async fn import(
+    db: &FirestoreDb,
+    projects: &[Project],
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: ImportArgs,
+) -> Result<()> {
+    match args.format {
+        ImportFormat::Json { file } => {
+            let content = read(&file)?;
+            let dump: Dump = serde_json::from_str(&content)
+                .wrap_err_with(|| format!("{} is not an export of tcheater", file.display()))?;
+            if dump.version > SCHEMA_VERSION {
+                bail!(
+                    "{} has schema version {}, this tcheater reads up to {}",
+                    file.display(),
+                    dump.version,
+                    SCHEMA_VERSION
+                );
+            }
+            import_json(db, settings, profile, dump).await
+        }
+        ImportFormat::Toggl { file } => {
+            let entries = toggl::parse_entries(&read(&file)?).wrap_err_with(|| {
+                format!("{} is not a detailed report of Toggl", file.display())
+            })?;
+            let project_ids = toggl::ask_project_ids(
+                &entries,
+                projects,
+                std::io::stdin().lock(),
+                std::io::stdout(),
+            )?;
+            let checkpoints = toggl::to_checkpoints(&entries, &project_ids, settings.timezone)?;
+            import_new_days(db, settings, profile, checkpoints).await
+        }
+    }
+}
+
+fn read(file: &Path) -> Result<String> {
+    std::fs::read_to_string(file).wrap_err_with(|| format!("Could not read {}", file.display()))
+}
+
+/// Fails on locked days among the dates
+async fn check_unlocked(db: &FirestoreDb, dates: &BTreeSet<NaiveDate>) -> Result<()> {
+    let locked: Vec<_> = find_locked_days(db)
+        .await?
+        .intersection(dates)
+        .map(|date| date.to_string())
+        .collect();
+    if !locked.is_empty() {
+        return Err(failed(
+            Failure::Locked,
+            format!(
+                "{} locked, unlock in the week view first",
+                locked.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Inserts the checkpoints of the days which have none yet, so that an import can be repeated
+async fn import_new_days(
+    db: &FirestoreDb,
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    checkpoints: Vec<Checkpoint>,
+) -> Result<()> {
+    let (Some(first), Some(last)) = (checkpoints.first(), checkpoints.last()) else {
+        println!("Nothing to import");
+        return Ok(());
+    };
+    let (first, last) = (first.time.date_naive(), last.time.date_naive());
+    let tracked: BTreeSet<_> = find_checkpoints_between(db, &first, &last, settings.timezone)
+        .await?
+        .iter()
+        .map(|checkpoint| checkpoint.time.date_naive())
+        .collect();
+    let (skipped, new): (Vec<_>, Vec<_>) = checkpoints
+        .into_iter()
+        .partition(|checkpoint| tracked.contains(&checkpoint.time.date_naive()));
+    let dates: BTreeSet<_> = new
+        .iter()
+        .map(|checkpoint| checkpoint.time.date_naive())
+        .collect();
+    check_unlocked(db, &dates).await?;
+
+    let inserted = new.len();
+    for mut checkpoint in new {
+        checkpoint.id = Some(Uuid::new_v4().simple().to_string());
+        insert_checkpoint(db, checkpoint).await?;
+    }
+    for date in &dates {
+        refresh_tracking(db, date, settings, profile).await?;
+    }
+
+    println!(
+        "Imported {} checkpoints over {} days",
+        inserted,
+        dates.len()
+    );
+    if !skipped.is_empty() {
+        let days: BTreeSet<_> = skipped
+            .iter()
+            .map(|checkpoint| checkpoint.time.date_naive().to_string())
+            .collect();
+        let days: Vec<_> = days.into_iter().collect();
+        println!("Skipped {} which already have checkpoints", days.join(", "));
+    }
+    Ok(())
+}
+
+async fn import_json(
+    db: &FirestoreDb,
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    dump: Dump,
+) -> Result<()> {
+    let stored = find_checkpoints_between(db, &dump.from, &dump.to, settings.timezone).await?;
+    let plan = ImportPlan::new(&stored, dump.checkpoints);
+
+    // Both the days the checkpoints move to and those they leave are written
+    let dates: BTreeSet<_> = plan
+        .inserts
+        .iter()
+        .chain(plan.updates.iter().flat_map(|(new, old)| [new, old]))
+        .map(|checkpoint| checkpoint.time.date_naive())
+        .collect();
+    check_unlocked(db, &dates).await?;
+
+    let updates: Vec<_> = plan.updates.into_iter().map(|(new, _)| new).collect();
+    if !updates.is_empty() {
+        update_checkpoints(db, &updates).await?;
+    }
+    let inserted = plan.inserts.len();
+    for mut checkpoint in plan.inserts {
+        checkpoint
+            .id
+            .get_or_insert_with(|| Uuid::new_v4().simple().to_string());
+        insert_checkpoint(db, checkpoint).await?;
+    }
+    for date in &dates {
+        refresh_tracking(db, date, settings, profile).await?;
+    }
+
+    println!(
+        "Imported {} new and {} changed checkpoints, {} were unchanged",
+        inserted,
+        updates.len(),
+        plan.unchanged
+    );
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use std::path::{Path, PathBuf};
+
+use ::firestore::FirestoreDb;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use clap::Args;
+use color_eyre::eyre::WrapErr;
+use color_eyre::Result;
+use serde::Serialize;
+use tera::{Context, Tera};
+
+use super::parse_month;
+use crate::app::CheckpointKind;
+use crate::earnings::Rates;
+use crate::firestore::find_checkpoints_between;
+use crate::projects::{find, Project};
+use crate::span::Span;
+use crate::time::{decimal_hours, Locale, TimeSettings};
+
+/// Template used without `--template`
+const TIMESHEET: &str = include_str!("timesheet.html");
+
+#[derive(Debug, Clone, Args)]
+pub struct InvoiceArgs {
+    /// Month like 2025-01, this month when left out
+    #[arg(long, value_name = "YYYY-MM", value_parser = parse_month)]
+    pub month: Option<NaiveDate>,
+    /// Project whose billable work spans are listed
+    #[arg(long)]
+    pub project: String,
+    /// Tera template of the HTML, the built-in timesheet when left out
+    #[arg(long)]
+    pub template: Option<PathBuf>,
+    /// File to write, printed as PDF when it ends in `.pdf` (needs the `pdf` feature), the
+    /// standard output when left out
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+/// Values of the template
+#[derive(Debug, Serialize)]
+struct Timesheet {
+    project: TimesheetProject,
+    /// Like `2025-01`
+    month: String,
+    from: String,
+    to: String,
+    rows: Vec<TimesheetRow>,
+    total_minutes: u32,
+    total_hours: String,
+    /// Hourly rate with the currency, missing without a rate
+    rate: Option<String>,
+    amount: Option<String>,
+    currency: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TimesheetProject {
+    id: String,
+    /// The id when the project has no name
+    name: String,
+    billing_code: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TimesheetRow {
+    date: String,
+    start: String,
+    end: String,
+    minutes: u32,
+    hours: String,
+    message: String,
+}
+
+impl Timesheet {
+    /// The billable work spans of the project in the month, `month` is its first day
+    fn new(
+        month: NaiveDate,
+        project: &str,
+        spans: &[Span],
+        projects: &[Project],
+        rates: &Rates,
+        locale: &Locale,
+    ) -> Self {
+        let found = find(projects, project);
+        let rows: Vec<TimesheetRow> = spans
+            .iter()
+            .filter(|span| {
+                span.kind == CheckpointKind::Work
+                    && span.billable
+                    && span.project.as_deref() == Some(project)
+            })
+            .map(|span| TimesheetRow {
+                date: locale.date(span.start.date_naive()),
+                start: locale.time(span.start.time()),
+                end: locale.time(span.end.time()),
+                minutes: span.minutes,
+                hours: decimal_hours(span.minutes),
+                message: span.message.clone().unwrap_or_default(),
+            })
+            .collect();
+        let total_minutes = rows.iter().map(|row| row.minutes).sum();
+        let last = month + Months::new(1) - Days::new(1);
+        Self {
+            project: TimesheetProject {
+                id: project.to_string(),
+                name: found
+                    .and_then(|found| found.name.clone())
+                    .unwrap_or_else(|| project.to_string()),
+                billing_code: found.and_then(|found| found.billing_code.clone()),
+            },
+            month: month.format("%Y-%m").to_string(),
+            from: locale.date(month),
+            to: locale.date(last),
+            rows,
+            total_minutes,
+            total_hours: decimal_hours(total_minutes),
+            rate: rates.earned(project, 60).map(|rate| rates.format(rate)),
+            amount: rates
+                .earned(project, total_minutes)
+                .map(|amount| rates.format(amount)),
+            currency: rates.currency().map(str::to_string),
+        }
+    }
+
+    fn render(&self, template: &str) -> Result<String> {
+        let context = Context::from_serialize(self)?;
+        Tera::one_off(template, &context, true).wrap_err("The template could not be rendered")
+    }
+}
+
+/// Writes a timesheet of the billable work spans of a project in a month as HTML or PDF, for
+/// clients that want one attached to the invoice
+pub async fn invoice(
+    db: &FirestoreDb,
+    projects: &[Project],
+    rates: &Rates,
+    settings: &TimeSettings,
+    args: InvoiceArgs,
+) -> Result<()> {
+    let month = args
+        .month
+        .unwrap_or_else(|| settings.now().date_naive().with_day(1).unwrap());
+    let last = month + Months::new(1) - Days::new(1);
+    let template = match &args.template {
+        Some(path) => std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read {}", path.display()))?,
+        None => TIMESHEET.to_string(),
+    };
+    let checkpoints = find_checkpoints_between(db, &month, &last, settings.timezone).await?;
+    let spans = Span::from_days(&checkpoints, &settings.rounding);
+    let sheet = Timesheet::new(
+        month,
+        &args.project,
+        &spans,
+        projects,
+        rates,
+        &settings.locale,
+    );
+    let html = sheet.render(&template)?;
+
+    match &args.output {
+        Some(path) if is_pdf(path) => write_pdf(&html, path),
+        Some(path) => Ok(std::fs::write(path, html)?),
+        None => {
+            print!("{}", html);
+            Ok(())
+        }
+    }
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// Prints the HTML to the PDF in a headless Chrome
+#[cfg(feature = "pdf")]
+fn write_pdf(html: &str, path: &Path) -> Result<()> {
+    use color_eyre::eyre::eyre;
+    use headless_chrome::Browser;
+
+    let page = std::env::temp_dir().join(format!("tcheater-{}.html", std::process::id()));
+    std::fs::write(&page, html)?;
+    let printed = (|| {
+        let browser = Browser::default()?;
+        let tab = browser.new_tab()?;
+        tab.navigate_to(&format!("file://{}", page.display()))?
+            .wait_until_navigated()?;
+        tab.print_to_pdf(None)
+    })();
+    std::fs::remove_file(&page)?;
+    let pdf = printed.map_err(|err| eyre!("Chrome could not print the PDF: {}", err))?;
+    Ok(std::fs::write(path, pdf)?)
+}
+
+#[cfg(not(feature = "pdf"))]
+fn write_pdf(_html: &str, _path: &Path) -> Result<()> {
+    color_eyre::eyre::bail!("PDF needs tcheater built with the pdf feature, write .html instead")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::Checkpoint;
+    use crate::projects::parse_projects;
+    use crate::time::test_util::{noted, on};
+    use crate::time::Rounding;
+
+    #[test]
+    fn test_timesheet() {
+        let projects = parse_projects(
+            "[[projects]]\nid = \"acme\"\nname = \"ACME & Co\"\nbilling_code = \"AC-7\"\nrate = 90.0",
+        )
+        .unwrap();
+        let rates = Rates::new(&projects, Some("EUR".to_string()));
+        let work = CheckpointKind::Work;
+        let checkpoints = vec![
+            noted(on(6, 8, 0), work, "acme", "<b>importer</b>"),
+            noted(on(6, 9, 30), work, "web", "landing page"),
+            noted(on(6, 10, 0), work, "acme", "review"),
+            Checkpoint {
+                billable: false,
+                ..noted(on(6, 11, 0), work, "acme", "internal sync")
+            },
+            noted(on(6, 12, 0), CheckpointKind::Stop, "", ""),
+        ];
+        let month = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let sheet = Timesheet::new(
+            month,
+            "acme",
+            &Span::from_days(&checkpoints, &Rounding::DEFAULT),
+            &projects,
+            &rates,
+            &Locale::DEFAULT,
+        );
+        assert_eq!(sheet.rows.len(), 2);
+        assert_eq!(sheet.total_hours, "2.5h");
+        assert_eq!(sheet.amount.as_deref(), Some("225.00 EUR"));
+
+        let html = sheet.render(TIMESHEET).unwrap();
+        assert!(html.contains("ACME &amp; Co (AC-7)"));
+        assert!(html.contains("&lt;b&gt;importer&lt;&#x2F;b&gt;"));
+        assert!(html.contains("2.5h × 90.00 EUR"));
+        assert!(!html.contains("landing page"));
+        assert!(!html.contains("internal sync"));
+
+        let custom = "{% for row in rows %}{{ row.hours }};{% endfor %}{{ amount }}";
+        assert_eq!(sheet.render(custom).unwrap(), "1.5h;1h;225.00 EUR");
+        assert!(sheet.render("{{ nope }}").is_err());
+        assert!(is_pdf(Path::new("feb.PDF")));
+    }
+}
@@ -0,0 +1,157 @@
+use ::firestore::FirestoreDb;
+use chrono::{NaiveDate, NaiveTime};
+use clap::Args;
+use color_eyre::eyre::{bail, eyre};
+use color_eyre::Result;
+use uuid::Uuid;
+
+use super::completions::project_ids;
+use super::{failed, parse_date, parse_time, Failure};
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::firestore::{find_checkpoints, find_locked_days, insert_checkpoint, refresh_tracking};
+use crate::time::TimeSettings;
+use crate::webhook::{self, Event, WebhookConfig};
+
+#[derive(Debug, Clone, Args)]
+pub struct LogArgs {
+    /// Start and end of the span, e.g. 9:00-11:30
+    #[arg(value_parser = parse_time_range)]
+    pub range: (NaiveTime, NaiveTime),
+    /// Project or PBS task id of the span
+    #[arg(add = project_ids())]
+    pub project: String,
+    /// Message of the span, the #tags in it are picked up
+    pub message: Option<String>,
+    /// Day of the span, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub date: Option<NaiveDate>,
+}
+
+fn parse_time_range(input: &str) -> Result<(NaiveTime, NaiveTime), String> {
+    let (start, end) = input
+        .split_once('-')
+        .ok_or_else(|| format!("`{}` is not a range like 9:00-11:30", input))?;
+    let (start, end) = (parse_time(start)?, parse_time(end)?);
+    if end <= start {
+        return Err(format!("`{}` ends before it starts", input));
+    }
+    Ok((start, end))
+}
+
+impl LogArgs {
+    /// The checkpoints starting and ending the span among the existing ones of its day.
+    ///
+    /// The end is a stop, unless a span of the day already starts there.
+    fn checkpoints(
+        &self,
+        date: NaiveDate,
+        existing: &[Checkpoint],
+        settings: &TimeSettings,
+    ) -> Result<Vec<Checkpoint>> {
+        let at = |time| {
+            settings
+                .display_time(date.and_time(time))
+                .ok_or_else(|| eyre!("{} does not exist", time))
+        };
+        let (start, end) = (at(self.range.0)?, at(self.range.1)?);
+        if let Some(inside) = existing.iter().find(|ch| ch.time >= start && ch.time < end) {
+            bail!(
+                "The span overlaps the checkpoint at {}",
+                settings.locale.time(inside.time.time())
+            );
+        }
+
+        let mut logged = Checkpoint {
+            id: Some(Uuid::new_v4().simple().to_string()),
+            time: start,
+            project: Some(self.project.clone()),
+            ..Checkpoint::new()
+        };
+        if let Some(message) = &self.message {
+            logged.set_message(message.clone());
+        }
+        let mut checkpoints = vec![logged];
+        if !existing.iter().any(|ch| ch.time == end) {
+            checkpoints.push(Checkpoint {
+                id: Some(Uuid::new_v4().simple().to_string()),
+                time: end,
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            });
+        }
+        Ok(checkpoints)
+    }
+}
+
+/// Stores a span given by its start and end, for back-filling from memory
+pub async fn log(
+    db: &FirestoreDb,
+    webhooks: &[WebhookConfig],
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: LogArgs,
+) -> Result<()> {
+    let date = args.date.unwrap_or_else(|| settings.now().date_naive());
+    if find_locked_days(db).await?.contains(&date) {
+        return Err(failed(
+            Failure::Locked,
+            format!("{} is locked, unlock it in the week view first", date),
+        ));
+    }
+    let existing = find_checkpoints(db, &date, settings.timezone).await?;
+    let checkpoints = args.checkpoints(date, &existing, settings)?;
+    for checkpoint in &checkpoints {
+        insert_checkpoint(db, checkpoint.clone()).await?;
+    }
+    refresh_tracking(db, &date, settings, profile).await?;
+    println!(
+        "Logged {} {} {}-{} {}",
+        date.format("%a"),
+        settings.locale.date(date),
+        settings.locale.time(args.range.0),
+        settings.locale.time(args.range.1),
+        args.project
+    );
+    for err in webhook::fire(webhooks, Event::Insert, &checkpoints).await {
+        eprintln!("{:#}", err);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(range: &str) -> LogArgs {
+        LogArgs {
+            range: parse_time_range(range).unwrap(),
+            project: "ABC-1".to_string(),
+            message: Some("fixed the importer #bug".to_string()),
+            date: None,
+        }
+    }
+
+    #[test]
+    fn test_logged_checkpoints() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let settings = TimeSettings::default();
+        let existing = args("11:30-12:00")
+            .checkpoints(date, &[], &settings)
+            .unwrap();
+
+        let logged = args("9:00-11:30")
+            .checkpoints(date, &existing, &settings)
+            .unwrap();
+        assert_eq!(logged.len(), 1);
+        assert_eq!(logged[0].time.format("%H:%M").to_string(), "09:00");
+        assert_eq!(logged[0].tags, vec!["bug".to_string()]);
+
+        let logged = args("8:00-9:00").checkpoints(date, &[], &settings).unwrap();
+        assert_eq!(logged[1].kind, CheckpointKind::Stop);
+
+        assert!(args("11:00-11:45")
+            .checkpoints(date, &existing, &settings)
+            .is_err());
+        assert!(parse_time_range("11:30-9:00").is_err());
+    }
+}
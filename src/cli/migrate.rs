@@ -0,0 +1,32 @@
+use ::firestore::FirestoreDb;
+use clap::Args;
+use color_eyre::Result;
+
+use crate::firestore::{find_unmigrated_times, save_times};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct MigrateArgs {
+    /// Only print how many checkpoints would be rewritten
+    #[arg(long, short = 'n')]
+    pub dry_run: bool,
+}
+
+/// Rewrites the times stored with a local offset before they were kept in UTC, so that the store
+/// orders and finds them by time again. Running it again changes nothing.
+pub async fn migrate(db: &FirestoreDb, args: MigrateArgs) -> Result<()> {
+    let times = find_unmigrated_times(db).await?;
+    if times.is_empty() {
+        println!("All checkpoint times are stored in UTC");
+        return Ok(());
+    }
+    if !args.dry_run {
+        save_times(db, &times).await?;
+    }
+    let verb = if args.dry_run {
+        "Would rewrite"
+    } else {
+        "Rewrote"
+    };
+    println!("{} the times of {} checkpoints", verb, times.len());
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::time::Duration as StdDuration;
+
+use ::firestore::FirestoreDb;
+use chrono::{Datelike, Duration, TimeZone};
+use clap::Args;
+use color_eyre::Result;
+
+use super::{failed, Failure};
+use crate::app::Checkpoint;
+use crate::config::{Config, WorkingHours};
+use crate::firestore::{find_checkpoints, find_day_types};
+use crate::holidays::Holidays;
+use crate::notification::{notify, Reminders};
+use crate::time::{Locale, Time, TimeSettings};
+
+/// How often `--watch` looks at the store
+const WATCH_INTERVAL: StdDuration = StdDuration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct RemindArgs {
+    /// Hours of working time without a new checkpoint, `after_hours` of [remind] in
+    /// config.toml when left out
+    #[arg(long)]
+    pub after_hours: Option<f32>,
+    /// Show a desktop notification too, not only fail
+    #[arg(long)]
+    pub notify: bool,
+    /// Keep running and check every five minutes, notifying once per reminder period and about
+    /// the reminders of [remind]
+    #[arg(long)]
+    pub watch: bool,
+}
+
+/// Fails, or notifies, when no checkpoint was added for a while during the working time of
+/// today as configured in [remind] of config.toml
+pub async fn remind(
+    db: &FirestoreDb,
+    config: &Config,
+    holidays: &Holidays,
+    args: RemindArgs,
+) -> Result<()> {
+    let settings = config.time_settings();
+    let hours = args.after_hours.unwrap_or(config.remind.after_hours);
+    let after = Duration::minutes((hours * 60.0).round() as i64);
+    if !args.watch {
+        let Some(since) = check(db, config, &settings, holidays, after).await? else {
+            return Ok(());
+        };
+        let message = message(since, &settings.locale);
+        if args.notify {
+            notify("Track your time", &message)?;
+        }
+        return Err(failed(Failure::Untracked, message));
+    }
+
+    let mut reminded: Option<Time> = None;
+    let mut reminders = Reminders::default();
+    loop {
+        let now = settings.now();
+        let today = find_checkpoints(db, &now.date_naive(), settings.timezone).await;
+        match today {
+            Ok(checkpoints) => {
+                let target = config.goals.daily_target_hours;
+                let due = reminders.due(&config.remind, &settings, target, &checkpoints, now);
+                for (summary, body) in due {
+                    if let Err(err) = notify(&summary, &body) {
+                        eprintln!("{}", err);
+                    }
+                }
+            }
+            Err(err) => eprintln!("{}", err),
+        }
+        match check(db, config, &settings, holidays, after).await {
+            Ok(Some(since)) if reminded.is_none_or(|at| now - at >= after) => {
+                if let Err(err) = notify("Track your time", &message(since, &settings.locale)) {
+                    eprintln!("{}", err);
+                }
+                reminded = Some(now);
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => reminded = None,
+            Err(err) => eprintln!("{}", err),
+        }
+        tokio::time::sleep(WATCH_INTERVAL).await;
+    }
+}
+
+/// Since when nothing was tracked today, when that is overdue
+async fn check(
+    db: &FirestoreDb,
+    config: &Config,
+    settings: &TimeSettings,
+    holidays: &Holidays,
+    after: Duration,
+) -> Result<Option<Time>> {
+    let now = settings.now();
+    let date = now.date_naive();
+    let Some(hours) = config.remind.working_hours(date.weekday()) else {
+        return Ok(None);
+    };
+    if holidays.contains_key(&date) || find_day_types(db).await?.contains_key(&date) {
+        return Ok(None);
+    }
+    let checkpoints = find_checkpoints(db, &date, settings.timezone).await?;
+    Ok(untracked_since(hours, &checkpoints, after, now))
+}
+
+/// The last checkpoint of the day before `now`, or the start of the working time, when it is
+/// at least `after` ago. Outside the working time nothing is overdue.
+fn untracked_since(
+    hours: WorkingHours,
+    checkpoints: &[Checkpoint],
+    after: Duration,
+    now: Time,
+) -> Option<Time> {
+    let date = now.date_naive();
+    let at = |time| {
+        now.timezone()
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+    };
+    let (start, end) = (at(hours.start)?, at(hours.end)?);
+    if now < start || now > end {
+        return None;
+    }
+    let since = checkpoints
+        .iter()
+        .rev()
+        .map(|ch| ch.time)
+        .find(|time| *time <= now)
+        .map_or(start, |last| last.max(start));
+    (now - since >= after).then_some(since)
+}
+
+fn message(since: Time, locale: &Locale) -> String {
+    format!("No checkpoint since {}", locale.time(since.time()))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::app::CheckpointKind;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_untracked_since() {
+        let hours = WorkingHours {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        };
+        let two_hours = Duration::hours(2);
+        let checkpoints = vec![
+            Checkpoint {
+                time: at(8, 0),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+
+        assert_eq!(untracked_since(hours, &[], two_hours, at(10, 59)), None);
+        assert_eq!(
+            untracked_since(hours, &[], two_hours, at(11, 0)),
+            Some(at(9, 0))
+        );
+        assert_eq!(
+            untracked_since(hours, &checkpoints, two_hours, at(13, 0)),
+            None
+        );
+        assert_eq!(
+            untracked_since(hours, &checkpoints, two_hours, at(14, 30)),
+            Some(at(12, 0))
+        );
+        assert_eq!(
+            untracked_since(hours, &checkpoints, two_hours, at(18, 0)),
+            None
+        );
+        assert_eq!(
+            message(at(12, 0), &Locale::DEFAULT),
+            "No checkpoint since 12:00"
+        );
+    }
+}
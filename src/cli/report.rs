@@ -0,0 +1,500 @@
+use ::firestore::FirestoreDb;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use clap::{Args, ValueEnum};
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::{parse_date, parse_month, print_json};
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::earnings::Rates;
+use crate::firestore::find_checkpoints_between;
+use crate::projects::{find, Project};
+use crate::span::Span;
+use crate::stats::PeriodStats;
+use crate::time::{Locale, Rounding, TimeSettings};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ReportArgs {
+    /// Week containing the date, this week when no date is given. The default period.
+    #[arg(long, value_name = "DATE", value_parser = parse_date, conflicts_with = "month")]
+    pub week: Option<Option<NaiveDate>>,
+    /// Month like 2025-01, this month when left out
+    #[arg(long, value_name = "YYYY-MM", value_parser = parse_month)]
+    pub month: Option<Option<NaiveDate>>,
+    /// First day of the period
+    #[arg(long, value_parser = parse_date, requires = "to", conflicts_with_all = ["week", "month"])]
+    pub from: Option<NaiveDate>,
+    /// Last day of the period, inclusive
+    #[arg(long, value_parser = parse_date, requires = "from")]
+    pub to: Option<NaiveDate>,
+    /// List the totals of each day under its date, followed by the totals of the period
+    #[arg(long)]
+    pub by_day: bool,
+    /// Print the totals as JSON, short for `--format json`
+    #[arg(long, conflicts_with = "format")]
+    pub json: bool,
+    /// How the report is printed
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ReportFormat,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// Aligned totals per project
+    #[default]
+    Text,
+    Json,
+    /// Projects with the comments of each day as bullet lists, e.g. for a weekly update
+    Md,
+}
+
+impl ReportArgs {
+    pub fn format(&self) -> ReportFormat {
+        match self.json {
+            true => ReportFormat::Json,
+            false => self.format,
+        }
+    }
+
+    /// First and last day of the reported period, the weeks start on the first day of the locale
+    pub fn period(&self, today: NaiveDate, locale: &Locale) -> (NaiveDate, NaiveDate) {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            return (from, to);
+        }
+        if let Some(month) = self.month {
+            let first = month.unwrap_or(today).with_day(1).unwrap();
+            return (first, first + Months::new(1) - Days::new(1));
+        }
+        let start = locale.week_start(self.week.flatten().unwrap_or(today));
+        (start, start + Days::new(6))
+    }
+}
+
+/// Prints the tracked time per project over the period, and the money earned with the projects
+/// that have a rate
+pub async fn report(
+    db: &FirestoreDb,
+    projects: &[Project],
+    rates: &Rates,
+    settings: &TimeSettings,
+    args: ReportArgs,
+) -> Result<()> {
+    let (first, last) = args.period(settings.now().date_naive(), &settings.locale);
+    let checkpoints = find_checkpoints_between(db, &first, &last, settings.timezone).await?;
+    match args.format() {
+        ReportFormat::Json => {
+            return print_json(&ReportJson::new(
+                first,
+                last,
+                &checkpoints,
+                rates,
+                &settings.rounding,
+                args.by_day,
+            ))
+        }
+        ReportFormat::Md => print!(
+            "{}",
+            render_markdown(first, last, &checkpoints, projects, settings)
+        ),
+        ReportFormat::Text => print!(
+            "{}",
+            render(first, last, &checkpoints, rates, settings, args.by_day)
+        ),
+    }
+    Ok(())
+}
+
+/// Stats of the days with tracked work, the checkpoints must be ordered by time
+fn days(checkpoints: &[Checkpoint], rounding: &Rounding) -> Vec<(NaiveDate, PeriodStats)> {
+    checkpoints
+        .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+        .map(|day| {
+            let stats = PeriodStats::from_checkpoints(day, rounding);
+            (day[0].time.date_naive(), stats)
+        })
+        .filter(|(_, stats)| !stats.project_minutes.is_empty())
+        .collect()
+}
+
+#[derive(Serialize)]
+struct ReportJson {
+    from: NaiveDate,
+    to: NaiveDate,
+    /// Of the earned amounts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    currency: Option<String>,
+    #[serde(flatten)]
+    totals: TotalsJson,
+    /// Only with `--by-day`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    days: Vec<DayJson>,
+}
+
+#[derive(Serialize)]
+struct DayJson {
+    date: NaiveDate,
+    #[serde(flatten)]
+    totals: TotalsJson,
+}
+
+#[derive(Serialize)]
+struct TotalsJson {
+    projects: Vec<ProjectJson>,
+    total_minutes: u32,
+    /// When any of the projects has a rate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_earned: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct ProjectJson {
+    project: String,
+    minutes: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    earned: Option<f64>,
+}
+
+impl TotalsJson {
+    /// The earned amounts count the billable minutes
+    fn new(stats: &PeriodStats, rates: &Rates) -> Self {
+        Self {
+            total_minutes: stats
+                .project_minutes
+                .iter()
+                .map(|(_, minutes)| minutes)
+                .sum(),
+            total_earned: stats.total_earned(rates),
+            projects: stats
+                .project_minutes
+                .iter()
+                .map(|(project, minutes)| ProjectJson {
+                    earned: stats.earned(rates, project),
+                    project: project.clone(),
+                    minutes: *minutes,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl ReportJson {
+    fn new(
+        first: NaiveDate,
+        last: NaiveDate,
+        checkpoints: &[Checkpoint],
+        rates: &Rates,
+        rounding: &Rounding,
+        by_day: bool,
+    ) -> Self {
+        let days = match by_day {
+            true => days(checkpoints, rounding),
+            false => vec![],
+        };
+        Self {
+            from: first,
+            to: last,
+            currency: rates.currency().map(str::to_string),
+            totals: TotalsJson::new(&PeriodStats::from_checkpoints(checkpoints, rounding), rates),
+            days: days
+                .into_iter()
+                .map(|(date, stats)| DayJson {
+                    date,
+                    totals: TotalsJson::new(&stats, rates),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The report as aligned text, the checkpoints must be ordered by time. The amounts earned with
+/// the billable time follow the durations of the projects with a rate.
+fn render(
+    first: NaiveDate,
+    last: NaiveDate,
+    checkpoints: &[Checkpoint],
+    rates: &Rates,
+    settings: &TimeSettings,
+    by_day: bool,
+) -> String {
+    let (locale, rounding) = (&settings.locale, &settings.rounding);
+    let mut sections = vec![];
+    if by_day {
+        for (date, stats) in days(checkpoints, rounding) {
+            sections.push((
+                format!("{} {}", date.format("%a"), locale.date(date)),
+                stats,
+            ));
+        }
+    }
+    let title = format!("{} - {}", locale.date(first), locale.date(last));
+    sections.push((title, PeriodStats::from_checkpoints(checkpoints, rounding)));
+
+    // The same columns in all sections, so that the days line up with the period
+    let width = sections
+        .iter()
+        .flat_map(|(_, stats)| {
+            stats
+                .project_minutes
+                .iter()
+                .map(|(project, _)| project.len())
+        })
+        .chain(["Total".len()])
+        .max()
+        .unwrap_or_default();
+    let earned = |earned: Option<f64>| {
+        earned
+            .map(|amount| format!("  {:>12}", rates.format(amount)))
+            .unwrap_or_default()
+    };
+    let mut out = String::new();
+    for (title, stats) in sections {
+        out.push_str(&format!("{}\n", title));
+        for (project, minutes) in &stats.project_minutes {
+            out.push_str(&format!(
+                "  {:<width$}  {:>8}{}\n",
+                project,
+                settings.duration(*minutes),
+                earned(stats.earned(rates, project))
+            ));
+        }
+        let total = stats
+            .project_minutes
+            .iter()
+            .map(|(_, minutes)| minutes)
+            .sum();
+        out.push_str(&format!(
+            "  {:<width$}  {:>8}{}\n\n",
+            "Total",
+            settings.duration(total),
+            earned(stats.total_earned(rates))
+        ));
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+/// The report as Markdown, the checkpoints must be ordered by time. Each project lists the days
+/// it was worked on with the distinct messages of its spans.
+fn render_markdown(
+    first: NaiveDate,
+    last: NaiveDate,
+    checkpoints: &[Checkpoint],
+    projects: &[Project],
+    settings: &TimeSettings,
+) -> String {
+    let (locale, rounding) = (&settings.locale, &settings.rounding);
+    let days = days(checkpoints, rounding);
+    let spans = Span::from_days(checkpoints, rounding);
+    let messages = |date: NaiveDate, project: &str| {
+        let mut messages: Vec<&str> = vec![];
+        for span in &spans {
+            let message = span.message.as_deref().unwrap_or_default().trim();
+            if span.start.date_naive() == date
+                && span.kind == CheckpointKind::Work
+                && span.project.as_deref().unwrap_or("-") == project
+                && !message.is_empty()
+                && !messages.contains(&message)
+            {
+                messages.push(message);
+            }
+        }
+        messages
+    };
+
+    let mut out = format!("## {} - {}\n", locale.date(first), locale.date(last));
+    let totals = PeriodStats::from_checkpoints(checkpoints, rounding).project_minutes;
+    for (project, minutes) in &totals {
+        let title = match find(projects, project).and_then(|found| found.name.as_deref()) {
+            Some(name) => format!("{} ({})", name, project),
+            None => project.clone(),
+        };
+        out.push_str(&format!(
+            "\n### {} - {}\n\n",
+            title,
+            settings.duration(*minutes)
+        ));
+        for (date, day) in &days {
+            let Some((_, minutes)) = day.project_minutes.iter().find(|(p, _)| p == project) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "- {} {} - {}\n",
+                date.format("%a"),
+                locale.date(*date),
+                settings.duration(*minutes)
+            ));
+            for message in messages(*date, project) {
+                out.push_str(&format!("  - {}\n", message));
+            }
+        }
+    }
+    let total = totals.iter().map(|(_, minutes)| minutes).sum();
+    out.push_str(&format!("\n**Total: {}**\n", settings.duration(total)));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::{checkpoint, noted, on};
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+    }
+
+    #[test]
+    fn test_report_periods() {
+        let today = date(15);
+        assert_eq!(
+            ReportArgs::default().period(today, &Locale::DEFAULT),
+            (date(13), date(19))
+        );
+
+        let args = ReportArgs {
+            week: Some(Some(date(2))),
+            ..ReportArgs::default()
+        };
+        assert_eq!(
+            args.period(today, &Locale::DEFAULT),
+            (date(2) - Days::new(3), date(5))
+        );
+
+        let args = ReportArgs {
+            month: Some(None),
+            ..ReportArgs::default()
+        };
+        assert_eq!(args.period(today, &Locale::DEFAULT), (date(1), date(31)));
+    }
+
+    #[test]
+    fn test_render_by_day() {
+        let checkpoints = vec![
+            checkpoint(on(6, 8, 0), CheckpointKind::Work, "ABC-1"),
+            checkpoint(on(6, 10, 0), CheckpointKind::Work, "LONGER-2"),
+            checkpoint(on(6, 11, 0), CheckpointKind::Stop, ""),
+            checkpoint(on(7, 9, 0), CheckpointKind::Work, "ABC-1"),
+            checkpoint(on(7, 12, 0), CheckpointKind::Stop, ""),
+        ];
+        let settings = TimeSettings::default();
+
+        let report = render(
+            date(6),
+            date(12),
+            &checkpoints,
+            &Rates::default(),
+            &settings,
+            true,
+        );
+
+        assert_eq!(
+            report,
+            [
+                "Mon 06.01.25",
+                "  ABC-1           2h",
+                "  LONGER-2        1h",
+                "  Total           3h",
+                "",
+                "Tue 07.01.25",
+                "  ABC-1           3h",
+                "  Total           3h",
+                "",
+                "06.01.25 - 12.01.25",
+                "  ABC-1           5h",
+                "  LONGER-2        1h",
+                "  Total           6h",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let rates = Rates::default();
+        let json = ReportJson::new(
+            date(6),
+            date(12),
+            &checkpoints,
+            &rates,
+            &settings.rounding,
+            true,
+        );
+        let json = serde_json::to_value(json).unwrap();
+        assert_eq!(json["total_minutes"], 360);
+        assert_eq!(json["projects"][0]["project"], "ABC-1");
+        assert_eq!(json["days"][1]["date"], "2025-01-07");
+        assert_eq!(json["days"][1]["total_minutes"], 180);
+        assert!(json.get("total_earned").is_none());
+
+        let projects =
+            crate::projects::parse_projects("[[projects]]\nid = \"ABC-1\"\nrate = 50").unwrap();
+        let rates = Rates::new(&projects, Some("EUR".to_string()));
+        // Tuesday's three hours of ABC-1 are not billed
+        let mut checkpoints = checkpoints;
+        checkpoints[3].billable = false;
+        let report = render(date(6), date(12), &checkpoints, &rates, &settings, false);
+
+        assert_eq!(
+            report,
+            [
+                "06.01.25 - 12.01.25",
+                "  ABC-1           5h    100.00 EUR",
+                "  LONGER-2        1h",
+                "  Total           6h    100.00 EUR",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let json = ReportJson::new(
+            date(6),
+            date(12),
+            &checkpoints,
+            &rates,
+            &settings.rounding,
+            false,
+        );
+        let json = serde_json::to_value(json).unwrap();
+        assert_eq!(json["currency"], "EUR");
+        assert_eq!(json["total_earned"], 100.0);
+        assert_eq!(json["projects"][0]["earned"], 100.0);
+        assert!(json["projects"][1].get("earned").is_none());
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let checkpoints = vec![
+            noted(on(6, 8, 0), CheckpointKind::Work, "ABC-1", "importer"),
+            noted(on(6, 9, 0), CheckpointKind::Work, "ABC-1", "importer"),
+            noted(on(6, 10, 0), CheckpointKind::Work, "WEB", ""),
+            noted(on(6, 11, 0), CheckpointKind::Stop, "", ""),
+            noted(on(7, 9, 0), CheckpointKind::Work, "ABC-1", "review"),
+            noted(on(7, 12, 0), CheckpointKind::Break, "", "lunch"),
+            noted(on(7, 13, 0), CheckpointKind::Stop, "", ""),
+        ];
+        let projects =
+            crate::projects::parse_projects("[[projects]]\nid = \"ABC-1\"\nname = \"Importer\"")
+                .unwrap();
+        let settings = TimeSettings::default();
+
+        assert_eq!(
+            render_markdown(date(6), date(12), &checkpoints, &projects, &settings),
+            [
+                "## 06.01.25 - 12.01.25",
+                "",
+                "### Importer (ABC-1) - 5h",
+                "",
+                "- Mon 06.01.25 - 2h",
+                "  - importer",
+                "- Tue 07.01.25 - 3h",
+                "  - review",
+                "",
+                "### WEB - 1h",
+                "",
+                "- Mon 06.01.25 - 1h",
+                "",
+                "**Total: 6h**",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use clap::Args;
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::print_json;
+use crate::app::CheckpointKind;
+use crate::state::{self, Tracking};
+use crate::time::{human_duration, Time, TimeSettings};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct StatusArgs {
+    /// Print the state as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Prints what is being tracked from the local cache of the profile, without connecting to the
+/// store or reading the config
+pub fn status(profile: Option<&str>, args: StatusArgs) -> Result<()> {
+    let tracking = state::load(profile);
+    let now = TimeSettings::default().now();
+    if args.json {
+        return print_json(&StatusJson::new(tracking, now));
+    }
+    println!("{}", render(tracking.as_ref(), now));
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct StatusJson {
+    running: bool,
+    #[serde(flatten)]
+    tracking: Option<Tracking>,
+    minutes: Option<u32>,
+}
+
+impl StatusJson {
+    fn new(tracking: Option<Tracking>, now: Time) -> Self {
+        Self {
+            running: tracking.is_some(),
+            minutes: tracking.as_ref().map(|tracking| elapsed(tracking, now)),
+            tracking,
+        }
+    }
+}
+
+fn elapsed(tracking: &Tracking, now: Time) -> u32 {
+    (now - tracking.since).num_minutes().max(0) as u32
+}
+
+/// The project and how long it runs, e.g. `ABC-1 1h30m`, or `idle`
+fn render(tracking: Option<&Tracking>, now: Time) -> String {
+    let Some(tracking) = tracking else {
+        return "idle".to_string();
+    };
+    let elapsed = human_duration(elapsed(tracking, now));
+    let label = match tracking.kind {
+        CheckpointKind::Work => tracking.project.clone(),
+        CheckpointKind::Break => Some("break".to_string()),
+        CheckpointKind::Absence => Some("absence".to_string()),
+        CheckpointKind::Stop => return "idle".to_string(),
+    };
+    match label {
+        Some(label) if !label.is_empty() => format!("{} {}", label, elapsed),
+        _ => elapsed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_render_status() {
+        let mut tracking = Tracking {
+            since: at(8, 0),
+            project: Some("ABC-1".to_string()),
+            kind: CheckpointKind::Work,
+        };
+
+        assert_eq!(render(Some(&tracking), at(9, 30)), "ABC-1 1h30m");
+        assert_eq!(render(None, at(9, 30)), "idle");
+
+        tracking.kind = CheckpointKind::Break;
+        assert_eq!(render(Some(&tracking), at(8, 15)), "break 15m");
+
+        let json = serde_json::to_value(StatusJson::new(Some(tracking), at(8, 15))).unwrap();
+        assert_eq!(json["running"], true);
+        assert_eq!(json["kind"], "break");
+        assert_eq!(json["minutes"], 15);
+        let json = serde_json::to_value(StatusJson::new(None, at(8, 15))).unwrap();
+        assert_eq!(json["running"], false);
+    }
+}
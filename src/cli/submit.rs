@@ -0,0 +1,370 @@
+use std::collections::BTreeSet;
+
+use ::firestore::FirestoreDb;
+use chrono::NaiveDate;
+use clap::Args;
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::{failed, parse_date, print_json, Failure};
+use crate::app::Checkpoint;
+use crate::firestore::{
+    find_checkpoints_between, find_locked_days, refresh_tracking, update_checkpoints,
+};
+use crate::projects::Project;
+use crate::time::{unregistered_spans, Locale, Time, TimeSettings};
+use crate::webhook::{self, Event, WebhookConfig};
+use crate::worklog::{route, Worklog, WorklogSink};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SubmitArgs {
+    /// First submitted day, the Monday of this week when left out
+    #[arg(long, value_parser = parse_date)]
+    pub from: Option<NaiveDate>,
+    /// Last submitted day, inclusive, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub to: Option<NaiveDate>,
+    /// Print what would be submitted without logging into PBS or Jira
+    #[arg(long, short = 'n')]
+    pub dry_run: bool,
+    /// Print the spans and their results as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl SubmitArgs {
+    fn period(&self, today: NaiveDate, locale: &Locale) -> (NaiveDate, NaiveDate) {
+        let last = self.to.unwrap_or(today);
+        let first = self.from.unwrap_or_else(|| locale.week_start(last));
+        (first, last)
+    }
+}
+
+/// An unregistered work span and what became of it
+struct Submission {
+    checkpoint: Checkpoint,
+    minutes: u32,
+    status: SubmitStatus,
+}
+
+enum SubmitStatus {
+    /// To be submitted to the sink of the index as the worklog
+    Ready(usize, Worklog),
+    /// Submitted to the sink of the index
+    Submitted(usize),
+    Skipped(&'static str),
+    Failed(String),
+}
+
+impl SubmitStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            SubmitStatus::Ready(..) => "ready",
+            SubmitStatus::Submitted(_) => "submitted",
+            SubmitStatus::Skipped(_) => "skipped",
+            SubmitStatus::Failed(_) => "failed",
+        }
+    }
+
+    fn reason(&self) -> Option<String> {
+        match self {
+            SubmitStatus::Skipped(reason) => Some(reason.to_string()),
+            SubmitStatus::Failed(err) => Some(err.clone()),
+            SubmitStatus::Ready(..) | SubmitStatus::Submitted(_) => None,
+        }
+    }
+
+    /// Index of the sink the span goes to
+    fn sink(&self) -> Option<usize> {
+        match self {
+            SubmitStatus::Ready(sink, _) | SubmitStatus::Submitted(sink) => Some(*sink),
+            SubmitStatus::Skipped(_) | SubmitStatus::Failed(_) => None,
+        }
+    }
+}
+
+impl Submission {
+    fn new(
+        checkpoint: Checkpoint,
+        minutes: u32,
+        sinks: &[Box<dyn WorklogSink>],
+        projects: &[Project],
+        locked: &BTreeSet<NaiveDate>,
+    ) -> Self {
+        let date = checkpoint.time.date_naive();
+        let routed = checkpoint.project.as_deref().and_then(|project| {
+            route(
+                sinks,
+                projects,
+                project,
+                checkpoint.time,
+                minutes,
+                checkpoint.message.as_deref(),
+            )
+        });
+        let status = match routed {
+            _ if locked.contains(&date) => SubmitStatus::Skipped("the day is locked"),
+            _ if minutes == 0 => SubmitStatus::Skipped("no time after rounding"),
+            None => SubmitStatus::Skipped("the project is no PBS task or Jira issue"),
+            Some((sink, worklog)) => SubmitStatus::Ready(sink, worklog),
+        };
+        Self {
+            checkpoint,
+            minutes,
+            status,
+        }
+    }
+}
+
+/// Registers the rounded unregistered work spans of the period in the sink that takes their
+/// project
+pub async fn submit(
+    db: &FirestoreDb,
+    mut sinks: Vec<Box<dyn WorklogSink>>,
+    projects: &[Project],
+    webhooks: &[WebhookConfig],
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: SubmitArgs,
+) -> Result<()> {
+    let (first, last) = args.period(settings.now().date_naive(), &settings.locale);
+    let locked = find_locked_days(db).await?;
+    let checkpoints = find_checkpoints_between(db, &first, &last, settings.timezone).await?;
+    let mut submissions: Vec<_> = unregistered_spans(&checkpoints, &settings.rounding)
+        .into_iter()
+        .map(|(checkpoint, minutes)| {
+            Submission::new(checkpoint, minutes, &sinks, projects, &locked)
+        })
+        .collect();
+
+    if args.dry_run {
+        return print_submissions(&submissions, &sinks, settings, args.json);
+    }
+
+    // Only the sinks with something to submit are logged into
+    let used: BTreeSet<_> = submissions
+        .iter()
+        .filter_map(|submission| submission.status.sink())
+        .collect();
+    for &idx in &used {
+        let sink = &mut sinks[idx];
+        let name = sink.name();
+        sink.connect().await.map_err(|err| {
+            failed(
+                Failure::Pbs,
+                format!("Could not log into {}: {}", name, err),
+            )
+        })?;
+    }
+    let mut registered = vec![];
+    let mut failures = 0;
+    for submission in submissions.iter_mut() {
+        let SubmitStatus::Ready(sink, worklog) = &submission.status else {
+            continue;
+        };
+        let sink = *sink;
+        submission.status = match sinks[sink].submit(worklog).await {
+            Ok(()) => {
+                submission.checkpoint.registered = true;
+                registered.push(submission.checkpoint.clone());
+                SubmitStatus::Submitted(sink)
+            }
+            Err(err) => {
+                failures += 1;
+                SubmitStatus::Failed(err.to_string())
+            }
+        };
+    }
+
+    if !registered.is_empty() {
+        update_checkpoints(db, &registered).await?;
+        let dates: BTreeSet<_> = registered
+            .iter()
+            .map(|checkpoint| checkpoint.time.date_naive())
+            .collect();
+        for date in &dates {
+            refresh_tracking(db, date, settings, profile).await?;
+        }
+        // The spans are registered, a failed hook doesn't fail the submission
+        for err in webhook::fire(webhooks, Event::Submit, &registered).await {
+            eprintln!("{:#}", err);
+        }
+    }
+    print_submissions(&submissions, &sinks, settings, args.json)?;
+    if failures > 0 {
+        return Err(failed(
+            Failure::Pbs,
+            format!("{} spans were not submitted", failures),
+        ));
+    }
+    Ok(())
+}
+
+fn print_submissions(
+    submissions: &[Submission],
+    sinks: &[Box<dyn WorklogSink>],
+    settings: &TimeSettings,
+    json: bool,
+) -> Result<()> {
+    if json {
+        let spans: Vec<_> = submissions
+            .iter()
+            .map(|submission| SubmissionJson::new(submission, sinks))
+            .collect();
+        return print_json(&spans);
+    }
+    print!("{}", render(submissions, sinks, settings));
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct SubmissionJson {
+    #[serde(with = "crate::firestore::utc_time")]
+    start: Time,
+    project: Option<String>,
+    minutes: u32,
+    /// Where the span is submitted, e.g. `pbs` or `jira`
+    sink: Option<String>,
+    status: &'static str,
+    reason: Option<String>,
+}
+
+impl SubmissionJson {
+    fn new(submission: &Submission, sinks: &[Box<dyn WorklogSink>]) -> Self {
+        Self {
+            start: submission.checkpoint.time,
+            project: submission.checkpoint.project.clone(),
+            minutes: submission.minutes,
+            sink: submission
+                .status
+                .sink()
+                .map(|sink| sinks[sink].name().to_lowercase()),
+            status: submission.status.name(),
+            reason: submission.status.reason(),
+        }
+    }
+}
+
+/// A row per span with its result
+fn render(
+    submissions: &[Submission],
+    sinks: &[Box<dyn WorklogSink>],
+    settings: &TimeSettings,
+) -> String {
+    if submissions.is_empty() {
+        return "Nothing to submit\n".to_string();
+    }
+    let rows: Vec<_> = submissions
+        .iter()
+        .map(|submission| {
+            let checkpoint = &submission.checkpoint;
+            [
+                format!(
+                    "{} {}",
+                    checkpoint.time.format("%a"),
+                    settings.locale.date_time(checkpoint.time)
+                ),
+                checkpoint.project.clone().unwrap_or_default(),
+                settings.duration(submission.minutes),
+                match &submission.status {
+                    SubmitStatus::Ready(sink, _) => format!("to submit to {}", sinks[*sink].name()),
+                    SubmitStatus::Submitted(sink) => {
+                        format!("submitted to {}", sinks[*sink].name())
+                    }
+                    SubmitStatus::Skipped(reason) => format!("skipped, {}", reason),
+                    SubmitStatus::Failed(err) => format!("failed, {}", err),
+                },
+            ]
+        })
+        .collect();
+    let width = |column: usize| {
+        rows.iter()
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or_default()
+    };
+    let (project_width, time_width) = (width(1), width(2));
+    rows.iter()
+        .map(|[start, project, time, status]| {
+            format!(
+                "{}  {:<project_width$}  {:>time_width$}  {}\n",
+                start, project, time, status
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jira::{JiraConfig, JiraSink};
+    use crate::pbs::AuthConfig;
+    use crate::projects::parse_projects;
+    use crate::time::test_util::on;
+    use crate::worklog::PbsSink;
+
+    #[test]
+    fn test_submissions() {
+        let locked = BTreeSet::from([NaiveDate::from_ymd_opt(2025, 1, 7).unwrap()]);
+        let projects =
+            parse_projects("[[projects]]\nid = \"119627\"\nbilling_code = \"B-100\"").unwrap();
+        let sinks: Vec<Box<dyn WorklogSink>> = vec![
+            Box::new(JiraSink::new(JiraConfig::default())),
+            Box::new(PbsSink::new(AuthConfig::default())),
+        ];
+        let submission = |day, project: &str, minutes| {
+            Submission::new(
+                Checkpoint {
+                    time: on(day, 8, 0),
+                    project: Some(project.to_string()),
+                    message: Some("review".to_string()),
+                    ..Checkpoint::new()
+                },
+                minutes,
+                &sinks,
+                &projects,
+                &locked,
+            )
+        };
+
+        let submissions = vec![
+            submission(6, "119627", 90),
+            submission(6, "internal", 30),
+            submission(6, "WEB-12", 45),
+            submission(7, "119627", 60),
+        ];
+
+        let SubmitStatus::Ready(_, worklog) = &submissions[0].status else {
+            panic!("the first span is not ready");
+        };
+        assert_eq!(worklog.target, "119627");
+        assert_eq!(worklog.billing_code.as_deref(), Some("B-100"));
+        assert_eq!(
+            render(&submissions, &sinks, &TimeSettings::default()),
+            [
+                "Mon 06.01.25 08:00  119627    1h30m  to submit to PBS",
+                "Mon 06.01.25 08:00  internal    30m  skipped, the project is no PBS task or Jira issue",
+                "Mon 06.01.25 08:00  WEB-12      45m  to submit to Jira",
+                "Tue 07.01.25 08:00  119627       1h  skipped, the day is locked",
+                "",
+            ]
+            .join("\n")
+        );
+
+        let json = serde_json::to_value(SubmissionJson::new(&submissions[2], &sinks)).unwrap();
+        assert_eq!(json["status"], "ready");
+        assert_eq!(json["sink"], "jira");
+        let json = serde_json::to_value(SubmissionJson::new(&submissions[3], &sinks)).unwrap();
+        assert_eq!(json["status"], "skipped");
+        assert_eq!(json["reason"], "the day is locked");
+    }
+
+    #[test]
+    fn test_submit_period() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        assert_eq!(
+            SubmitArgs::default().period(date(15), &Locale::DEFAULT),
+            (date(13), date(15))
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use ::firestore::FirestoreDb;
+use chrono::{Days, NaiveDate};
+use clap::Args;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use uuid::Uuid;
+
+use super::{failed, parse_date, Failure};
+use crate::app::Checkpoint;
+use crate::firestore::{
+    find_checkpoints, find_locked_days, insert_checkpoint, refresh_tracking, update_checkpoints,
+};
+use crate::projects::Project;
+use crate::sync::{entries_on, pull, pushes, Toggl, TogglConfig};
+use crate::time::{Locale, TimeSettings};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct SyncArgs {
+    /// First synced day, the first day of this week when left out
+    #[arg(long, value_parser = parse_date)]
+    pub from: Option<NaiveDate>,
+    /// Last synced day, inclusive, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub to: Option<NaiveDate>,
+    /// Only print what would be pushed and pulled
+    #[arg(long, short = 'n')]
+    pub dry_run: bool,
+}
+
+impl SyncArgs {
+    fn period(&self, today: NaiveDate, locale: &Locale) -> (NaiveDate, NaiveDate) {
+        let last = self.to.unwrap_or(today);
+        let first = self.from.unwrap_or_else(|| locale.week_start(last));
+        (first, last)
+    }
+}
+
+/// Pushes the work spans of the period to Toggl Track and pulls in the entries started there
+pub async fn sync(
+    db: &FirestoreDb,
+    config: Option<&TogglConfig>,
+    projects: &[Project],
+    settings: &TimeSettings,
+    profile: Option<&str>,
+    args: SyncArgs,
+) -> Result<()> {
+    let config = config.ok_or_else(|| eyre!("No [toggl] in config.toml"))?;
+    let toggl = Toggl::new(config.clone());
+    let (first, last) = args.period(settings.now().date_naive(), &settings.locale);
+    let at_midnight = |date: NaiveDate| {
+        settings
+            .display_time(date.and_time(Default::default()))
+            .ok_or_else(|| eyre!("The midnight of {} does not exist", date))
+    };
+    let entries = toggl
+        .entries(at_midnight(first)?, at_midnight(last + Days::new(1))?)
+        .await?;
+    let locked = find_locked_days(db).await?;
+
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        if locked.contains(&date) {
+            println!("Skipped {}, it is locked", settings.locale.date(date));
+            continue;
+        }
+        let existing = find_checkpoints(db, &date, settings.timezone).await?;
+        let day_entries = entries_on(&entries, date, settings.timezone);
+        let mut checkpoints = pull(&existing, &day_entries, projects, settings.timezone);
+        let pushes = pushes(&checkpoints, &day_entries, projects);
+        if checkpoints.len() == existing.len() && pushes.is_empty() {
+            continue;
+        }
+
+        for checkpoint in checkpoints.iter_mut().filter(|ch| ch.id.is_none()) {
+            if checkpoint.toggl_id.is_some() {
+                println!("Pulled {}", describe(checkpoint, &settings.locale));
+            }
+            checkpoint.id = Some(Uuid::new_v4().simple().to_string());
+            if !args.dry_run {
+                insert_checkpoint(db, checkpoint.clone()).await?;
+            }
+        }
+        let mut pushed = vec![];
+        for push in &pushes {
+            let checkpoint = &mut checkpoints[push.idx];
+            let verb = if push.id.is_some() {
+                "Updated"
+            } else {
+                "Pushed"
+            };
+            println!("{} {}", verb, describe(checkpoint, &settings.locale));
+            if args.dry_run {
+                continue;
+            }
+            let id = toggl
+                .save(push)
+                .await
+                .map_err(|err| failed(Failure::Other, format!("Could not push: {}", err)))?;
+            if checkpoint.toggl_id != Some(id) {
+                checkpoint.toggl_id = Some(id);
+                pushed.push(checkpoint.clone());
+            }
+        }
+        if args.dry_run {
+            continue;
+        }
+        if !pushed.is_empty() {
+            update_checkpoints(db, &pushed).await?;
+        }
+        refresh_tracking(db, &date, settings, profile).await?;
+    }
+    Ok(())
+}
+
+fn describe(checkpoint: &Checkpoint, locale: &Locale) -> String {
+    format!(
+        "{} {} {}",
+        locale.date(checkpoint.time.date_naive()),
+        locale.time(checkpoint.time.time()),
+        checkpoint
+            .project_label()
+            .or(checkpoint.message.clone())
+            .unwrap_or_default()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync_period() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        assert_eq!(
+            SyncArgs::default().period(date(15), &Locale::DEFAULT),
+            (date(13), date(15))
+        );
+        let args = SyncArgs {
+            from: Some(date(1)),
+            ..SyncArgs::default()
+        };
+        assert_eq!(args.period(date(15), &Locale::DEFAULT), (date(1), date(15)));
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{BufRead, Write};
+
+use chrono::{NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+use color_eyre::eyre::eyre;
+use color_eyre::Result;
+use serde::Deserialize;
+
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::projects::Project;
+use crate::time::Time;
+
+#[derive(Debug, Deserialize)]
+pub struct TogglEntry {
+    #[serde(rename = "Project", default)]
+    pub project: String,
+    #[serde(rename = "Description", default)]
+    pub description: String,
+    #[serde(rename = "Start date")]
+    pub start_date: NaiveDate,
+    #[serde(rename = "Start time")]
+    pub start_time: NaiveTime,
+    #[serde(rename = "End date")]
+    pub end_date: NaiveDate,
+    #[serde(rename = "End time")]
+    pub end_time: NaiveTime,
+    /// `Yes` or `No`
+    #[serde(rename = "Billable", default)]
+    pub billable: String,
+    /// Separated by commas
+    #[serde(rename = "Tags", default)]
+    pub tags: String,
+}
+
+impl TogglEntry {
+    fn start(&self, timezone: Tz) -> Result<Time> {
+        timezone
+            .from_local_datetime(&self.start_date.and_time(self.start_time))
+            .earliest()
+            .ok_or_else(|| eyre!("{} {} does not exist", self.start_date, self.start_time))
+    }
+
+    fn end(&self, timezone: Tz) -> Result<Time> {
+        timezone
+            .from_local_datetime(&self.end_date.and_time(self.end_time))
+            .earliest()
+            .ok_or_else(|| eyre!("{} {} does not exist", self.end_date, self.end_time))
+    }
+}
+
+/// Entries of the report, ordered by their start
+pub fn parse_entries(content: &str) -> Result<Vec<TogglEntry>> {
+    let mut entries = csv::Reader::from_reader(content.as_bytes())
+        .deserialize()
+        .collect::<Result<Vec<TogglEntry>, _>>()?;
+    entries.sort_by_key(|entry| (entry.start_date, entry.start_time));
+    Ok(entries)
+}
+
+/// Asks for the project id of each Toggl project, offering the configured project of the same id
+/// or name. An empty answer takes the offer, `-` leaves the entries without a project.
+pub fn ask_project_ids(
+    entries: &[TogglEntry],
+    projects: &[Project],
+    mut input: impl BufRead,
+    mut output: impl Write,
+) -> Result<BTreeMap<String, Option<String>>> {
+    let names: BTreeSet<_> = entries
+        .iter()
+        .map(|entry| entry.project.as_str())
+        .filter(|name| !name.is_empty())
+        .collect();
+    let mut ids = BTreeMap::new();
+    for name in names {
+        let offer = projects
+            .iter()
+            .find(|project| {
+                project.id.eq_ignore_ascii_case(name)
+                    || project
+                        .name
+                        .as_deref()
+                        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+            })
+            .map_or(name, |project| project.id.as_str());
+        write!(
+            output,
+            "Project id for Toggl project \"{}\" [{}]: ",
+            name, offer
+        )?;
+        output.flush()?;
+        let mut answer = String::new();
+        input.read_line(&mut answer)?;
+        let id = match answer.trim() {
+            "" => Some(offer.to_string()),
+            "-" => None,
+            id => Some(id.to_string()),
+        };
+        ids.insert(name.to_string(), id);
+    }
+    Ok(ids)
+}
+
+/// Checkpoints of the ordered entries, the projects are mapped by `project_ids` and the times
+/// are in the timezone
+pub fn to_checkpoints(
+    entries: &[TogglEntry],
+    project_ids: &BTreeMap<String, Option<String>>,
+    timezone: Tz,
+) -> Result<Vec<Checkpoint>> {
+    let mut checkpoints = vec![];
+    for (idx, entry) in entries.iter().enumerate() {
+        let mut checkpoint = Checkpoint {
+            time: entry.start(timezone)?,
+            project: project_ids.get(&entry.project).cloned().flatten(),
+            billable: entry.billable != "No",
+            ..Checkpoint::new()
+        };
+        if !entry.description.is_empty() {
+            checkpoint.set_message(entry.description.clone());
+        }
+        for tag in entry.tags.split(',').map(str::trim) {
+            if !tag.is_empty() && !checkpoint.tags.iter().any(|t| t == tag) {
+                checkpoint.tags.push(tag.to_string());
+            }
+        }
+        checkpoints.push(checkpoint);
+
+        let end = entry.end(timezone)?;
+        let next_start = entries
+            .get(idx + 1)
+            .map(|next| next.start(timezone))
+            .transpose()?;
+        if next_start.is_none_or(|start| start > end) {
+            checkpoints.push(Checkpoint {
+                time: end,
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            });
+        }
+    }
+    Ok(checkpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPORT: &str = "\
+User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags
+Me,me@example.com,,Website,,Fix login #bug,Yes,2025-01-06,08:00:00,2025-01-06,10:00:00,02:00:00,
+Me,me@example.com,,Meetings,,Standup,No,2025-01-06,10:00:00,2025-01-06,10:15:00,00:15:00,\"team, daily\"
+Me,me@example.com,,Website,,Deploy,Yes,2025-01-06,11:00:00,2025-01-06,12:00:00,01:00:00,
+";
+
+    #[test]
+    fn test_ask_project_ids() {
+        let entries = parse_entries(REPORT).unwrap();
+        let projects = vec![Project {
+            id: "WEB-1".to_string(),
+            name: Some("website".to_string()),
+            hotkey: None,
+            order: None,
+            activities: vec![],
+            archived: false,
+            default_message: None,
+            billing_code: None,
+            rate: None,
+            jira_issue: None,
+            toggl_project: None,
+            slack_status: None,
+            slack_emoji: None,
+        }];
+        let mut output = vec![];
+
+        let ids = ask_project_ids(&entries, &projects, "-\n\n".as_bytes(), &mut output).unwrap();
+
+        assert_eq!(ids["Meetings"], None);
+        assert_eq!(ids["Website"].as_deref(), Some("WEB-1"));
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .contains("\"Website\" [WEB-1]"));
+    }
+
+    #[test]
+    fn test_entries_to_checkpoints() {
+        let entries = parse_entries(REPORT).unwrap();
+        let ids = BTreeMap::from([
+            ("Website".to_string(), Some("WEB-1".to_string())),
+            ("Meetings".to_string(), None),
+        ]);
+
+        let checkpoints = to_checkpoints(&entries, &ids, chrono_tz::UTC).unwrap();
+
+        let summary: Vec<_> = checkpoints
+            .iter()
+            .map(|checkpoint| {
+                (
+                    checkpoint.time.format("%H:%M").to_string(),
+                    checkpoint.project.as_deref(),
+                    checkpoint.kind,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("08:00".to_string(), Some("WEB-1"), CheckpointKind::Work),
+                ("10:00".to_string(), None, CheckpointKind::Work),
+                ("10:15".to_string(), None, CheckpointKind::Stop),
+                ("11:00".to_string(), Some("WEB-1"), CheckpointKind::Work),
+                ("12:00".to_string(), None, CheckpointKind::Stop),
+            ]
+        );
+        assert_eq!(checkpoints[0].tags, vec!["bug".to_string()]);
+        assert_eq!(checkpoints[1].tags, vec!["team", "daily"]);
+        assert!(!checkpoints[1].billable);
+    }
+}
@@ -0,0 +1,255 @@
+use ::firestore::FirestoreDb;
+use chrono::{Datelike, NaiveDate, NaiveTime, Weekday};
+use clap::Args;
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::{failed, parse_date, print_json, Failure};
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::config::Config;
+use crate::firestore::{find_checkpoints_between, find_day_types};
+use crate::holidays::Holidays;
+use crate::time::{DayStats, Locale, TimeSettings};
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct VerifyArgs {
+    /// First checked day, the Monday of this week when left out
+    #[arg(long, value_parser = parse_date)]
+    pub from: Option<NaiveDate>,
+    /// Last checked day, inclusive, today when left out
+    #[arg(long, value_parser = parse_date)]
+    pub to: Option<NaiveDate>,
+    /// Longest stop between two spans of a day, in minutes
+    #[arg(long, default_value_t = 30)]
+    pub max_gap: u32,
+    /// Print the issues as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl VerifyArgs {
+    fn period(&self, today: NaiveDate, locale: &Locale) -> (NaiveDate, NaiveDate) {
+        let last = self.to.unwrap_or(today);
+        let first = self.from.unwrap_or_else(|| locale.week_start(last));
+        (first, last)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct Issue {
+    date: NaiveDate,
+    /// Start of the span, none for issues of the whole day
+    time: Option<NaiveTime>,
+    kind: IssueKind,
+    detail: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum IssueKind {
+    MissingProject,
+    MissingMessage,
+    Unregistered,
+    Gap,
+    UnderHours,
+}
+
+impl IssueKind {
+    fn label(self) -> &'static str {
+        match self {
+            IssueKind::MissingProject => "no project",
+            IssueKind::MissingMessage => "no message",
+            IssueKind::Unregistered => "unregistered",
+            IssueKind::Gap => "gap",
+            IssueKind::UnderHours => "under hours",
+        }
+    }
+}
+
+/// Checks the days of the period before they are submitted, failing when anything is off
+pub async fn verify(
+    db: &FirestoreDb,
+    config: &Config,
+    holidays: &Holidays,
+    args: VerifyArgs,
+) -> Result<()> {
+    let settings = config.time_settings();
+    let (first, last) = args.period(settings.now().date_naive(), &settings.locale);
+    let checkpoints = find_checkpoints_between(db, &first, &last, settings.timezone).await?;
+    let day_types = find_day_types(db).await?;
+    let expected_hours = config
+        .goals
+        .expected_hours_per_day
+        .or(config.goals.daily_target_hours);
+    let issues = find_issues(
+        first,
+        last,
+        &checkpoints,
+        expected_hours.map(|hours| (hours * 60.0).round() as u32),
+        args.max_gap,
+        &settings,
+        |date| day_types.contains_key(&date) || holidays.contains_key(&date),
+    );
+
+    if args.json {
+        print_json(&issues)?;
+    } else {
+        print!("{}", render(&issues, &settings.locale));
+    }
+    if !issues.is_empty() {
+        return Err(failed(
+            Failure::Unverified,
+            format!("{} issues found", issues.len()),
+        ));
+    }
+    Ok(())
+}
+
+/// Issues of the spans of the ordered checkpoints and of the working days under the expected
+/// minutes, weekends and days off expect nothing
+fn find_issues(
+    first: NaiveDate,
+    last: NaiveDate,
+    checkpoints: &[Checkpoint],
+    expected_minutes: Option<u32>,
+    max_gap: u32,
+    settings: &TimeSettings,
+    day_off: impl Fn(NaiveDate) -> bool,
+) -> Vec<Issue> {
+    let days: Vec<_> = checkpoints
+        .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+        .collect();
+    let mut issues = vec![];
+    for date in first.iter_days().take_while(|date| *date <= last) {
+        let day = days
+            .iter()
+            .find(|day| day[0].time.date_naive() == date)
+            .copied()
+            .unwrap_or_default();
+        for (pair, span) in day.windows(2).zip(settings.rounding.spans(day)) {
+            let start = &pair[0];
+            let issue = |kind, detail: String| Issue {
+                date,
+                time: Some(start.time.time()),
+                kind,
+                detail,
+            };
+            let label = start.project_label().unwrap_or_default();
+            if start.kind == CheckpointKind::Stop {
+                let minutes = (pair[1].time - start.time).num_minutes().max(0) as u32;
+                if minutes > max_gap {
+                    issues.push(issue(IssueKind::Gap, settings.duration(minutes)));
+                }
+                continue;
+            }
+            if !start.is_work() {
+                continue;
+            }
+            if start.project.as_deref().unwrap_or("").is_empty() {
+                let minutes = settings.duration(span.minutes());
+                issues.push(issue(IssueKind::MissingProject, minutes));
+            }
+            if start.message.as_deref().unwrap_or("").is_empty() {
+                issues.push(issue(IssueKind::MissingMessage, label.clone()));
+            }
+            if !start.registered {
+                issues.push(issue(IssueKind::Unregistered, label));
+            }
+        }
+
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let Some(expected) = expected_minutes.filter(|_| !weekend && !day_off(date)) else {
+            continue;
+        };
+        let tracked = DayStats::from_checkpoints(day, &settings.rounding).tracked_minutes;
+        if tracked < expected {
+            issues.push(Issue {
+                date,
+                time: None,
+                kind: IssueKind::UnderHours,
+                detail: format!(
+                    "{} of {}",
+                    settings.duration(tracked),
+                    settings.duration(expected)
+                ),
+            });
+        }
+    }
+    issues
+}
+
+fn render(issues: &[Issue], locale: &Locale) -> String {
+    if issues.is_empty() {
+        return "Everything is ready\n".to_string();
+    }
+    issues
+        .iter()
+        .map(|issue| {
+            let time = issue.time.map(|time| locale.time(time)).unwrap_or_default();
+            format!(
+                "{} {} {:<5}  {:<12}  {}\n",
+                issue.date.format("%a"),
+                locale.date(issue.date),
+                time,
+                issue.kind.label(),
+                issue.detail
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_find_issues() {
+        let unassigned = |hour, kind| Checkpoint {
+            time: at(hour, 0),
+            kind,
+            message: Some(String::new()),
+            registered: true,
+            ..Checkpoint::new()
+        };
+        let checkpoints = vec![
+            Checkpoint {
+                project: Some("ABC-1".to_string()),
+                message: Some("review".to_string()),
+                ..unassigned(8, CheckpointKind::Work)
+            },
+            unassigned(12, CheckpointKind::Stop),
+            unassigned(13, CheckpointKind::Work),
+            unassigned(17, CheckpointKind::Stop),
+        ];
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let settings = TimeSettings::default();
+
+        let issues = find_issues(
+            date(6),
+            date(8),
+            &checkpoints,
+            Some(480),
+            30,
+            &settings,
+            |date| date.day() == 8,
+        );
+
+        let summary: Vec<_> = issues
+            .iter()
+            .map(|issue| (issue.date.day(), issue.kind, issue.detail.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (6, IssueKind::Gap, "1h"),
+                (6, IssueKind::MissingProject, "4h"),
+                (6, IssueKind::MissingMessage, ""),
+                (7, IssueKind::UnderHours, "0m of 8h"),
+            ]
+        );
+        assert!(
+            render(&issues, &Locale::DEFAULT).starts_with("Mon 06.01.25 12:00  gap           1h\n")
+        );
+    }
+}
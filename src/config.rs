@@ -1,19 +1,662 @@
+use crate::calendar::CalendarConfig;
+use crate::jira::JiraConfig;
 use crate::pbs::AuthConfig;
+use crate::slack::SlackConfig;
+use crate::sync::TogglConfig;
+use crate::theme::ThemeConfig;
+use crate::time::{system_timezone, Locale, LunchBreak, Rounding, TimeSettings};
+use crate::webhook::WebhookConfig;
+use crate::{credentials, profile};
+use chrono::{NaiveDate, NaiveTime, Weekday};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::{fmt, io};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Left out by profiles that don't register their time in PBS
+    #[serde(default)]
     pub auth: AuthConfig,
     #[serde(default)]
     pub task_url_prefix: Option<String>,
+    /// Currency of the hourly rates of the projects, e.g. `EUR`
+    #[serde(default)]
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    #[serde(default)]
+    pub messages: MessagesConfig,
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    #[serde(default)]
+    pub goals: GoalsConfig,
+    #[serde(default)]
+    pub rounding: Rounding,
+    #[serde(default)]
+    pub locale: Locale,
+    /// IANA name of the timezone the times are shown in, e.g. `Europe/Prague`, the system
+    /// timezone when left out
+    #[serde(default)]
+    pub display_timezone: Option<chrono_tz::Tz>,
+    #[serde(default)]
+    pub holidays: HolidaysConfig,
+    #[serde(default)]
+    pub git: GitConfig,
+    /// Left out when the meetings are not imported from Google Calendar
+    #[serde(default)]
+    pub calendar: Option<CalendarConfig>,
+    /// Left out when no spans are submitted to Jira
+    #[serde(default)]
+    pub jira: Option<JiraConfig>,
+    /// Left out when the spans are not synced with Toggl Track
+    #[serde(default)]
+    pub toggl: Option<TogglConfig>,
+    /// Left out when the Slack status doesn't follow the running project
+    #[serde(default)]
+    pub slack: Option<SlackConfig>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Minutes without any input after which ending the running span is suggested, needs the
+    /// `idle` feature
+    #[serde(default)]
+    pub idle_minutes: Option<u32>,
+    #[serde(default)]
+    pub lunch_break: Option<LunchBreak>,
+    #[serde(default)]
+    pub remind: RemindConfig,
+    #[serde(default)]
+    pub firestore: FirestoreConfig,
+}
+
+/// Google Cloud project and database the checkpoints are stored in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FirestoreConfig {
+    pub project_id: String,
+    /// Every profile has a database of its own by default, it is named once the config is read
+    pub database_id: String,
+}
+
+impl Default for FirestoreConfig {
+    fn default() -> Self {
+        Self {
+            project_id: "double-vehicle-452318-e4".to_string(),
+            database_id: String::new(),
+        }
+    }
+}
+
+/// When `tcheater remind` expects new checkpoints. Without the table Monday to Friday
+/// 9:00-17:00 are checked, with it only the days it lists.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemindConfig {
+    /// Hours of working time without a new checkpoint after which to remind
+    #[serde(default = "default_remind_after_hours")]
+    pub after_hours: f32,
+    #[serde(default)]
+    pub mon: Option<WorkingHours>,
+    #[serde(default)]
+    pub tue: Option<WorkingHours>,
+    #[serde(default)]
+    pub wed: Option<WorkingHours>,
+    #[serde(default)]
+    pub thu: Option<WorkingHours>,
+    #[serde(default)]
+    pub fri: Option<WorkingHours>,
+    #[serde(default)]
+    pub sat: Option<WorkingHours>,
+    #[serde(default)]
+    pub sun: Option<WorkingHours>,
+    /// Hours a span may run before a notification tells so, none when left out
+    #[serde(default)]
+    pub span_hours: Option<f32>,
+    /// Notify once the tracked time of the day reaches `daily_target_hours` of [goals]
+    #[serde(default)]
+    pub target_reached: bool,
+    /// Time of day after which unregistered spans of the day are notified about
+    #[serde(default)]
+    pub unregistered_after: Option<NaiveTime>,
+}
+
+fn default_remind_after_hours() -> f32 {
+    2.0
+}
+
+impl Default for RemindConfig {
+    fn default() -> Self {
+        let office = Some(WorkingHours {
+            start: NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt(17, 0, 0).unwrap(),
+        });
+        Self {
+            after_hours: default_remind_after_hours(),
+            mon: office,
+            tue: office,
+            wed: office,
+            thu: office,
+            fri: office,
+            sat: None,
+            sun: None,
+            span_hours: None,
+            target_reached: false,
+            unregistered_after: None,
+        }
+    }
+}
+
+impl RemindConfig {
+    /// Working time of the weekday, `None` on days that are not checked
+    pub fn working_hours(&self, weekday: Weekday) -> Option<WorkingHours> {
+        match weekday {
+            Weekday::Mon => self.mon,
+            Weekday::Tue => self.tue,
+            Weekday::Wed => self.wed,
+            Weekday::Thu => self.thu,
+            Weekday::Fri => self.fri,
+            Weekday::Sat => self.sat,
+            Weekday::Sun => self.sun,
+        }
+    }
+}
+
+/// Working time of a day, written like `"9:00-17:00"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct WorkingHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl TryFrom<String> for WorkingHours {
+    type Error = String;
+
+    fn try_from(input: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("`{}` is not a working time like 9:00-17:00", input);
+        let (start, end) = input.split_once('-').ok_or_else(invalid)?;
+        let start = crate::time::parse_hh_mm(start).ok_or_else(invalid)?;
+        let end = crate::time::parse_hh_mm(end).ok_or_else(invalid)?;
+        if end <= start {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl From<WorkingHours> for String {
+    fn from(hours: WorkingHours) -> Self {
+        format!(
+            "{}-{}",
+            hours.start.format("%H:%M"),
+            hours.end.format("%H:%M")
+        )
+    }
+}
+
+/// Public holidays, they have no hours to track like the days marked as holidays
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct HolidaysConfig {
+    /// Names by date, e.g. `"2025-12-24" = "Christmas Eve"`
+    #[serde(default)]
+    pub dates: BTreeMap<NaiveDate, String>,
+    /// iCalendar file with the holidays as all-day events, relative to the home directory
+    #[serde(default)]
+    pub ics: Option<PathBuf>,
+}
+
+/// Local repositories whose commit messages are offered as comments of the spans they were made
+/// in
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct GitConfig {
+    /// Relative to the home directory
+    #[serde(default)]
+    pub repositories: Vec<PathBuf>,
+    /// Author of the commits, the `user.email` of each repository when left out
+    #[serde(default)]
+    pub author: Option<String>,
+}
+
+/// Hours to be tracked, the progress towards them is shown below the timeline
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GoalsConfig {
+    #[serde(default)]
+    pub daily_target_hours: Option<f32>,
+    #[serde(default)]
+    pub weekly_target_hours: Option<f32>,
+    /// Hours expected on every working day, the flex balance is the difference of the tracked
+    /// hours to them
+    #[serde(default)]
+    pub expected_hours_per_day: Option<f32>,
+    /// First day of the flex balance, the start of the current year when left out
+    #[serde(default)]
+    pub flex_since: Option<NaiveDate>,
+}
+
+impl GoalsConfig {
+    /// Targets with vacation, sick and holiday days taken out, such a day has nothing left to
+    /// track and takes its daily target off the weekly one
+    pub fn without_days_off(self, day_off: bool, week_days_off: u32) -> Self {
+        let daily = self.daily_target_hours.unwrap_or(0.0);
+        Self {
+            daily_target_hours: self
+                .daily_target_hours
+                .map(|hours| if day_off { 0.0 } else { hours }),
+            weekly_target_hours: self
+                .weekly_target_hours
+                .map(|hours| (hours - daily * week_days_off as f32).max(0.0)),
+            ..self
+        }
+    }
+}
+
+/// Frequently used comments offered in the message template popup
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MessagesConfig {
+    #[serde(default)]
+    pub templates: Vec<String>,
+}
+
+/// Which panes are shown below the timeline and how wide the project totals are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LayoutConfig {
+    pub show_totals: bool,
+    pub show_detail: bool,
+    pub show_input: bool,
+    pub totals_width: u16,
+    /// Draw the timelines with braille characters, two quarter hours per cell
+    pub braille: bool,
+    /// Show the rounded times, as they are reported, instead of the stored ones
+    pub rounded_times: bool,
+    /// Show Saturday and Sunday below the working days
+    pub show_weekend: bool,
+    /// Durations in decimal hours, e.g. 7.75h instead of 7h45m, in the summaries too
+    pub decimal_hours: bool,
+}
+
+impl LayoutConfig {
+    pub const MIN_TOTALS_WIDTH: u16 = 20;
+    pub const MAX_TOTALS_WIDTH: u16 = 80;
+
+    /// Change the width of the project totals by `delta` columns within the allowed range
+    pub fn resize_totals(&mut self, delta: i16) {
+        self.totals_width = self
+            .totals_width
+            .saturating_add_signed(delta)
+            .clamp(Self::MIN_TOTALS_WIDTH, Self::MAX_TOTALS_WIDTH);
+    }
+}
+
+impl Default for LayoutConfig {
+    fn default() -> Self {
+        Self {
+            show_totals: true,
+            show_detail: true,
+            show_input: true,
+            totals_width: 40,
+            braille: false,
+            rounded_times: false,
+            show_weekend: false,
+            decimal_hours: false,
+        }
+    }
+}
+
+/// The login with the password stored in the keyring when neither the file nor the environment
+/// set one
+pub fn with_stored_password(mut auth: AuthConfig) -> AuthConfig {
+    if auth.password.is_empty() && !auth.username.is_empty() {
+        // Without a reachable keyring the login fails and doctor tells why
+        if let Ok(Some(password)) = credentials::load_password(&auth.username) {
+            auth.password = password;
+        }
+    }
+    auth
+}
+
+/// Writes the layout into the `[layout]` table of the config file keeping the rest of it,
+/// including comments, untouched
+pub fn save_layout<P: AsRef<Path>>(
+    path: P,
+    layout: &LayoutConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let content = fs::read_to_string(&path)?;
+    fs::write(&path, with_layout(&content, layout)?)?;
+    Ok(())
+}
+
+fn with_layout(content: &str, layout: &LayoutConfig) -> Result<String, toml_edit::TomlError> {
+    let mut doc = content.parse::<toml_edit::DocumentMut>()?;
+    let table = doc["layout"].or_insert(toml_edit::table());
+    table["show_totals"] = toml_edit::value(layout.show_totals);
+    table["show_detail"] = toml_edit::value(layout.show_detail);
+    table["show_input"] = toml_edit::value(layout.show_input);
+    table["totals_width"] = toml_edit::value(layout.totals_width as i64);
+    table["braille"] = toml_edit::value(layout.braille);
+    table["rounded_times"] = toml_edit::value(layout.rounded_times);
+    table["show_weekend"] = toml_edit::value(layout.show_weekend);
+    table["decimal_hours"] = toml_edit::value(layout.decimal_hours);
+    Ok(doc.to_string())
 }
 
 impl Config {
-    pub fn from_toml_file<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(path)?;
-        let config: Config = toml::from_str(&content)?;
+    /// The config file with the `TCHEATER_*` environment variables layered over it. Without a
+    /// password in either, the one stored in the keyring is taken.
+    pub fn from_toml_file<P: AsRef<Path>>(
+        path: P,
+        profile: Option<&str>,
+    ) -> Result<Self, ConfigError> {
+        let mut config = Self::read(path, profile)?;
+        config.auth = with_stored_password(config.auth);
+        Ok(config)
+    }
+
+    /// The config file with the `TCHEATER_*` environment variables layered over it, without
+    /// asking the keyring, which may keep the caller waiting
+    pub fn read<P: AsRef<Path>>(path: P, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::from_toml(path, &content, profile, |name| std::env::var(name).ok())
+    }
+
+    fn from_toml(
+        path: &Path,
+        content: &str,
+        profile: Option<&str>,
+        env: impl Fn(&str) -> Option<String>,
+    ) -> Result<Self, ConfigError> {
+        let deserializer = toml::Deserializer::parse(content)
+            .map_err(|err| ConfigError::invalid(path, content, None, err))?;
+        let mut config: Config = serde_path_to_error::deserialize(deserializer).map_err(|err| {
+            let key = err.path().to_string();
+            let key = (key != ".").then_some(key);
+            ConfigError::invalid(path, content, key, err.into_inner())
+        })?;
+        config.override_from(env)?;
+        if config.firestore.database_id.is_empty() {
+            config.firestore.database_id = profile::database_id(profile);
+        }
         Ok(config)
     }
+
+    /// How the times are shown and measured
+    pub fn time_settings(&self) -> TimeSettings {
+        TimeSettings {
+            timezone: self.display_timezone.unwrap_or_else(system_timezone),
+            rounding: self.rounding,
+            locale: self.locale,
+            decimal_hours: self.layout.decimal_hours,
+        }
+    }
+
+    /// Replaces the settings whose variables are set, an empty variable counts as unset
+    fn override_from(&mut self, env: impl Fn(&str) -> Option<String>) -> Result<(), ConfigError> {
+        let var = |name: &str| env(name).filter(|value| !value.is_empty());
+        if let Some(login_url) = var("TCHEATER_PBS_LOGIN_URL") {
+            self.auth.login_url = login_url;
+        }
+        if let Some(username) = var("TCHEATER_PBS_USERNAME") {
+            self.auth.username = username;
+        }
+        if let Some(password) = var("TCHEATER_PBS_PASSWORD") {
+            self.auth.password = password;
+        }
+        if let Some(project_id) = var("TCHEATER_FIRESTORE_PROJECT") {
+            self.firestore.project_id = project_id;
+        }
+        if let Some(database_id) = var("TCHEATER_FIRESTORE_DATABASE") {
+            self.firestore.database_id = database_id;
+        }
+        if let Some(prefix) = var("TCHEATER_TASK_URL_PREFIX") {
+            self.task_url_prefix = Some(prefix);
+        }
+        if let Some(timezone) = var("TCHEATER_DISPLAY_TIMEZONE") {
+            let timezone = timezone.parse().map_err(|_| ConfigError::Env {
+                var: "TCHEATER_DISPLAY_TIMEZONE",
+                message: format!("`{}` is not a timezone", timezone),
+            })?;
+            self.display_timezone = Some(timezone);
+        }
+        if let Some(api_token) = var("TCHEATER_JIRA_API_TOKEN") {
+            if let Some(jira) = &mut self.jira {
+                jira.api_token = api_token;
+            }
+        }
+        if let Some(api_token) = var("TCHEATER_TOGGL_API_TOKEN") {
+            if let Some(toggl) = &mut self.toggl {
+                toggl.api_token = api_token;
+            }
+        }
+        if let Some(token) = var("TCHEATER_SLACK_TOKEN") {
+            if let Some(slack) = &mut self.slack {
+                slack.token = token;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why the config could not be loaded
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read
+    Read { path: PathBuf, source: io::Error },
+    /// The file is not TOML, or a setting in it is missing or of the wrong type
+    Invalid {
+        path: PathBuf,
+        /// Line and column of the offending value, both from 1
+        position: Option<(usize, usize)>,
+        /// TOML path of the setting, e.g. `layout.totals_width`, none at the top level
+        key: Option<String>,
+        message: String,
+    },
+    /// An overriding environment variable holds a value the setting can't take
+    Env { var: &'static str, message: String },
+}
+
+impl ConfigError {
+    fn invalid(path: &Path, content: &str, key: Option<String>, err: toml::de::Error) -> Self {
+        let position = err.span().map(|span| {
+            let before = &content[..span.start.min(content.len())];
+            let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+            (
+                before.matches('\n').count() + 1,
+                before[line_start..].chars().count() + 1,
+            )
+        });
+        ConfigError::Invalid {
+            path: path.to_path_buf(),
+            position,
+            key,
+            message: err.message().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read { path, source } => {
+                write!(f, "{} could not be read: {}", path.display(), source)
+            }
+            ConfigError::Invalid {
+                path,
+                position,
+                key,
+                message,
+            } => {
+                write!(f, "{}", path.display())?;
+                if let Some((line, column)) = position {
+                    write!(f, ":{}:{}", line, column)?;
+                }
+                write!(f, ": {}", message)?;
+                if let Some(key) = key {
+                    write!(f, " at `{}`", key)?;
+                }
+                Ok(())
+            }
+            ConfigError::Env { var, message } => write!(f, "{}: {}", var, message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ConfigError::Read { source, .. } => Some(source),
+            ConfigError::Invalid { .. } | ConfigError::Env { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_layout_keeps_comments() {
+        let content = "# credentials\n[auth]\nusername = \"me\"\n";
+        let layout = LayoutConfig {
+            show_detail: false,
+            ..LayoutConfig::default()
+        };
+
+        let updated = with_layout(content, &layout).unwrap();
+
+        assert!(updated.starts_with(content));
+        let parsed: toml::Table = toml::from_str(&updated).unwrap();
+        assert_eq!(parsed["layout"]["show_detail"].as_bool(), Some(false));
+        assert_eq!(parsed["layout"]["totals_width"].as_integer(), Some(40));
+    }
+
+    #[test]
+    fn test_goals_without_days_off() {
+        let goals = GoalsConfig {
+            daily_target_hours: Some(8.0),
+            weekly_target_hours: Some(40.0),
+            ..GoalsConfig::default()
+        };
+
+        let adjusted = goals.without_days_off(true, 2);
+
+        assert_eq!(adjusted.daily_target_hours, Some(0.0));
+        assert_eq!(adjusted.weekly_target_hours, Some(24.0));
+        assert_eq!(goals.without_days_off(false, 0), goals);
+        assert_eq!(
+            GoalsConfig::default().without_days_off(true, 5),
+            GoalsConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_holiday_dates() {
+        let holidays: HolidaysConfig = toml::from_str(
+            r#"dates = { "2025-12-24" = "Christmas Eve" }
+ics = "holidays.ics""#,
+        )
+        .unwrap();
+
+        let date = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        assert_eq!(holidays.dates[&date], "Christmas Eve");
+        assert_eq!(holidays.ics, Some(PathBuf::from("holidays.ics")));
+    }
+
+    #[test]
+    fn test_goals_accept_whole_hours() {
+        let goals: GoalsConfig = toml::from_str("daily_target_hours = 8").unwrap();
+
+        assert_eq!(goals.daily_target_hours, Some(8.0));
+        assert_eq!(goals.weekly_target_hours, None);
+
+        let goals: GoalsConfig =
+            toml::from_str("expected_hours_per_day = 8\nflex_since = \"2025-01-01\"").unwrap();
+        assert_eq!(goals.expected_hours_per_day, Some(8.0));
+        assert_eq!(goals.flex_since, NaiveDate::from_ymd_opt(2025, 1, 1));
+    }
+
+    #[test]
+    fn test_remind_working_hours() {
+        let remind: RemindConfig =
+            toml::from_str("after_hours = 1.5\nsat = \"10:00-12:30\"").unwrap();
+
+        assert_eq!(remind.after_hours, 1.5);
+        assert_eq!(remind.working_hours(Weekday::Mon), None);
+        let saturday = remind.working_hours(Weekday::Sat).unwrap();
+        assert_eq!(saturday.start, NaiveTime::from_hms_opt(10, 0, 0).unwrap());
+        assert_eq!(String::from(saturday), "10:00-12:30");
+
+        let remind: RemindConfig = toml::from_str("unregistered_after = \"16:00\"").unwrap();
+        assert_eq!(remind.unregistered_after, NaiveTime::from_hms_opt(16, 0, 0));
+        assert_eq!(remind.span_hours, None);
+
+        assert!(RemindConfig::default()
+            .working_hours(Weekday::Fri)
+            .is_some());
+        assert!(toml::from_str::<RemindConfig>("mon = \"17:00-9:00\"").is_err());
+    }
+
+    #[test]
+    fn test_env_overrides() {
+        let content = "[auth]\nlogin_url = \"https://pbs/login\"\nusername = \"me\"\n";
+        let env = |name: &str| match name {
+            "TCHEATER_PBS_PASSWORD" => Some("secret".to_string()),
+            "TCHEATER_FIRESTORE_PROJECT" => Some("ci-project".to_string()),
+            "TCHEATER_PBS_USERNAME" => Some(String::new()),
+            _ => None,
+        };
+
+        let config = Config::from_toml(Path::new("config.toml"), content, None, env).unwrap();
+
+        assert_eq!(config.auth.username, "me");
+        assert_eq!(config.auth.password, "secret");
+        assert_eq!(config.firestore.project_id, "ci-project");
+        assert_eq!(config.firestore.database_id, "tcheater");
+
+        let content =
+            "[jira]\nurl = \"https://example.atlassian.net\"\nemail = \"me@example.com\"\n";
+        let env = |name: &str| (name == "TCHEATER_JIRA_API_TOKEN").then(|| "token".to_string());
+        let config = Config::from_toml(Path::new("config.toml"), content, None, env).unwrap();
+        assert_eq!(config.jira.unwrap().api_token, "token");
+
+        let env = |name: &str| (name == "TCHEATER_DISPLAY_TIMEZONE").then(|| "Mars".to_string());
+        let err = Config::from_toml(Path::new("config.toml"), content, None, env).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TCHEATER_DISPLAY_TIMEZONE: `Mars` is not a timezone"
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let path = Path::new("config.toml");
+        let auth = "[auth]\nlogin_url = \"https://pbs/login\"\nusername = \"me\"\n";
+        let load = |content: &str| Config::from_toml(path, content, None, |_| None).unwrap_err();
+
+        let err = load(&format!("{}[layout]\ntotals_width = \"wide\"\n", auth));
+        let ConfigError::Invalid { position, key, .. } = &err else {
+            panic!("{:?} is not invalid", err);
+        };
+        assert_eq!(*position, Some((5, 16)));
+        assert_eq!(key.as_deref(), Some("layout.totals_width"));
+        assert!(err.to_string().starts_with("config.toml:5:16: "));
+
+        let err = load("[auth]\nusername = \"me\"\n");
+        assert!(err.to_string().contains("missing field `login_url`"));
+
+        let err = load("[auth\n");
+        assert!(matches!(err, ConfigError::Invalid { key: None, .. }));
+    }
+
+    #[test]
+    fn test_resize_totals() {
+        let mut layout = LayoutConfig::default();
+        layout.resize_totals(100);
+        assert_eq!(layout.totals_width, LayoutConfig::MAX_TOTALS_WIDTH);
+        layout.resize_totals(-100);
+        assert_eq!(layout.totals_width, LayoutConfig::MIN_TOTALS_WIDTH);
+    }
 }
@@ -0,0 +1,26 @@
+use keyring::{Entry, Error};
+
+/// Service the password is stored under in the keyring
+const SERVICE: &str = "tcheater";
+
+/// The stored password of the user, `None` when there is none
+pub fn load_password(username: &str) -> Result<Option<String>, Error> {
+    match Entry::new(SERVICE, username)?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(Error::NoEntry) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+pub fn save_password(username: &str, password: &str) -> Result<(), Error> {
+    Entry::new(SERVICE, username)?.set_password(password)
+}
+
+/// Removes the stored password, `false` when there was none
+pub fn delete_password(username: &str) -> Result<bool, Error> {
+    match Entry::new(SERVICE, username)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(Error::NoEntry) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+
+use crate::projects::Project;
+
+/// Hourly rates of the projects that have one
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Rates {
+    rates: BTreeMap<String, f64>,
+    currency: Option<String>,
+}
+
+impl Rates {
+    pub fn new(projects: &[Project], currency: Option<String>) -> Self {
+        Self {
+            rates: projects
+                .iter()
+                .filter_map(|project| Some((project.id.clone(), project.rate?)))
+                .collect(),
+            currency,
+        }
+    }
+
+    /// No project has a rate, nothing is earned
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Earned with the minutes of the project, `None` when it has no rate
+    pub fn earned(&self, project: &str, minutes: u32) -> Option<f64> {
+        self.rates
+            .get(project)
+            .map(|rate| rate * minutes as f64 / 60.0)
+    }
+
+    /// Earned with the minutes of all the projects, `None` when none of them has a rate
+    pub fn total(&self, project_minutes: &[(String, u32)]) -> Option<f64> {
+        project_minutes
+            .iter()
+            .filter_map(|(project, minutes)| self.earned(project, *minutes))
+            .reduce(|a, b| a + b)
+    }
+
+    pub fn currency(&self) -> Option<&str> {
+        self.currency.as_deref()
+    }
+
+    /// The amount with two decimals and the currency, e.g. `1234.50 EUR`
+    pub fn format(&self, amount: f64) -> String {
+        match &self.currency {
+            Some(currency) => format!("{:.2} {}", amount, currency),
+            None => format!("{:.2}", amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::projects::parse_projects;
+
+    #[test]
+    fn test_earnings() {
+        let projects = parse_projects(
+            "[[projects]]\nid = \"ABC-1\"\nrate = 80\n[[projects]]\nid = \"ABC-2\"\nrate = 60.5",
+        )
+        .unwrap();
+        let rates = Rates::new(&projects, Some("EUR".to_string()));
+        let minutes = vec![
+            ("ABC-1".to_string(), 90),
+            ("ABC-2".to_string(), 60),
+            ("-".to_string(), 30),
+        ];
+
+        assert_eq!(rates.earned("ABC-1", 90), Some(120.0));
+        assert_eq!(rates.earned("-", 30), None);
+        assert_eq!(rates.total(&minutes), Some(180.5));
+        assert_eq!(rates.format(180.5), "180.50 EUR");
+        assert_eq!(rates.total(&minutes[2..]), None);
+        assert!(Rates::new(&[], None).is_empty());
+    }
+}
@@ -1,24 +1,56 @@
-use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Days, NaiveDate, NaiveTime, TimeZone};
+use chrono_tz::Tz;
 use firestore::*;
 use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Checkpoint, DayType};
+use crate::config::FirestoreConfig;
+use crate::state::{self, Tracking};
+use crate::time::TimeSettings;
 
-use crate::app::Checkpoint;
+/// Most writes Firestore takes in a single batch
+const BATCH_LIMIT: usize = 500;
 
-pub async fn connect() -> FirestoreResult<FirestoreDb> {
+pub async fn connect(config: &FirestoreConfig) -> FirestoreResult<FirestoreDb> {
     FirestoreDb::with_options(
-        FirestoreDbOptions::new("double-vehicle-452318-e4".into())
-            .with_database_id("tcheater".into()),
+        FirestoreDbOptions::new(config.project_id.clone())
+            .with_database_id(config.database_id.clone()),
     )
     .await
 }
 
+/// Checkpoints of the day in the timezone, ordered by time
 pub async fn find_checkpoints(
     db: &FirestoreDb,
     day: &NaiveDate,
+    timezone: Tz,
+) -> FirestoreResult<Vec<Checkpoint>> {
+    find_checkpoints_between(db, day, day, timezone).await
+}
+
+/// Checkpoints from the start of `first` to the end of `last` in the timezone, ordered by time
+pub async fn find_checkpoints_between(
+    db: &FirestoreDb,
+    first: &NaiveDate,
+    last: &NaiveDate,
+    timezone: Tz,
 ) -> FirestoreResult<Vec<Checkpoint>> {
-    // Calculate start and end of today in UTC
-    let start_of_day = day.and_hms_opt(0, 0, 0).unwrap();
-    let end_of_day = day.and_hms_opt(23, 59, 59).unwrap();
+    let midnight = |date: NaiveDate| {
+        timezone
+            .from_local_datetime(&date.and_time(NaiveTime::MIN))
+            .earliest()
+    };
+    let (Some(start_of_day), Some(end_of_day)) = (midnight(*first), midnight(*last + Days::new(1)))
+    else {
+        return Ok(vec![]);
+    };
+    // Times stored with a local offset before `tcheater migrate` don't compare with the UTC ones
+    // as strings, a day more on each side covers any offset
+    let query_start = utc_time::format(start_of_day - Days::new(1));
+    let query_end = utc_time::format(end_of_day + Days::new(1));
 
     let stream = db
         .fluent()
@@ -27,40 +59,287 @@ pub async fn find_checkpoints(
         .filter(|q| {
             q.for_all([
                 q.field(path!(Checkpoint::time))
-                    .greater_than_or_equal(start_of_day),
-                q.field(path!(Checkpoint::time))
-                    .less_than_or_equal(end_of_day),
+                    .greater_than_or_equal(&query_start),
+                q.field(path!(Checkpoint::time)).less_than(&query_end),
             ])
         })
-        .order_by([(path!(Checkpoint::time), FirestoreQueryDirection::Ascending)])
         .obj()
         .stream_query_with_errors()
         .await?;
-    stream.try_collect().await
+    let checkpoints: Vec<Checkpoint> = stream.try_collect().await?;
+    let mut checkpoints: Vec<Checkpoint> = in_timezone(checkpoints, timezone)
+        .filter(|checkpoint| checkpoint.time >= start_of_day && checkpoint.time < end_of_day)
+        .collect();
+    checkpoints.sort_by_key(|checkpoint| checkpoint.time);
+    Ok(checkpoints)
+}
+
+/// The loaded checkpoints with their times in the timezone
+fn in_timezone(checkpoints: Vec<Checkpoint>, timezone: Tz) -> impl Iterator<Item = Checkpoint> {
+    checkpoints.into_iter().map(move |checkpoint| Checkpoint {
+        time: checkpoint.time.with_timezone(&timezone),
+        ..checkpoint
+    })
+}
+
+/// Checkpoint times are stored in UTC, so that they keep their order across timezones and DST
+/// transitions. They are loaded in UTC too and converted to the display timezone by the queries.
+pub mod utc_time {
+    use chrono::{DateTime, FixedOffset, SecondsFormat, TimeZone, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::time::Time;
+
+    /// RFC 3339 in UTC to the second, these strings sort in time order
+    pub fn format<Tz: TimeZone>(time: DateTime<Tz>) -> String {
+        time.with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// The stored time in the format of [`format`], `None` when it already is or is no time
+    pub fn migrated(stored: &str) -> Option<String> {
+        let time = DateTime::parse_from_rfc3339(stored).ok()?;
+        Some(format(time)).filter(|migrated| migrated != stored)
+    }
+
+    pub fn serialize<S: Serializer>(time: &Time, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format(*time))
+    }
+
+    /// Also reads the times stored with a local offset before they were kept in UTC
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Time, D::Error> {
+        let time = DateTime::<FixedOffset>::deserialize(deserializer)?;
+        Ok(time.with_timezone(&chrono_tz::UTC))
+    }
+}
+
+/// Free-form notes about a day, stored in a document named after the date
+#[derive(Default, Serialize, Deserialize)]
+struct DayNote {
+    text: String,
+}
+
+fn day_id(date: &NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// Notes of the day, empty when there are none
+pub async fn find_note(db: &FirestoreDb, date: &NaiveDate) -> FirestoreResult<String> {
+    let note: Option<DayNote> = db
+        .fluent()
+        .select()
+        .by_id_in("notes")
+        .obj()
+        .one(day_id(date))
+        .await?;
+    Ok(note.unwrap_or_default().text)
+}
+
+/// Replaces the notes of the day, empty notes are deleted
+pub async fn save_note(db: &FirestoreDb, date: &NaiveDate, text: String) -> FirestoreResult<()> {
+    if text.is_empty() {
+        return db
+            .fluent()
+            .delete()
+            .from("notes")
+            .document_id(day_id(date))
+            .execute()
+            .await;
+    }
+    let _: DayNote = db
+        .fluent()
+        .update()
+        .in_col("notes")
+        .document_id(day_id(date))
+        .object(&DayNote { text })
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Caches what runs today for `tcheater status` of the profile once checkpoints of the date
+/// were written, other dates are not read
+pub async fn refresh_tracking(
+    db: &FirestoreDb,
+    date: &NaiveDate,
+    settings: &TimeSettings,
+    profile: Option<&str>,
+) -> FirestoreResult<()> {
+    let now = settings.now();
+    if *date != now.date_naive() {
+        return Ok(());
+    }
+    let checkpoints = find_checkpoints(db, date, settings.timezone).await?;
+    // Only a cache, the checkpoints are stored either way
+    let _ = state::save(
+        Tracking::from_checkpoints(&checkpoints, now).as_ref(),
+        profile,
+    );
+    Ok(())
+}
+
+/// Marks a day whose checkpoints must not change, e.g. because they were already invoiced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockedDay {
+    /// The date, as the name of the document
+    #[serde(alias = "_firestore_id", skip_serializing)]
+    id: Option<String>,
+}
+
+/// Dates of all locked days
+pub async fn find_locked_days(db: &FirestoreDb) -> FirestoreResult<BTreeSet<NaiveDate>> {
+    let stream = db
+        .fluent()
+        .select()
+        .from("locked_days")
+        .obj()
+        .stream_query_with_errors()
+        .await?;
+    let days: Vec<LockedDay> = stream.try_collect().await?;
+    Ok(days
+        .into_iter()
+        .filter_map(|day| NaiveDate::parse_from_str(day.id.as_deref()?, "%Y-%m-%d").ok())
+        .collect())
 }
 
+pub async fn set_day_locked(
+    db: &FirestoreDb,
+    date: &NaiveDate,
+    locked: bool,
+) -> FirestoreResult<()> {
+    if !locked {
+        return db
+            .fluent()
+            .delete()
+            .from("locked_days")
+            .document_id(day_id(date))
+            .execute()
+            .await;
+    }
+    let _: LockedDay = db
+        .fluent()
+        .update()
+        .in_col("locked_days")
+        .document_id(day_id(date))
+        .object(&LockedDay { id: None })
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Vacation, sick day or holiday, named after the date
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DayTypeDoc {
+    #[serde(alias = "_firestore_id", skip_serializing)]
+    id: Option<String>,
+    kind: DayType,
+}
+
+/// Types of all days marked as not working
+pub async fn find_day_types(db: &FirestoreDb) -> FirestoreResult<BTreeMap<NaiveDate, DayType>> {
+    let stream = db
+        .fluent()
+        .select()
+        .from("day_types")
+        .obj()
+        .stream_query_with_errors()
+        .await?;
+    let days: Vec<DayTypeDoc> = stream.try_collect().await?;
+    Ok(days
+        .into_iter()
+        .filter_map(|day| {
+            let date = NaiveDate::parse_from_str(day.id.as_deref()?, "%Y-%m-%d").ok()?;
+            Some((date, day.kind))
+        })
+        .collect())
+}
+
+/// Marks the day with the type, `None` makes it a working day again
+pub async fn set_day_type(
+    db: &FirestoreDb,
+    date: &NaiveDate,
+    kind: Option<DayType>,
+) -> FirestoreResult<()> {
+    let Some(kind) = kind else {
+        return db
+            .fluent()
+            .delete()
+            .from("day_types")
+            .document_id(day_id(date))
+            .execute()
+            .await;
+    };
+    let _: DayTypeDoc = db
+        .fluent()
+        .update()
+        .in_col("day_types")
+        .document_id(day_id(date))
+        .object(&DayTypeDoc { id: None, kind })
+        .execute()
+        .await?;
+    Ok(())
+}
+
+/// Distinct messages used with the project, most recently used first
+pub async fn find_project_messages(
+    db: &FirestoreDb,
+    project: &str,
+) -> FirestoreResult<Vec<String>> {
+    let stream = db
+        .fluent()
+        .select()
+        .from("checkpoints")
+        .filter(|q| q.for_all([q.field(path!(Checkpoint::project)).eq(project)]))
+        .obj()
+        .stream_query_with_errors()
+        .await?;
+    let mut checkpoints: Vec<Checkpoint> = stream.try_collect().await?;
+    // Times stored with a local offset before `tcheater migrate` don't sort as strings
+    checkpoints.sort_by_key(|checkpoint| std::cmp::Reverse(checkpoint.time));
+
+    let mut messages: Vec<String> = vec![];
+    for message in checkpoints.into_iter().filter_map(|ch| ch.message) {
+        if !message.is_empty() && !messages.contains(&message) {
+            messages.push(message);
+        }
+    }
+    Ok(messages)
+}
+
+/// Inserts the checkpoint under its id, or under a generated one if it has none
 pub async fn insert_checkpoint(
     db: &FirestoreDb,
-    checkpoint: Checkpoint,
+    mut checkpoint: Checkpoint,
 ) -> FirestoreResult<Checkpoint> {
+    // The id is the name of the document rather than one of its fields
+    let id = checkpoint.id.take().unwrap_or_default();
     db.fluent()
         .insert()
         .into("checkpoints")
-        .document_id(String::new())
+        .document_id(id)
         .object(&checkpoint)
         .execute()
         .await
 }
 
+fn checkpoint_fields() -> Vec<String> {
+    vec![
+        path!(Checkpoint::time),
+        path!(Checkpoint::project),
+        path!(Checkpoint::activity),
+        path!(Checkpoint::message),
+        path!(Checkpoint::registered),
+        path!(Checkpoint::kind),
+        path!(Checkpoint::tags),
+        path!(Checkpoint::billable),
+        path!(Checkpoint::toggl_id),
+    ]
+}
+
 pub async fn update_checkpoint(db: &FirestoreDb, ch: &Checkpoint) -> FirestoreResult<Checkpoint> {
     db.fluent()
         .update()
-        .fields(vec![
-            path!(Checkpoint::time),
-            path!(Checkpoint::project),
-            path!(Checkpoint::message),
-            path!(Checkpoint::registered),
-        ])
+        .fields(checkpoint_fields())
         .in_col("checkpoints")
         .document_id(ch.id.as_ref().unwrap())
         .object(ch)
@@ -77,17 +356,129 @@ pub async fn delete_checkpoint(db: &FirestoreDb, ch: &Checkpoint) -> FirestoreRe
         .await
 }
 
-pub async fn find_distinct_dates(db: &FirestoreDb) -> FirestoreResult<Vec<chrono::NaiveDate>> {
+/// Updates all given checkpoints in batched writes of at most [`BATCH_LIMIT`] each.
+pub async fn update_checkpoints(db: &FirestoreDb, chs: &[Checkpoint]) -> FirestoreResult<()> {
+    let writer = db.create_simple_batch_writer().await?;
+    for chunk in chs.chunks(BATCH_LIMIT) {
+        let mut batch = writer.new_batch();
+        for ch in chunk {
+            db.fluent()
+                .update()
+                .fields(checkpoint_fields())
+                .in_col("checkpoints")
+                .document_id(ch.id.as_ref().unwrap())
+                .object(ch)
+                .add_to_batch(&mut batch)?;
+        }
+        batch.write().await?;
+    }
+    Ok(())
+}
+
+/// Deletes all given checkpoints in batched writes of at most [`BATCH_LIMIT`] each.
+pub async fn delete_checkpoints(db: &FirestoreDb, chs: &[Checkpoint]) -> FirestoreResult<()> {
+    let writer = db.create_simple_batch_writer().await?;
+    for chunk in chs.chunks(BATCH_LIMIT) {
+        let mut batch = writer.new_batch();
+        for ch in chunk {
+            db.fluent()
+                .delete()
+                .from("checkpoints")
+                .document_id(ch.id.as_ref().unwrap())
+                .add_to_batch(&mut batch)?;
+        }
+        batch.write().await?;
+    }
+    Ok(())
+}
+
+pub async fn find_all_checkpoints(
+    db: &FirestoreDb,
+    timezone: Tz,
+) -> FirestoreResult<Vec<Checkpoint>> {
     let stream = db
         .fluent()
         .select()
         .from("checkpoints")
-        .order_by([(path!(Checkpoint::time), FirestoreQueryDirection::Ascending)])
         .obj()
         .stream_query_with_errors()
         .await?;
-
     let checkpoints: Vec<Checkpoint> = stream.try_collect().await?;
+    let mut checkpoints: Vec<Checkpoint> = in_timezone(checkpoints, timezone).collect();
+    checkpoints.sort_by_key(|checkpoint| checkpoint.time);
+    Ok(checkpoints)
+}
+
+/// A checkpoint's time as it is stored
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredTime {
+    #[serde(alias = "_firestore_id", skip_serializing)]
+    id: Option<String>,
+    time: String,
+}
+
+/// Ids of the checkpoints whose time was stored with a local offset or below the second, with
+/// the time stored in UTC instead
+pub async fn find_unmigrated_times(db: &FirestoreDb) -> FirestoreResult<Vec<(String, String)>> {
+    let stream = db
+        .fluent()
+        .select()
+        .from("checkpoints")
+        .obj()
+        .stream_query_with_errors()
+        .await?;
+    let stored: Vec<StoredTime> = stream.try_collect().await?;
+    Ok(stored
+        .into_iter()
+        .filter_map(|stored| Some((stored.id?, utc_time::migrated(&stored.time)?)))
+        .collect())
+}
+
+/// Rewrites the times of the checkpoints in batched writes of at most [`BATCH_LIMIT`] each
+pub async fn save_times(db: &FirestoreDb, times: &[(String, String)]) -> FirestoreResult<()> {
+    let writer = db.create_simple_batch_writer().await?;
+    for chunk in times.chunks(BATCH_LIMIT) {
+        let mut batch = writer.new_batch();
+        for (id, time) in chunk {
+            db.fluent()
+                .update()
+                .fields([path!(Checkpoint::time)])
+                .in_col("checkpoints")
+                .document_id(id)
+                .object(&StoredTime {
+                    id: None,
+                    time: time.clone(),
+                })
+                .add_to_batch(&mut batch)?;
+        }
+        batch.write().await?;
+    }
+    Ok(())
+}
+
+/// Finds checkpoints whose project or message contains the query, newest first.
+///
+/// Firestore has no substring queries, so the filtering is done on the client.
+pub async fn search_checkpoints(
+    db: &FirestoreDb,
+    query: &str,
+    timezone: Tz,
+) -> FirestoreResult<Vec<Checkpoint>> {
+    let mut checkpoints: Vec<Checkpoint> = find_all_checkpoints(db, timezone)
+        .await?
+        .into_iter()
+        .filter(|checkpoint| checkpoint.matches(query))
+        .collect();
+    checkpoints.reverse();
+    Ok(checkpoints)
+}
+
+/// Dates with checkpoints in the timezone
+pub async fn find_distinct_dates(
+    db: &FirestoreDb,
+    timezone: Tz,
+) -> FirestoreResult<Vec<chrono::NaiveDate>> {
+    let checkpoints = find_all_checkpoints(db, timezone).await?;
 
     let mut dates: Vec<chrono::NaiveDate> = checkpoints
         .iter()
@@ -99,3 +490,38 @@ pub async fn find_distinct_dates(db: &FirestoreDb) -> FirestoreResult<Vec<chrono
 
     Ok(dates)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::Time;
+
+    #[derive(Serialize, Deserialize)]
+    struct Stored {
+        #[serde(with = "utc_time")]
+        time: Time,
+    }
+
+    #[test]
+    fn test_times_are_stored_in_utc() {
+        let stored: Stored = toml::from_str(r#"time = "2025-01-06T09:00:00+01:00""#).unwrap();
+        let utc = chrono::Utc.with_ymd_and_hms(2025, 1, 6, 8, 0, 0).unwrap();
+
+        assert_eq!(stored.time, utc);
+        assert_eq!(stored.time.timezone(), chrono_tz::UTC);
+        assert_eq!(
+            toml::to_string(&stored).unwrap().trim(),
+            r#"time = "2025-01-06T08:00:00Z""#
+        );
+    }
+
+    #[test]
+    fn test_migrated_times() {
+        assert_eq!(
+            utc_time::migrated("2025-01-06T00:30:00.123+01:00").as_deref(),
+            Some("2025-01-05T23:30:00Z")
+        );
+        assert_eq!(utc_time::migrated("2025-01-05T23:30:00Z"), None);
+        assert_eq!(utc_time::migrated("yesterday"), None);
+    }
+}
@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+use color_eyre::{eyre::eyre, Result};
+
+use crate::time::Time;
+
+/// Separates the fields of a commit in the output of `git log`
+const FIELD_SEPARATOR: char = '\u{1f}';
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commit {
+    /// Author date
+    pub time: Time,
+    /// Name of the directory of the repository
+    pub repository: String,
+    /// First line of the message
+    pub subject: String,
+}
+
+/// Commits authored between `start` and `end` in all the repositories, ordered by time.
+///
+/// The author is the `user.email` of each repository when not given.
+pub fn commits(
+    repositories: &[PathBuf],
+    author: Option<&str>,
+    start: Time,
+    end: Time,
+) -> Result<Vec<Commit>> {
+    let mut commits = vec![];
+    for repository in repositories {
+        commits.extend(
+            log(repository, author, start, end)?
+                .into_iter()
+                .filter(|commit| commit.time >= start && commit.time <= end),
+        );
+    }
+    commits.sort_by_key(|commit| commit.time);
+    Ok(commits)
+}
+
+fn log(repository: &Path, author: Option<&str>, start: Time, end: Time) -> Result<Vec<Commit>> {
+    let author = match author {
+        Some(author) => Some(author.to_string()),
+        None => git(repository, &["config", "user.email"])
+            .ok()
+            .map(|email| email.trim().to_string()),
+    };
+    let mut args = vec![
+        "log".to_string(),
+        "--all".to_string(),
+        "--no-merges".to_string(),
+        format!("--since={}", start.to_rfc3339()),
+        format!("--until={}", end.to_rfc3339()),
+        format!("--format=%aI{}%s", FIELD_SEPARATOR),
+    ];
+    if let Some(author) = author {
+        args.push(format!("--author={}", author));
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = git(repository, &args)?;
+    let name = repository.file_name().map_or_else(
+        || repository.display().to_string(),
+        |name| name.to_string_lossy().to_string(),
+    );
+    Ok(parse_log(&name, &output, start.timezone()))
+}
+
+/// Runs git in the repository and returns its standard output
+fn git(repository: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(args)
+        .output()
+        .map_err(|err| eyre!("Failed to run git: {}", err))?;
+    if !output.status.success() {
+        return Err(eyre!(
+            "git {} failed in {}: {}",
+            args[0],
+            repository.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Commits of the output of `git log` with the format `%aI<separator>%s`, lines that don't match
+/// it are skipped. The times are in the timezone.
+fn parse_log(repository: &str, output: &str, timezone: Tz) -> Vec<Commit> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (time, subject) = line.split_once(FIELD_SEPARATOR)?;
+            let time = DateTime::parse_from_rfc3339(time).ok()?;
+            Some(Commit {
+                time: time.with_timezone(&timezone),
+                repository: repository.to_string(),
+                subject: subject.trim().to_string(),
+            })
+        })
+        .filter(|commit| !commit.subject.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = "2025-01-06T09:15:00+01:00\u{1f}Fix the login form\n\
+                      not a commit\n\
+                      2025-01-06T10:00:00+01:00\u{1f}\n";
+
+        let commits = parse_log("tcheater", output, chrono_tz::UTC);
+
+        assert_eq!(
+            commits,
+            vec![Commit {
+                time: chrono_tz::Europe::Prague
+                    .with_ymd_and_hms(2025, 1, 6, 9, 15, 0)
+                    .unwrap()
+                    .with_timezone(&chrono_tz::UTC),
+                repository: "tcheater".to_string(),
+                subject: "Fix the login form".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{Days, NaiveDate};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::config::HolidaysConfig;
+
+/// Public holidays by date with their names
+pub type Holidays = BTreeMap<NaiveDate, String>;
+
+/// Holidays listed in the config together with the ones of its ICS file, a relative path is
+/// looked up in `dir`. Names from the config win for dates found in both.
+pub fn load_holidays(
+    config: &HolidaysConfig,
+    dir: &Path,
+) -> Result<Holidays, Box<dyn std::error::Error>> {
+    let mut holidays = match &config.ics {
+        Some(path) => parse_ics(&fs::read_to_string(dir.join(path))?),
+        None => Holidays::new(),
+    };
+    holidays.extend(config.dates.clone());
+    Ok(holidays)
+}
+
+/// All-day events of an iCalendar file, such as the public holidays exported from a calendar
+fn parse_ics(content: &str) -> Holidays {
+    // Long lines are folded by starting the continuation with a space or a tab
+    let mut lines: Vec<String> = vec![];
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continuation), Some(last)) => last.push_str(continuation),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut holidays = Holidays::new();
+    let (mut start, mut end, mut summary) = (None, None, None);
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters such as `;VALUE=DATE` follow the property name
+        let name = name.split(';').next().unwrap_or_default();
+        let date = || {
+            value
+                .get(..8)
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y%m%d").ok())
+        };
+        match name {
+            "BEGIN" if value == "VEVENT" => (start, end, summary) = (None, None, None),
+            "DTSTART" => start = date(),
+            "DTEND" => end = date(),
+            "SUMMARY" => summary = Some(value.replace("\\,", ",").replace("\\;", ";")),
+            "END" if value == "VEVENT" => {
+                let Some(first) = start else {
+                    continue;
+                };
+                // The end date is exclusive, one-day events may leave it out
+                let last = end
+                    .and_then(|end| end.pred_opt())
+                    .unwrap_or(first)
+                    .max(first);
+                let mut date = first;
+                while date <= last {
+                    holidays.insert(date, summary.clone().unwrap_or_default());
+                    date = date + Days::new(1);
+                }
+            }
+            _ => {}
+        }
+    }
+    holidays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ics() {
+        let content = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20251224\r\n\
+            DTEND;VALUE=DATE:20251227\r\n\
+            SUMMARY:Christmas\\, Czech\r\n  Republic\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20260101\r\n\
+            SUMMARY;LANGUAGE=cs:Nový rok\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        let holidays = parse_ics(content);
+
+        assert_eq!(holidays.len(), 4);
+        assert_eq!(holidays[&date(2025, 12, 24)], "Christmas, Czech Republic");
+        assert!(holidays.contains_key(&date(2025, 12, 26)));
+        assert!(!holidays.contains_key(&date(2025, 12, 27)));
+        assert_eq!(holidays[&date(2026, 1, 1)], "Nový rok");
+    }
+
+    #[test]
+    fn test_load_holidays_from_config() {
+        let date = NaiveDate::from_ymd_opt(2025, 12, 24).unwrap();
+        let config = HolidaysConfig {
+            dates: [(date, "Štědrý den".to_string())].into(),
+            ics: None,
+        };
+
+        let holidays = load_holidays(&config, Path::new(".")).unwrap();
+
+        assert_eq!(holidays[&date], "Štědrý den");
+    }
+}
@@ -0,0 +1,84 @@
+use std::time::Duration;
+
+/// How long the user has been idle, `None` when it can't be told
+#[cfg(feature = "idle")]
+pub fn idle_time() -> Option<Duration> {
+    if cfg!(target_os = "macos") {
+        return output("ioreg", &["-c", "IOHIDSystem", "-d", "4"])
+            .and_then(|out| parse_ioreg(&out));
+    }
+    output(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ],
+    )
+    .and_then(|out| parse_gdbus(&out))
+    .or_else(|| output("xprintidle", &[]).and_then(|out| parse_millis(&out)))
+}
+
+/// Without the `idle` feature the user never counts as idle
+#[cfg(not(feature = "idle"))]
+pub fn idle_time() -> Option<Duration> {
+    None
+}
+
+#[cfg(feature = "idle")]
+fn output(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Milliseconds printed by `xprintidle`
+#[cfg_attr(not(feature = "idle"), allow(dead_code))]
+fn parse_millis(out: &str) -> Option<Duration> {
+    out.trim().parse().ok().map(Duration::from_millis)
+}
+
+/// Milliseconds in the reply of the idle monitor, e.g. `(uint64 12345,)`
+#[cfg_attr(not(feature = "idle"), allow(dead_code))]
+fn parse_gdbus(out: &str) -> Option<Duration> {
+    let millis = out.trim().strip_prefix("(uint64 ")?.strip_suffix(",)")?;
+    parse_millis(millis)
+}
+
+/// Nanoseconds of the `HIDIdleTime` property, e.g. `"HIDIdleTime" = 12345678`
+#[cfg_attr(not(feature = "idle"), allow(dead_code))]
+fn parse_ioreg(out: &str) -> Option<Duration> {
+    let line = out.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos = line.split('=').nth(1)?.trim().parse().ok()?;
+    Some(Duration::from_nanos(nanos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_idle_outputs() {
+        assert_eq!(parse_millis("1500\n"), Some(Duration::from_millis(1500)));
+        assert_eq!(
+            parse_gdbus("(uint64 60000,)\n"),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            parse_ioreg("    | |   \"HIDIdleTime\" = 2000000000\n"),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(parse_gdbus("Error: no such name"), None);
+    }
+}
@@ -0,0 +1,124 @@
+use color_eyre::{eyre::bail, Result};
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::projects::{self, Project};
+use crate::worklog::{Worklog, WorklogSink};
+
+/// Jira the work of the projects mapped to issues is logged in. The token can be left out of
+/// the file and set in `TCHEATER_JIRA_API_TOKEN`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct JiraConfig {
+    /// Address of the site, e.g. `https://example.atlassian.net`
+    pub url: String,
+    /// Account the worklogs are logged by
+    pub email: String,
+    #[serde(default)]
+    pub api_token: String,
+}
+
+/// Whether the text is an issue key like `WEB-12`
+pub fn is_issue_key(text: &str) -> bool {
+    let Some((key, number)) = text.split_once('-') else {
+        return false;
+    };
+    key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Logs the work as worklogs of Jira issues
+pub struct JiraSink {
+    config: JiraConfig,
+    client: Client,
+}
+
+impl JiraSink {
+    pub fn new(config: JiraConfig) -> Self {
+        Self {
+            config,
+            client: Client::new(),
+        }
+    }
+}
+
+impl WorklogSink for JiraSink {
+    fn name(&self) -> &'static str {
+        "Jira"
+    }
+
+    /// The issue of the project in projects.toml, or the project itself when it is an issue key
+    fn target(&self, project: &str, projects: &[Project]) -> Option<String> {
+        let project = project.trim();
+        projects::find(projects, project)
+            .and_then(|found| found.jira_issue.clone())
+            .or_else(|| is_issue_key(project).then(|| project.to_string()))
+    }
+
+    fn submit<'a>(&'a self, worklog: &'a Worklog) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let url = format!(
+                "{}/rest/api/2/issue/{}/worklog",
+                self.config.url.trim_end_matches('/'),
+                worklog.target
+            );
+            let response = self
+                .client
+                .post(url)
+                .basic_auth(&self.config.email, Some(&self.config.api_token))
+                .json(&body(worklog))
+                .send()
+                .await?;
+            let status = response.status();
+            if !status.is_success() {
+                bail!("Jira answered {}", status);
+            }
+            Ok(())
+        })
+    }
+}
+
+fn body(worklog: &Worklog) -> Value {
+    json!({
+        "started": worklog.start.format("%Y-%m-%dT%H:%M:%S%.3f%z").to_string(),
+        "timeSpentSeconds": worklog.minutes * 60,
+        "comment": worklog.description,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use chrono_tz::Europe::Prague;
+
+    use super::*;
+
+    #[test]
+    fn test_is_issue_key() {
+        assert!(is_issue_key("WEB-12"));
+        assert!(is_issue_key("OPS2-7"));
+        assert!(!is_issue_key("web-12"));
+        assert!(!is_issue_key("WEB-"));
+        assert!(!is_issue_key("119627"));
+        assert!(!is_issue_key("WEB-12a"));
+    }
+
+    #[test]
+    fn test_body() {
+        let start = Prague.with_ymd_and_hms(2025, 1, 6, 8, 0, 0).unwrap();
+        let worklog = Worklog::new("WEB-12".to_string(), start, 90, Some("review"));
+        assert_eq!(
+            body(&worklog),
+            json!({
+                "started": "2025-01-06T08:00:00.000+0100",
+                "timeSpentSeconds": 5400,
+                "comment": "review",
+            })
+        );
+    }
+}
@@ -1,60 +1,332 @@
-use std::env;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 
+use ::firestore::FirestoreDb;
 pub use app::App;
-use chrono::{Datelike, Local};
+use clap::{CommandFactory, Parser};
+use clap_complete::CompleteEnv;
+use cli::{Cli, Command, Failure, ReportFormat, TuiArgs};
+use config::Config;
 use directories::UserDirs;
-use time::get_mondays_in_month;
+use earnings::Rates;
+use holidays::Holidays;
+use projects::Project;
+use time::{get_week_starts_in_month, TimeSettings};
 
 pub mod app;
+pub mod calendar;
+pub mod cli;
 pub mod config;
+pub mod credentials;
+pub mod earnings;
 pub mod firestore;
+pub mod git;
+pub mod holidays;
+pub mod idle;
+pub mod jira;
+mod note_editor;
+pub mod notification;
 pub mod pbs;
+pub mod profile;
+pub mod projects;
+pub mod reload;
+pub mod slack;
+pub mod span;
+pub mod state;
+pub mod stats;
+mod summary;
+pub mod sync;
+pub mod theme;
 pub mod time;
 pub mod timeline_widget;
+pub mod webhook;
 pub mod widgets;
+pub mod worklog;
 
 #[tokio::main]
 async fn main() {
-    let db = match firestore::connect().await {
+    CompleteEnv::with_factory(Cli::command)
+        .var(cli::COMPLETE_VAR)
+        .complete();
+    let cli = Cli::parse();
+    let profile = cli.profile.clone();
+    let profile = profile.as_deref();
+    match cli.into_command() {
+        Command::Tui(args) => run_tui(profile, args).await,
+        Command::Add(args) => {
+            let Setup {
+                db,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            exit_on_error(
+                cli::add(&db, &config.webhooks, &settings, profile, args).await,
+                false,
+            )
+        }
+        Command::Log(args) => {
+            let Setup {
+                db,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            exit_on_error(
+                cli::log(&db, &config.webhooks, &settings, profile, args).await,
+                false,
+            )
+        }
+        Command::Edit(args) => {
+            let Setup { db, settings, .. } = setup(profile).await;
+            exit_on_error(cli::edit(&db, &settings, profile, args).await, false)
+        }
+        Command::Report(args) => {
+            let json = args.format() == ReportFormat::Json;
+            let Setup {
+                db,
+                home_dir,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            let projects = load_projects(&home_dir, profile);
+            let rates = Rates::new(&projects, config.currency);
+            exit_on_error(
+                cli::report(&db, &projects, &rates, &settings, args).await,
+                json,
+            )
+        }
+        Command::Invoice(args) => {
+            let Setup {
+                db,
+                home_dir,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            let projects = load_projects(&home_dir, profile);
+            let rates = Rates::new(&projects, config.currency);
+            exit_on_error(
+                cli::invoice(&db, &projects, &rates, &settings, args).await,
+                false,
+            )
+        }
+        Command::Export(args) => {
+            let json = args.json();
+            let Setup {
+                db,
+                home_dir,
+                settings,
+                ..
+            } = setup(profile).await;
+            exit_on_error(
+                cli::export(&db, &load_projects(&home_dir, profile), &settings, args).await,
+                json,
+            )
+        }
+        Command::Import(args) => {
+            let Setup {
+                db,
+                home_dir,
+                settings,
+                ..
+            } = setup(profile).await;
+            let projects = load_projects(&home_dir, profile);
+            exit_on_error(
+                cli::import(&db, &projects, &settings, profile, args).await,
+                false,
+            )
+        }
+        Command::Status(args) => {
+            let json = args.json;
+            exit_on_error(cli::status(profile, args), json)
+        }
+        Command::Verify(args) => {
+            let json = args.json;
+            let Setup {
+                db,
+                home_dir,
+                config,
+                ..
+            } = setup(profile).await;
+            let holidays = load_holidays(&config, &home_dir);
+            exit_on_error(cli::verify(&db, &config, &holidays, args).await, json)
+        }
+        Command::Remind(args) => {
+            let Setup {
+                db,
+                home_dir,
+                config,
+                ..
+            } = setup(profile).await;
+            let holidays = load_holidays(&config, &home_dir);
+            exit_on_error(cli::remind(&db, &config, &holidays, args).await, false)
+        }
+        Command::Auth(args) => {
+            let path = profile::path_in(&home_dir(), profile, "config.toml");
+            let config = load_config(&path, profile);
+            exit_on_error(cli::auth(&config.auth, args).await, false)
+        }
+        Command::Calendar(args) => {
+            let path = profile::path_in(&home_dir(), profile, "config.toml");
+            let config = load_config(&path, profile);
+            exit_on_error(cli::calendar(&config, profile, args).await, false)
+        }
+        Command::Doctor => exit_on_error(cli::doctor(profile).await, false),
+        Command::Migrate(args) => {
+            exit_on_error(cli::migrate(&setup(profile).await.db, args).await, false)
+        }
+        Command::Completions(args) => exit_on_error(cli::completions(args), false),
+        Command::Submit(args) => {
+            let json = args.json;
+            let Setup {
+                db,
+                home_dir,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            let projects = load_projects(&home_dir, profile);
+            let sinks = worklog::sinks(&config);
+            let webhooks = &config.webhooks;
+            exit_on_error(
+                cli::submit(&db, sinks, &projects, webhooks, &settings, profile, args).await,
+                json,
+            )
+        }
+        Command::Sync(args) => {
+            let Setup {
+                db,
+                home_dir,
+                config,
+                settings,
+                ..
+            } = setup(profile).await;
+            let projects = load_projects(&home_dir, profile);
+            let toggl = config.toggl.as_ref();
+            exit_on_error(
+                cli::sync(&db, toggl, &projects, &settings, profile, args).await,
+                false,
+            )
+        }
+    }
+}
+
+/// Ends a command without the week view, failing with the error and the exit code of its
+/// [`Failure`]. Given `--json`, the error is written as JSON too.
+fn exit_on_error(result: color_eyre::Result<()>, json: bool) {
+    if let Err(err) = result {
+        let failure = Failure::of(&err);
+        if json {
+            let error = serde_json::json!({ "error": err.to_string(), "failure": failure });
+            eprintln!("{}", error);
+        } else {
+            eprintln!("{}", err);
+        }
+        exit(failure.exit_code());
+    }
+}
+
+const SEE_DOCTOR: &str = "\nRun `tcheater doctor` to check the setup";
+
+/// What every command needs: the store and the config of the profile from the home directory
+struct Setup {
+    db: FirestoreDb,
+    home_dir: PathBuf,
+    config_path: PathBuf,
+    config: Config,
+    /// How the commands show and measure the times
+    settings: TimeSettings,
+}
+
+async fn setup(profile: Option<&str>) -> Setup {
+    let home_dir = home_dir();
+    let config_path = profile::path_in(&home_dir, profile, "config.toml");
+    let config = load_config(&config_path, profile);
+    let settings = config.time_settings();
+
+    let db = match firestore::connect(&config.firestore).await {
         Ok(db) => db,
         Err(err) => {
-            eprint!("{}", err);
-            exit(1)
+            eprintln!("Failed to connect to Firestore: {}{}", err, SEE_DOCTOR);
+            exit(Failure::Store.exit_code())
         }
     };
 
-    let home_dir = match UserDirs::new() {
+    Setup {
+        db,
+        home_dir,
+        config_path,
+        config,
+        settings,
+    }
+}
+
+fn home_dir() -> PathBuf {
+    match UserDirs::new() {
         Some(user_dirs) => user_dirs.home_dir().to_path_buf(),
-        None => exit(1),
-    };
+        None => {
+            eprintln!("The home directory was not found{}", SEE_DOCTOR);
+            exit(1)
+        }
+    }
+}
+
+fn load_config(path: &Path, profile: Option<&str>) -> Config {
+    Config::from_toml_file(path, profile).unwrap_or_else(|err| {
+        eprintln!("Failed to load config.toml: {}{}", err, SEE_DOCTOR);
+        exit(1);
+    })
+}
+
+fn load_holidays(config: &Config, home_dir: &Path) -> Holidays {
+    holidays::load_holidays(&config.holidays, home_dir).unwrap_or_else(|err| {
+        eprintln!("Failed to load holidays: {}{}", err, SEE_DOCTOR);
+        exit(1);
+    })
+}
+
+fn load_projects(home_dir: &Path, profile: Option<&str>) -> Vec<Project> {
+    let path = profile::path_in(home_dir, profile, "projects.toml");
+    projects::load_projects(path).unwrap_or_else(|err| {
+        eprintln!("Failed to load projects.toml: {}{}", err, SEE_DOCTOR);
+        exit(1);
+    })
+}
 
-    let config =
-        config::Config::from_toml_file(home_dir.join("config.toml")).unwrap_or_else(|_| {
-            eprintln!("Failed to load config.toml");
-            exit(1);
-        });
+async fn run_tui(profile: Option<&str>, args: TuiArgs) {
+    let Setup {
+        db,
+        home_dir,
+        config_path,
+        config,
+        settings,
+    } = setup(profile).await;
 
-    // Get month and year from command line arguments or use current
-    let now = Local::now();
-    let month = env::args()
-        .nth(1)
-        .and_then(|arg| arg.parse::<u32>().ok())
-        .filter(|&m| (1..=12).contains(&m))
-        .unwrap_or_else(|| now.month());
+    let holidays = load_holidays(&config, &home_dir);
 
-    let year = env::args()
-        .nth(2)
-        .and_then(|arg| arg.parse::<i32>().ok())
-        .unwrap_or_else(|| now.year());
+    let projects = load_projects(&home_dir, profile);
 
-    let mondays = get_mondays_in_month(year, month);
+    let (year, month) = args.year_and_month(settings.now().date_naive());
+    let mondays = get_week_starts_in_month(year, month, &settings.locale);
 
     color_eyre::install().unwrap();
     let terminal = ratatui::init();
-    if let Err(err) = App::new(db, mondays, config.auth, config.task_url_prefix)
-        .run(terminal)
-        .await
+    let profile = profile.map(str::to_string);
+    if let Err(err) = App::new(
+        db,
+        mondays,
+        config,
+        config_path,
+        profile,
+        projects,
+        holidays,
+    )
+    .with_start_date(args.date)
+    .with_project_filter(args.project)
+    .run(terminal)
+    .await
     {
         eprintln!("{}", err);
     }
@@ -0,0 +1,164 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+/// Multi-line text being edited, the cursor column counts characters rather than bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NoteEditor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for NoteEditor {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl NoteEditor {
+    /// Starts editing the text with the cursor at its end
+    pub fn new(text: &str) -> Self {
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        let row = lines.len() - 1;
+        let col = lines[row].chars().count();
+        Self { lines, row, col }
+    }
+
+    /// The edited text without trailing whitespace
+    pub fn text(&self) -> String {
+        self.lines.join("\n").trim_end().to_string()
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Row and column of the cursor
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char(c) => {
+                let idx = self.byte_idx();
+                self.lines[self.row].insert(idx, c);
+                self.col += 1;
+            }
+            KeyCode::Enter => {
+                let idx = self.byte_idx();
+                let rest = self.lines[self.row].split_off(idx);
+                self.row += 1;
+                self.lines.insert(self.row, rest);
+                self.col = 0;
+            }
+            KeyCode::Backspace => {
+                if self.col > 0 {
+                    self.col -= 1;
+                    let idx = self.byte_idx();
+                    self.lines[self.row].remove(idx);
+                } else if self.row > 0 {
+                    let line = self.lines.remove(self.row);
+                    self.row -= 1;
+                    self.col = self.line_len();
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Delete => {
+                if self.col < self.line_len() {
+                    let idx = self.byte_idx();
+                    self.lines[self.row].remove(idx);
+                } else if self.row + 1 < self.lines.len() {
+                    let line = self.lines.remove(self.row + 1);
+                    self.lines[self.row].push_str(&line);
+                }
+            }
+            KeyCode::Left => {
+                if self.col > 0 {
+                    self.col -= 1;
+                } else if self.row > 0 {
+                    self.row -= 1;
+                    self.col = self.line_len();
+                }
+            }
+            KeyCode::Right => {
+                if self.col < self.line_len() {
+                    self.col += 1;
+                } else if self.row + 1 < self.lines.len() {
+                    self.row += 1;
+                    self.col = 0;
+                }
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len());
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => {}
+        }
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    /// Byte index of the cursor in its line
+    fn byte_idx(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(idx, _)| idx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    fn type_keys(editor: &mut NoteEditor, codes: &[KeyCode]) {
+        for code in codes {
+            editor.handle_key(KeyEvent::new(*code, KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn test_note_editor_lines() {
+        let mut editor = NoteEditor::new("home office");
+        assert_eq!(editor.cursor(), (0, 11));
+
+        type_keys(
+            &mut editor,
+            &[KeyCode::Enter, KeyCode::Char('š'), KeyCode::Char('x')],
+        );
+        assert_eq!(editor.text(), "home office\nšx");
+
+        type_keys(&mut editor, &[KeyCode::Left, KeyCode::Backspace]);
+        assert_eq!(editor.text(), "home office\nx");
+
+        type_keys(&mut editor, &[KeyCode::Backspace]);
+        assert_eq!(editor.text(), "home officex");
+        assert_eq!(editor.cursor(), (0, 11));
+    }
+
+    #[test]
+    fn test_note_editor_moves() {
+        let mut editor = NoteEditor::new("partial\nsick day\n");
+        assert_eq!(editor.lines().len(), 2);
+        assert_eq!(editor.cursor(), (1, 8));
+
+        type_keys(&mut editor, &[KeyCode::Up, KeyCode::Delete]);
+        assert_eq!(editor.cursor(), (0, 7));
+        assert_eq!(editor.text(), "partialsick day");
+
+        type_keys(&mut editor, &[KeyCode::Home, KeyCode::Char('a')]);
+        assert_eq!(editor.text(), "apartialsick day");
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::BTreeSet;
+
+use chrono::NaiveDate;
+use notify_rust::Notification;
+
+use crate::app::Checkpoint;
+use crate::config::RemindConfig;
+use crate::time::{running_span, DayStats, Time, TimeSettings};
+
+/// Shows a notification with the summary as its title
+pub fn notify(summary: &str, body: &str) -> Result<(), notify_rust::error::Error> {
+    Notification::new()
+        .appname("tcheater")
+        .summary(summary)
+        .body(body)
+        .show()?;
+    Ok(())
+}
+
+/// Something about the day worth a notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Reminder {
+    /// The span starting at the time runs longer than `span_hours`
+    LongSpan(Time),
+    TargetReached,
+    Unregistered,
+}
+
+/// Reminders of the current day, each of them is shown once
+#[derive(Debug, Default)]
+pub struct Reminders {
+    date: Option<NaiveDate>,
+    shown: BTreeSet<Reminder>,
+}
+
+impl Reminders {
+    /// Summaries and bodies of the notifications due for today's checkpoints at `now`, those
+    /// shown before are left out
+    pub fn due(
+        &mut self,
+        config: &RemindConfig,
+        settings: &TimeSettings,
+        daily_target_hours: Option<f32>,
+        checkpoints: &[Checkpoint],
+        now: Time,
+    ) -> Vec<(String, String)> {
+        if self.date != Some(now.date_naive()) {
+            self.date = Some(now.date_naive());
+            self.shown.clear();
+        }
+        let stats = DayStats::from_checkpoints(checkpoints, &settings.rounding);
+        let running = running_span(checkpoints, now).filter(|(running, _)| running.is_work());
+        let mut due = vec![];
+
+        if let (Some((running, minutes)), Some(hours)) = (running, config.span_hours) {
+            if minutes as f32 >= hours * 60.0 {
+                let body = format!(
+                    "{} runs for {} since {}",
+                    running.project.as_deref().unwrap_or("The span"),
+                    settings.duration(minutes),
+                    settings.locale.time(running.time.time())
+                );
+                due.push((Reminder::LongSpan(running.time), "Long span", body));
+            }
+        }
+        let tracked = stats.tracked_minutes + running.map_or(0, |(_, minutes)| minutes);
+        if let Some(target) = daily_target_hours.filter(|_| config.target_reached) {
+            if target > 0.0 && tracked as f32 >= target * 60.0 {
+                let body = format!("{} tracked today", settings.duration(tracked));
+                due.push((Reminder::TargetReached, "Daily target reached", body));
+            }
+        }
+        if let Some(after) = config.unregistered_after {
+            if now.time() >= after && stats.unregistered_spans > 0 {
+                let body = format!(
+                    "{} spans of today are not registered",
+                    stats.unregistered_spans
+                );
+                due.push((Reminder::Unregistered, "Unregistered spans", body));
+            }
+        }
+
+        due.into_iter()
+            .filter(|(reminder, _, _)| self.shown.insert(*reminder))
+            .map(|(_, summary, body)| (summary.to_string(), body))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::app::CheckpointKind;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_reminders() {
+        let config = RemindConfig {
+            span_hours: Some(3.0),
+            target_reached: true,
+            unregistered_after: NaiveTime::from_hms_opt(16, 0, 0),
+            ..RemindConfig::default()
+        };
+        let mut checkpoints = vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("web".to_string()),
+                registered: true,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 0),
+                kind: CheckpointKind::Break,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 30),
+                project: Some("api".to_string()),
+                ..Checkpoint::new()
+            },
+        ];
+        let mut reminders = Reminders::default();
+        let settings = TimeSettings::default();
+        let summaries = |due: Vec<(String, String)>| -> Vec<String> {
+            due.into_iter().map(|(summary, _)| summary).collect()
+        };
+
+        assert!(reminders
+            .due(&config, &settings, Some(8.0), &checkpoints, at(14, 0))
+            .is_empty());
+        let due = reminders.due(&config, &settings, Some(8.0), &checkpoints, at(15, 45));
+        assert_eq!(
+            due,
+            [(
+                "Long span".to_string(),
+                "api runs for 3h15m since 12:30".to_string()
+            )]
+        );
+        assert!(reminders
+            .due(&config, &settings, Some(8.0), &checkpoints, at(15, 50))
+            .is_empty());
+
+        checkpoints.push(Checkpoint {
+            time: at(16, 0),
+            kind: CheckpointKind::Stop,
+            ..Checkpoint::new()
+        });
+        assert_eq!(
+            summaries(reminders.due(&config, &settings, Some(7.0), &checkpoints, at(16, 5))),
+            ["Daily target reached", "Unregistered spans"]
+        );
+
+        let tomorrow = at(16, 5) + chrono::Days::new(1);
+        assert!(reminders
+            .due(&config, &settings, Some(7.0), &[], tomorrow)
+            .is_empty());
+    }
+}
@@ -2,10 +2,13 @@ use libxml::parser::Parser;
 use libxml::xpath::Context;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Login to PBS, the password can be left out of the file and set in `TCHEATER_PBS_PASSWORD`
+/// or stored in the keyring with `tcheater auth set`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub login_url: String,
     pub username: String,
+    #[serde(default)]
     pub password: String,
 }
 
@@ -16,9 +19,12 @@ pub struct PbsTask {
     pub time_total: Option<String>,
 }
 
+use chrono::NaiveDate;
 use reqwest::{redirect::Policy, Client};
 use std::collections::HashMap;
 
+const TASK_DETAIL_URL: &str = "https://pbs2.praguebest.cz/main.php?pageid=110&action=detail";
+
 async fn login(config: &AuthConfig) -> Result<Client, Box<dyn std::error::Error>> {
     let client = Client::builder()
         .redirect(Policy::none())
@@ -54,6 +60,58 @@ pub async fn fetch_tasks(config: &AuthConfig) -> Result<Vec<PbsTask>, Box<dyn st
     parse_tasks_from_html(&html)
 }
 
+/// Work spent on a task, as entered in the work form of the task detail
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkEntry {
+    pub task_id: i32,
+    pub date: NaiveDate,
+    pub minutes: u32,
+    pub description: String,
+    pub billing_code: Option<String>,
+}
+
+impl WorkEntry {
+    fn form(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("action", "addwork".to_string()),
+            ("taskID", self.task_id.to_string()),
+            ("date", self.date.format("%d.%m.%Y").to_string()),
+            ("hours", format!("{:.2}", self.minutes as f64 / 60.0)),
+            ("description", self.description.clone()),
+            ("billingCode", self.billing_code.clone().unwrap_or_default()),
+        ]
+    }
+}
+
+/// Client logged into PBS, for submitting several entries under one login
+pub struct Session {
+    client: Client,
+}
+
+impl Session {
+    pub async fn login(config: &AuthConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(Self {
+            client: login(config).await?,
+        })
+    }
+
+    pub async fn submit(&self, entry: &WorkEntry) -> Result<(), Box<dyn std::error::Error>> {
+        let response = self
+            .client
+            .post(format!("{}&id={}", TASK_DETAIL_URL, entry.task_id))
+            .form(&entry.form())
+            .send()
+            .await?;
+        // The form redirects back to the task detail once the work is saved
+        let status = response.status();
+        if status.is_success() || status.is_redirection() {
+            Ok(())
+        } else {
+            Err(format!("PBS answered {}", status).into())
+        }
+    }
+}
+
 pub fn parse_tasks_from_html(html: &str) -> Result<Vec<PbsTask>, Box<dyn std::error::Error>> {
     let parser = Parser::default_html();
     let doc = parser.parse_string(html)?;
@@ -90,7 +148,7 @@ pub fn parse_tasks_from_html(html: &str) -> Result<Vec<PbsTask>, Box<dyn std::er
                 }
             })
             .collect();
-        parsed_tasks.sort_by(|a, b| b.id.cmp(&a.id));
+        parsed_tasks.sort_by_key(|t| std::cmp::Reverse(t.id));
         return Ok(parsed_tasks);
     }
     Ok(vec![])
@@ -120,6 +178,22 @@ mod tests {
         assert_eq!(rescale(5.0, 0.0, 10.0, 100.0, 0.0), 50.0);
     }
 
+    #[test]
+    fn test_work_entry_form() {
+        let entry = WorkEntry {
+            task_id: 119627,
+            date: NaiveDate::from_ymd_opt(2025, 1, 6).unwrap(),
+            minutes: 105,
+            description: "review".to_string(),
+            billing_code: Some("B-100".to_string()),
+        };
+        let form = entry.form();
+        assert!(form.contains(&("taskID", "119627".to_string())));
+        assert!(form.contains(&("date", "06.01.2025".to_string())));
+        assert!(form.contains(&("hours", "1.75".to_string())));
+        assert!(form.contains(&("billingCode", "B-100".to_string())));
+    }
+
     #[test]
     fn test_rescale_zero_range() {
         assert_eq!(rescale(5.0, 10.0, 10.0, 0.0, 100.0), 0.0);
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+/// Name of a file of the profile, e.g. `config.personal.toml` for `config.toml`. `None` is the
+/// default profile.
+pub fn file_name(profile: Option<&str>, default: &str) -> String {
+    match (profile, default.rsplit_once('.')) {
+        (Some(profile), Some((stem, extension))) => format!("{}.{}.{}", stem, profile, extension),
+        (Some(profile), None) => format!("{}.{}", default, profile),
+        (None, _) => default.to_string(),
+    }
+}
+
+/// Path of a file of the profile in the directory
+pub fn path_in(dir: &Path, profile: Option<&str>, default: &str) -> PathBuf {
+    dir.join(file_name(profile, default))
+}
+
+/// Firestore database of the profile when its config names none
+pub fn database_id(profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("tcheater-{}", profile),
+        None => "tcheater".to_string(),
+    }
+}
+
+/// A profile name becomes part of file and database names, so only letters, digits, `-` and `_`
+/// are taken
+pub fn parse_profile(input: &str) -> Result<String, String> {
+    let valid = !input.is_empty()
+        && input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(input.to_string())
+    } else {
+        Err(format!(
+            "`{}` is not a profile name, use letters, digits, - and _",
+            input
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_files() {
+        assert_eq!(
+            file_name(Some("personal"), "config.toml"),
+            "config.personal.toml"
+        );
+        assert_eq!(database_id(Some("personal")), "tcheater-personal");
+        assert_eq!(file_name(None, "projects.toml"), "projects.toml");
+        assert_eq!(database_id(None), "tcheater");
+
+        assert!(parse_profile("side-project_2").is_ok());
+        assert!(parse_profile("../work").is_err());
+    }
+}
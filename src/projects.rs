@@ -0,0 +1,209 @@
+use serde::Deserialize;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+/// Project offered in the project popup, defined in `projects.toml`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Project {
+    /// Stored as the project of checkpoints, usually the PBS task id
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Key assigning the project while the project popup is open
+    #[serde(default)]
+    pub hotkey: Option<char>,
+    /// Position in the popup, projects without it come last in the order of the file
+    #[serde(default)]
+    pub order: Option<i32>,
+    /// Kinds of work within the project, e.g. development or review, offered once the project
+    /// is picked
+    #[serde(default)]
+    pub activities: Vec<String>,
+    /// Finished project, no longer offered and its hotkey is free for another one. It stays
+    /// known, so that the checkpoints of old days still resolve it.
+    #[serde(default)]
+    pub archived: bool,
+    /// Comment offered for editing when the project is assigned to a span without one
+    #[serde(default)]
+    pub default_message: Option<String>,
+    /// Code the time is billed under, written to the exports and sent along to PBS
+    #[serde(default)]
+    pub billing_code: Option<String>,
+    /// Hourly rate in the currency of the config, the stats and reports show the money earned
+    #[serde(default)]
+    pub rate: Option<f64>,
+    /// Jira issue key, e.g. `WEB-12`, the spans are submitted to as worklogs
+    #[serde(default)]
+    pub jira_issue: Option<String>,
+    /// Toggl Track project the spans are synced with
+    #[serde(default)]
+    pub toggl_project: Option<i64>,
+    /// Slack status while the project runs, instead of its name. Empty, no status is set.
+    #[serde(default)]
+    pub slack_status: Option<String>,
+    /// Emoji of the Slack status, e.g. `:briefcase:`
+    #[serde(default)]
+    pub slack_emoji: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ProjectsFile {
+    #[serde(default)]
+    projects: Vec<Project>,
+}
+
+/// Reads the projects from the file, a missing file means no projects
+pub fn load_projects<P: AsRef<Path>>(path: P) -> Result<Vec<Project>, Box<dyn std::error::Error>> {
+    match fs::read_to_string(path) {
+        Ok(content) => parse_projects(&content),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// The project of the id, archived ones included
+pub fn find<'a>(projects: &'a [Project], id: &str) -> Option<&'a Project> {
+    projects.iter().find(|project| project.id == id)
+}
+
+/// Billing code of the project of the id
+pub fn billing_code<'a>(projects: &'a [Project], id: Option<&str>) -> Option<&'a str> {
+    find(projects, id?)?.billing_code.as_deref()
+}
+
+/// Projects that are not archived
+pub fn active(projects: &[Project]) -> impl Iterator<Item = &Project> {
+    projects.iter().filter(|project| !project.archived)
+}
+
+/// The active projects, recently used first, most recent on top, the rest in their configured
+/// order
+pub fn by_recency<'a>(projects: &'a [Project], recent: &[String]) -> Vec<&'a Project> {
+    let mut sorted: Vec<&Project> = active(projects).collect();
+    sorted.sort_by_key(|project| {
+        recent
+            .iter()
+            .position(|id| *id == project.id)
+            .unwrap_or(usize::MAX)
+    });
+    sorted
+}
+
+pub(crate) fn parse_projects(content: &str) -> Result<Vec<Project>, Box<dyn std::error::Error>> {
+    let mut projects = toml::from_str::<ProjectsFile>(content)?.projects;
+    for (idx, project) in projects.iter().enumerate() {
+        let Some(hotkey) = project.hotkey.filter(|_| !project.archived) else {
+            continue;
+        };
+        if let Some(other) = active(&projects[..idx]).find(|p| p.hotkey == Some(hotkey)) {
+            return Err(format!(
+                "hotkey '{}' is used by both {} and {}",
+                hotkey, other.id, project.id
+            )
+            .into());
+        }
+    }
+    // The sort is stable, projects without an order keep the order of the file
+    projects.sort_by_key(|project| project.order.unwrap_or(i32::MAX));
+    Ok(projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_projects() {
+        let content = r#"
+            [[projects]]
+            id = "ABC-1"
+
+            [[projects]]
+            id = "ABC-2"
+            name = "Backend"
+            hotkey = "b"
+            order = 2
+            activities = ["development", "review"]
+            default_message = "standup"
+            billing_code = "B-100"
+
+            [[projects]]
+            id = "ABC-3"
+            hotkey = "f"
+            order = 1
+        "#;
+
+        let projects = parse_projects(content).unwrap();
+
+        let ids: Vec<&str> = projects.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["ABC-3", "ABC-2", "ABC-1"]);
+        assert_eq!(projects[1].hotkey, Some('b'));
+        assert_eq!(projects[1].name.as_deref(), Some("Backend"));
+        assert_eq!(projects[1].activities, vec!["development", "review"]);
+        assert!(projects[0].activities.is_empty());
+        assert_eq!(projects[1].default_message.as_deref(), Some("standup"));
+        assert_eq!(billing_code(&projects, Some("ABC-2")), Some("B-100"));
+        assert_eq!(billing_code(&projects, Some("ABC-1")), None);
+        assert_eq!(billing_code(&projects, None), None);
+    }
+
+    #[test]
+    fn test_by_recency() {
+        let projects: Vec<Project> = ["ABC-1", "ABC-2", "ABC-3", "ABC-4"]
+            .into_iter()
+            .map(|id| Project {
+                id: id.to_string(),
+                name: None,
+                hotkey: None,
+                order: None,
+                activities: vec![],
+                archived: id == "ABC-4",
+                default_message: None,
+                billing_code: None,
+                rate: None,
+                jira_issue: None,
+                toggl_project: None,
+                slack_status: None,
+                slack_emoji: None,
+            })
+            .collect();
+        let recent = vec![
+            "ABC-3".to_string(),
+            "XYZ-9".to_string(),
+            "ABC-2".to_string(),
+        ];
+
+        let ids: Vec<&str> = by_recency(&projects, &recent)
+            .iter()
+            .map(|p| p.id.as_str())
+            .collect();
+
+        assert_eq!(ids, vec!["ABC-3", "ABC-2", "ABC-1"]);
+    }
+
+    #[test]
+    fn test_parse_projects_duplicate_hotkey() {
+        let content = r#"
+            [[projects]]
+            id = "ABC-1"
+            hotkey = "b"
+
+            [[projects]]
+            id = "ABC-2"
+            hotkey = "b"
+        "#;
+
+        let err = parse_projects(content).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "hotkey 'b' is used by both ABC-1 and ABC-2"
+        );
+
+        let archived = content.replacen("hotkey = \"b\"", "hotkey = \"b\"\narchived = true", 1);
+        let projects = parse_projects(&archived).unwrap();
+        assert!(projects[0].archived);
+        assert_eq!(active(&projects).count(), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::mpsc::{channel, Receiver};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::profile;
+
+/// File of the home directory the week view reloads
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ConfigFile {
+    Config,
+    Projects,
+}
+
+impl ConfigFile {
+    /// Name of the file of the profile
+    pub fn name(self, profile: Option<&str>) -> String {
+        profile::file_name(
+            profile,
+            match self {
+                ConfigFile::Config => "config.toml",
+                ConfigFile::Projects => "projects.toml",
+            },
+        )
+    }
+
+    fn of(path: &Path, profile: Option<&str>) -> Option<ConfigFile> {
+        let name = path.file_name()?.to_str()?;
+        [ConfigFile::Config, ConfigFile::Projects]
+            .into_iter()
+            .find(|file| file.name(profile) == name)
+    }
+}
+
+/// Changes of the config files while the week view runs
+pub struct ConfigWatcher {
+    /// Watches as long as it lives
+    _watcher: RecommendedWatcher,
+    changes: Receiver<ConfigFile>,
+}
+
+impl ConfigWatcher {
+    /// Watches the files of the profile in the directory
+    pub fn new(dir: &Path, profile: Option<String>) -> notify::Result<Self> {
+        let (tx, changes) = channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            if !(event.kind.is_create() || event.kind.is_modify()) {
+                return;
+            }
+            let files = event
+                .paths
+                .iter()
+                .filter_map(|path| ConfigFile::of(path, profile.as_deref()));
+            for file in files {
+                // the receiver only goes away with the watcher
+                let _ = tx.send(file);
+            }
+        })?;
+        // Editors often save by replacing the file, so the directory is watched
+        watcher.watch(dir, RecursiveMode::NonRecursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            changes,
+        })
+    }
+
+    /// The files changed since the last call, each once however often it was written
+    pub fn changed(&self) -> BTreeSet<ConfigFile> {
+        self.changes.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_file_of_path() {
+        assert_eq!(
+            ConfigFile::of(Path::new("/home/me/config.toml"), None),
+            Some(ConfigFile::Config)
+        );
+        assert_eq!(
+            ConfigFile::of(Path::new("/home/me/projects.toml"), None),
+            Some(ConfigFile::Projects)
+        );
+        assert_eq!(
+            ConfigFile::of(Path::new("/home/me/.config.toml.swp"), None),
+            None
+        );
+        assert_eq!(
+            ConfigFile::of(Path::new("/home/me/config.toml"), Some("personal")),
+            None
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::app::Checkpoint;
+use crate::projects::{self, Project};
+
+const PROFILE_SET_URL: &str = "https://slack.com/api/users.profile.set";
+
+/// Slack workspace whose status is set. The token can be left out of the file and set in
+/// `TCHEATER_SLACK_TOKEN`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// User token with the `users.profile:write` scope
+    #[serde(default)]
+    pub token: String,
+    /// Emoji of the projects without their own
+    #[serde(default = "default_emoji")]
+    pub emoji: String,
+    /// Text of the projects without their own, their name when left out
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+fn default_emoji() -> String {
+    ":computer:".to_string()
+}
+
+/// Text and emoji of the status, both empty clear it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Status {
+    pub text: String,
+    pub emoji: String,
+}
+
+/// The status while the checkpoint runs, cleared when it is no work or none runs. A project with
+/// an empty `slack_status` clears it too.
+pub fn status(running: Option<&Checkpoint>, projects: &[Project], config: &SlackConfig) -> Status {
+    let Some(running) = running.filter(|running| running.is_work()) else {
+        return Status::default();
+    };
+    let id = running.project.as_deref().unwrap_or_default();
+    let project = projects::find(projects, id);
+    let text = project
+        .and_then(|project| project.slack_status.clone())
+        .or_else(|| config.text.clone())
+        .unwrap_or_else(|| {
+            project
+                .and_then(|project| project.name.clone())
+                .unwrap_or_else(|| id.to_string())
+        });
+    if text.is_empty() {
+        return Status::default();
+    }
+    let emoji = project
+        .and_then(|project| project.slack_emoji.clone())
+        .unwrap_or_else(|| config.emoji.clone());
+    Status { text, emoji }
+}
+
+pub async fn set_status(config: &SlackConfig, status: &Status) -> Result<()> {
+    let response = reqwest::Client::new()
+        .post(PROFILE_SET_URL)
+        .bearer_auth(&config.token)
+        .json(&json!({
+            "profile": {
+                "status_text": status.text,
+                "status_emoji": status.emoji,
+                "status_expiration": 0,
+            }
+        }))
+        .send()
+        .await?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Slack answered {}", status);
+    }
+    // Refused calls are answered with 200 too
+    let body: Value = response.json().await?;
+    if body["ok"] != true {
+        bail!("Slack refused the status: {}", body["error"]);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::CheckpointKind;
+    use crate::projects::parse_projects;
+
+    #[test]
+    fn test_status() {
+        let projects = parse_projects(
+            "[[projects]]\nid = \"web\"\nname = \"Website\"\n\
+             [[projects]]\nid = \"acme\"\nname = \"ACME Corp\"\nslack_status = \"Client work\"\n\
+             slack_emoji = \":briefcase:\"\n\
+             [[projects]]\nid = \"secret\"\nslack_status = \"\"",
+        )
+        .unwrap();
+        let config: SlackConfig = toml::from_str("token = \"xoxp\"").unwrap();
+        let running = |project: &str, kind| Checkpoint {
+            project: Some(project.to_string()),
+            kind,
+            ..Checkpoint::new()
+        };
+        let status_of =
+            |checkpoint: &Checkpoint, config| status(Some(checkpoint), &projects, config);
+
+        let web = status_of(&running("web", CheckpointKind::Work), &config);
+        assert_eq!(web.text, "Website");
+        assert_eq!(web.emoji, ":computer:");
+        let acme = status_of(&running("acme", CheckpointKind::Work), &config);
+        assert_eq!(acme.text, "Client work");
+        assert_eq!(acme.emoji, ":briefcase:");
+        assert_eq!(
+            status_of(&running("secret", CheckpointKind::Work), &config),
+            Status::default()
+        );
+        assert_eq!(
+            status_of(&running("web", CheckpointKind::Break), &config),
+            Status::default()
+        );
+
+        let hidden = SlackConfig {
+            text: Some("Working".to_string()),
+            ..config.clone()
+        };
+        assert_eq!(
+            status_of(&running("web", CheckpointKind::Work), &hidden).text,
+            "Working"
+        );
+    }
+}
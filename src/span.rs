@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::time::{Rounding, Time};
+
+/// Time between two checkpoints with the details of the first one.
+///
+/// The checkpoints are what is stored and edited, each of them ends the previous span. Spans are
+/// generated from them for exports and reports, which then need not know that the next
+/// checkpoint is the end of a span.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    #[serde(with = "crate::firestore::utc_time")]
+    pub start: Time,
+    #[serde(with = "crate::firestore::utc_time")]
+    pub end: Time,
+    /// Rounded length, as it is reported
+    #[serde(default)]
+    pub minutes: u32,
+    pub project: Option<String>,
+    #[serde(default)]
+    pub activity: Option<String>,
+    pub message: Option<String>,
+    pub registered: bool,
+    #[serde(default)]
+    pub kind: CheckpointKind,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default = "default_billable")]
+    pub billable: bool,
+}
+
+fn default_billable() -> bool {
+    true
+}
+
+impl Span {
+    /// Spans between the consecutive checkpoints of a day, ordered by time.
+    ///
+    /// Nothing is tracked after a stop, so its span is left out, as is the span still running
+    /// after the last checkpoint.
+    pub fn from_checkpoints(checkpoints: &[Checkpoint], rounding: &Rounding) -> Vec<Span> {
+        checkpoints
+            .windows(2)
+            .zip(rounding.spans(checkpoints))
+            .filter(|(pair, _)| pair[0].kind != CheckpointKind::Stop)
+            .map(|(pair, span)| {
+                let (start, end) = (&pair[0], &pair[1]);
+                Span {
+                    start: start.time,
+                    end: end.time,
+                    minutes: span.minutes(),
+                    project: start.project.clone(),
+                    activity: start.activity.clone(),
+                    message: start.message.clone(),
+                    registered: start.registered,
+                    kind: start.kind,
+                    tags: start.tags.clone(),
+                    billable: start.billable,
+                }
+            })
+            .collect()
+    }
+
+    /// Spans of checkpoints ordered by time over several days, each day is split on its own
+    pub fn from_days(checkpoints: &[Checkpoint], rounding: &Rounding) -> Vec<Span> {
+        checkpoints
+            .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+            .flat_map(|day| Span::from_checkpoints(day, rounding))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::{at, checkpoint};
+
+    #[test]
+    fn test_spans_from_checkpoints() {
+        let day = vec![
+            checkpoint(at(8, 0), CheckpointKind::Work, "ABC-1"),
+            checkpoint(at(12, 0), CheckpointKind::Stop, ""),
+            checkpoint(at(12, 30), CheckpointKind::Work, "ABC-2"),
+            checkpoint(at(16, 0), CheckpointKind::Break, "ABC-2"),
+            checkpoint(at(16, 15), CheckpointKind::Work, "ABC-3"),
+        ];
+
+        let spans = Span::from_checkpoints(&day, &Rounding::DEFAULT);
+
+        let summary: Vec<_> = spans
+            .iter()
+            .map(|span| (span.start, span.minutes, span.project.as_deref(), span.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (at(8, 0), 240, Some("ABC-1"), CheckpointKind::Work),
+                (at(12, 30), 210, Some("ABC-2"), CheckpointKind::Work),
+                (at(16, 0), 15, Some("ABC-2"), CheckpointKind::Break),
+            ]
+        );
+    }
+}
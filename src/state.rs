@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+
+use directories::BaseDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::profile;
+use crate::time::Time;
+
+/// The span running after the last checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tracking {
+    #[serde(with = "crate::firestore::utc_time")]
+    pub since: Time,
+    pub project: Option<String>,
+    #[serde(default)]
+    pub kind: CheckpointKind,
+}
+
+impl Tracking {
+    /// What runs at `now` after the ordered checkpoints of a day, nothing after a stop
+    pub fn from_checkpoints(checkpoints: &[Checkpoint], now: Time) -> Option<Tracking> {
+        let last = checkpoints.iter().rev().find(|ch| ch.time <= now)?;
+        (last.kind != CheckpointKind::Stop).then(|| Tracking {
+            since: last.time,
+            project: last.project_label(),
+            kind: last.kind,
+        })
+    }
+}
+
+fn state_path(profile: Option<&str>) -> Option<PathBuf> {
+    let dir = BaseDirs::new()?.cache_dir().join("tcheater");
+    Some(profile::path_in(&dir, profile, "state.json"))
+}
+
+/// Replaces the cached state of the profile, `None` when the clock is stopped
+pub fn save(tracking: Option<&Tracking>, profile: Option<&str>) -> std::io::Result<()> {
+    let Some(path) = state_path(profile) else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string(&tracking)?)
+}
+
+/// The cached state of the profile, `None` when nothing runs or nothing was cached yet
+pub fn load(profile: Option<&str>) -> Option<Tracking> {
+    let content = std::fs::read_to_string(state_path(profile)?).ok()?;
+    serde_json::from_str(&content).ok()?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_tracking_from_checkpoints() {
+        let day = vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("ABC-1".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+
+        let tracking = Tracking::from_checkpoints(&day, at(10, 0)).unwrap();
+        assert_eq!(tracking.since, at(8, 0));
+        assert_eq!(tracking.project.as_deref(), Some("ABC-1"));
+
+        assert_eq!(Tracking::from_checkpoints(&day, at(13, 0)), None);
+        assert_eq!(Tracking::from_checkpoints(&day, at(7, 0)), None);
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Weekday};
+
+use crate::app::Checkpoint;
+use crate::earnings::Rates;
+use crate::time::{billable_project_units, project_units, DayStats, Rounding};
+
+/// Summary of the work tracked over a period of days.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PeriodStats {
+    /// Tracked minutes per project, the largest first
+    pub project_minutes: Vec<(String, u32)>,
+    /// The billable part of the minutes of the projects that have any
+    pub billable_minutes: BTreeMap<String, u32>,
+    /// Average time of the first checkpoint of a day
+    pub average_start: Option<NaiveTime>,
+    /// Average time of the last checkpoint of a day
+    pub average_end: Option<NaiveTime>,
+    /// Day with the most tracked minutes
+    pub longest_day: Option<(NaiveDate, u32)>,
+}
+
+impl PeriodStats {
+    /// Computes the stats from checkpoints ordered by time, possibly spanning several days
+    pub fn from_checkpoints(checkpoints: &[Checkpoint], rounding: &Rounding) -> Self {
+        let mut stats = Self::default();
+        let mut starts = vec![];
+        let mut ends = vec![];
+
+        for day in checkpoints.chunk_by(|a, b| a.time.date_naive() == b.time.date_naive()) {
+            let (Some(first), Some(last)) = (day.first(), day.last()) else {
+                continue;
+            };
+            let minutes = DayStats::from_checkpoints(day, rounding).tracked_minutes;
+            if minutes == 0 {
+                continue;
+            }
+            starts.push(first.time.time());
+            ends.push(last.time.time());
+            if stats
+                .longest_day
+                .is_none_or(|(_, longest)| minutes > longest)
+            {
+                stats.longest_day = Some((first.time.date_naive(), minutes));
+            }
+
+            for (project, units) in billable_project_units(day, rounding) {
+                *stats.billable_minutes.entry(project).or_insert(0) +=
+                    units as u32 * rounding.unit_minutes();
+            }
+            for (project, units) in project_units(day, rounding) {
+                let minutes = units as u32 * rounding.unit_minutes();
+                match stats
+                    .project_minutes
+                    .iter_mut()
+                    .find(|(p, _)| *p == project)
+                {
+                    Some((_, total)) => *total += minutes,
+                    None => stats.project_minutes.push((project, minutes)),
+                }
+            }
+        }
+
+        stats.project_minutes.retain(|(_, minutes)| *minutes > 0);
+        stats.billable_minutes.retain(|_, minutes| *minutes > 0);
+        stats
+            .project_minutes
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        stats.average_start = average_time(&starts);
+        stats.average_end = average_time(&ends);
+        stats
+    }
+
+    /// Earned with the billable minutes of the project, `None` when it has no rate
+    pub fn earned(&self, rates: &Rates, project: &str) -> Option<f64> {
+        let billable = self.billable_minutes.get(project).copied().unwrap_or(0);
+        rates.earned(project, billable)
+    }
+
+    /// Earned with the billable minutes of all the projects, `None` when none of them has a rate
+    pub fn total_earned(&self, rates: &Rates) -> Option<f64> {
+        let billable: Vec<(String, u32)> = self
+            .project_minutes
+            .iter()
+            .map(|(project, _)| {
+                let minutes = self.billable_minutes.get(project).copied().unwrap_or(0);
+                (project.clone(), minutes)
+            })
+            .collect();
+        rates.total(&billable)
+    }
+}
+
+/// Stats of each day with checkpoints, the checkpoints must be ordered by time
+pub fn daily_stats(
+    checkpoints: &[Checkpoint],
+    rounding: &Rounding,
+) -> BTreeMap<NaiveDate, DayStats> {
+    checkpoints
+        .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+        .map(|day| {
+            (
+                day[0].time.date_naive(),
+                DayStats::from_checkpoints(day, rounding),
+            )
+        })
+        .collect()
+}
+
+/// Tracked minus expected minutes summed over the days from `since` up to `until`, exclusive.
+///
+/// Working days expect `expected_minutes` unless `day_off` says otherwise, weekends expect
+/// nothing but the work done on them counts.
+pub fn flex_balance(
+    days: &BTreeMap<NaiveDate, DayStats>,
+    since: NaiveDate,
+    until: NaiveDate,
+    expected_minutes: u32,
+    day_off: impl Fn(NaiveDate) -> bool,
+) -> i64 {
+    since
+        .iter_days()
+        .take_while(|date| *date < until)
+        .map(|date| {
+            let tracked = days.get(&date).map_or(0, |day| day.tracked_minutes) as i64;
+            let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+            let expected = if weekend || day_off(date) {
+                0
+            } else {
+                expected_minutes as i64
+            };
+            tracked - expected
+        })
+        .sum()
+}
+
+fn average_time(times: &[NaiveTime]) -> Option<NaiveTime> {
+    if times.is_empty() {
+        return None;
+    }
+    let total: u64 = times
+        .iter()
+        .map(|time| time.num_seconds_from_midnight() as u64)
+        .sum();
+    NaiveTime::from_num_seconds_from_midnight_opt((total / times.len() as u64) as u32, 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::CheckpointKind;
+    use crate::time::test_util::{at, checkpoint, on};
+
+    #[test]
+    fn test_period_stats() {
+        let checkpoints = vec![
+            checkpoint(at(8, 0), CheckpointKind::Work, "a"),
+            checkpoint(at(10, 0), CheckpointKind::Work, "b"),
+            checkpoint(at(11, 0), CheckpointKind::Work, "-"),
+            checkpoint(on(7, 9, 0), CheckpointKind::Work, "b"),
+            checkpoint(on(7, 13, 0), CheckpointKind::Work, "-"),
+        ];
+
+        let stats = PeriodStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(
+            stats.project_minutes,
+            vec![("b".to_string(), 300), ("a".to_string(), 120)]
+        );
+        assert_eq!(stats.average_start, NaiveTime::from_hms_opt(8, 30, 0));
+        assert_eq!(stats.average_end, NaiveTime::from_hms_opt(12, 0, 0));
+        assert_eq!(
+            stats.longest_day,
+            Some((NaiveDate::from_ymd_opt(2025, 1, 7).unwrap(), 240))
+        );
+        assert_eq!(stats.billable_minutes["b"], 300);
+    }
+
+    #[test]
+    fn test_earned_with_billable_minutes() {
+        let mut checkpoints = vec![
+            checkpoint(at(8, 0), CheckpointKind::Work, "a"),
+            checkpoint(at(10, 0), CheckpointKind::Work, "a"),
+            checkpoint(at(11, 0), CheckpointKind::Work, "b"),
+            checkpoint(at(12, 0), CheckpointKind::Work, "-"),
+        ];
+        checkpoints[1].billable = false;
+        checkpoints[2].billable = false;
+        let projects = crate::projects::parse_projects(
+            "[[projects]]\nid = \"a\"\nrate = 60\n[[projects]]\nid = \"b\"\nrate = 10",
+        )
+        .unwrap();
+        let rates = Rates::new(&projects, None);
+
+        let stats = PeriodStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(stats.project_minutes[0], ("a".to_string(), 180));
+        assert_eq!(stats.earned(&rates, "a"), Some(120.0));
+        assert_eq!(stats.earned(&rates, "b"), Some(0.0));
+        assert_eq!(stats.total_earned(&rates), Some(120.0));
+        assert_eq!(stats.earned(&rates, "-"), None);
+    }
+
+    #[test]
+    fn test_daily_stats() {
+        let checkpoints = vec![
+            checkpoint(at(8, 0), CheckpointKind::Work, "a"),
+            checkpoint(at(10, 0), CheckpointKind::Work, "-"),
+            checkpoint(on(7, 9, 0), CheckpointKind::Work, "b"),
+        ];
+
+        let days = daily_stats(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(days.len(), 2);
+        let monday = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        assert_eq!(days[&monday].tracked_minutes, 120);
+        assert_eq!(days[&monday].unregistered_spans, 1);
+        assert_eq!(days[&monday.succ_opt().unwrap()].tracked_minutes, 0);
+    }
+
+    #[test]
+    fn test_period_stats_skips_days_without_work() {
+        let start = at(8, 0);
+        let checkpoints = vec![Checkpoint {
+            time: start,
+            ..Checkpoint::new()
+        }];
+
+        let stats = PeriodStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(stats, PeriodStats::default());
+        assert_eq!(
+            PeriodStats::from_checkpoints(&[], &Rounding::DEFAULT),
+            PeriodStats::default()
+        );
+    }
+
+    #[test]
+    fn test_flex_balance() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 1, day).unwrap();
+        let tracked = |minutes| DayStats {
+            tracked_minutes: minutes,
+            ..DayStats::default()
+        };
+        let days = BTreeMap::from([
+            (date(6), tracked(540)),
+            (date(8), tracked(420)),
+            (date(11), tracked(60)),
+        ]);
+
+        let balance = flex_balance(&days, date(6), date(13), 480, |d| d == date(7));
+
+        // +60 on Monday, Tuesday off, -60 on Wednesday, two empty days and a Saturday hour
+        assert_eq!(balance, 60 - 60 - 480 - 480 + 60);
+        assert_eq!(flex_balance(&days, date(6), date(6), 480, |_| false), 0);
+    }
+}
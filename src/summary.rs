@@ -0,0 +1,219 @@
+use chrono::NaiveDate;
+
+use crate::app::{Checkpoint, CheckpointKind};
+use crate::time::{billable_units, DayStats, TimeSettings};
+
+/// How a day summary is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryFormat {
+    Text,
+    Markdown,
+}
+
+/// A span of the day as it appears in the summary
+struct SummaryRow {
+    times: String,
+    duration: String,
+    project: String,
+    comment: String,
+}
+
+/// Formats the spans of a day with their times, projects, durations and comments, for pasting
+/// into standups or emails.
+pub fn day_summary(
+    date: NaiveDate,
+    checkpoints: &[Checkpoint],
+    format: SummaryFormat,
+    settings: &TimeSettings,
+) -> String {
+    let (rounding, locale) = (&settings.rounding, &settings.locale);
+    let rows: Vec<SummaryRow> = checkpoints
+        .windows(2)
+        .zip(rounding.spans(checkpoints))
+        .filter(|(pair, _)| pair[0].kind != CheckpointKind::Stop)
+        .map(|(pair, span)| {
+            let (start, end) = (&pair[0], &pair[1]);
+            let project = match start.kind {
+                CheckpointKind::Break => "break".to_string(),
+                CheckpointKind::Absence => "absence".to_string(),
+                CheckpointKind::Work | CheckpointKind::Stop => {
+                    start.project_label().unwrap_or_else(|| "-".to_string())
+                }
+            };
+            SummaryRow {
+                times: format!(
+                    "{}-{}",
+                    locale.time(start.rounded_time(rounding).time()),
+                    locale.time(end.rounded_time(rounding).time())
+                ),
+                duration: settings.duration(span.minutes()),
+                project,
+                comment: start.message.clone().unwrap_or_default(),
+            }
+        })
+        .collect();
+    let title = format!("{} {}", date.format("%a"), locale.date(date));
+    let mut total =
+        settings.duration(DayStats::from_checkpoints(checkpoints, rounding).tracked_minutes);
+    let (billable, non_billable) = billable_units(checkpoints, rounding);
+    if non_billable > 0 {
+        total = format!(
+            "{} (billable {}, non-billable {})",
+            total,
+            settings.duration(billable as u32 * rounding.unit_minutes()),
+            settings.duration(non_billable as u32 * rounding.unit_minutes())
+        );
+    }
+
+    let mut lines = vec![];
+    match format {
+        SummaryFormat::Text => {
+            lines.push(title.to_string());
+            for row in rows {
+                let line = format!(
+                    "{}  {:<6} {}  {}",
+                    row.times, row.duration, row.project, row.comment
+                );
+                lines.push(line.trim_end().to_string());
+            }
+            lines.push(format!("Total: {}", total));
+        }
+        SummaryFormat::Markdown => {
+            lines.push(format!("## {}", title));
+            lines.push(String::new());
+            lines.push("| Time | Duration | Project | Comment |".to_string());
+            lines.push("|---|---|---|---|".to_string());
+            for row in rows {
+                lines.push(format!(
+                    "| {} | {} | {} | {} |",
+                    row.times,
+                    row.duration,
+                    escape_cell(&row.project),
+                    escape_cell(&row.comment)
+                ));
+            }
+            lines.push(String::new());
+            lines.push(format!("**Total:** {}", total));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Keeps pipes in the text from ending the Markdown table cell
+fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    fn checkpoints() -> Vec<Checkpoint> {
+        vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("ABC-1".to_string()),
+                message: Some("login | logout".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(10, 0),
+                kind: CheckpointKind::Break,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(10, 30),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(13, 0),
+                project: Some("ABC-2".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(13, 45),
+                ..Checkpoint::new()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_day_summary_text() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+
+        let summary = day_summary(
+            date,
+            &checkpoints(),
+            SummaryFormat::Text,
+            &TimeSettings::default(),
+        );
+
+        assert_eq!(
+            summary,
+            "Mon 06.01.25\n\
+             08:00-10:00  2h     ABC-1  login | logout\n\
+             10:00-10:30  30m    break\n\
+             13:00-13:45  45m    ABC-2\n\
+             Total: 2h45m"
+        );
+    }
+
+    #[test]
+    fn test_day_summary_markdown() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+
+        let summary = day_summary(
+            date,
+            &checkpoints(),
+            SummaryFormat::Markdown,
+            &TimeSettings::default(),
+        );
+
+        assert_eq!(
+            summary,
+            "## Mon 06.01.25\n\
+             \n\
+             | Time | Duration | Project | Comment |\n\
+             |---|---|---|---|\n\
+             | 08:00-10:00 | 2h | ABC-1 | login \\| logout |\n\
+             | 10:00-10:30 | 30m | break |  |\n\
+             | 13:00-13:45 | 45m | ABC-2 |  |\n\
+             \n\
+             **Total:** 2h45m"
+        );
+    }
+
+    #[test]
+    fn test_day_summary_activities() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let mut checkpoints = checkpoints();
+        checkpoints[3].activity = Some("review".to_string());
+
+        let summary = day_summary(
+            date,
+            &checkpoints,
+            SummaryFormat::Text,
+            &TimeSettings::default(),
+        );
+
+        assert!(summary.contains("13:00-13:45  45m    ABC-2/review\n"));
+    }
+
+    #[test]
+    fn test_day_summary_billable_totals() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let mut checkpoints = checkpoints();
+        checkpoints[3].billable = false;
+
+        let summary = day_summary(
+            date,
+            &checkpoints,
+            SummaryFormat::Text,
+            &TimeSettings::default(),
+        );
+
+        assert!(summary.ends_with("Total: 2h45m (billable 2h, non-billable 45m)"));
+    }
+}
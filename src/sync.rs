@@ -0,0 +1,392 @@
+use chrono::{DateTime, DurationRound, NaiveDate, TimeDelta, Utc};
+use chrono_tz::Tz;
+use color_eyre::{eyre::bail, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{json, Value};
+
+use crate::app::Checkpoint;
+use crate::calendar::{meeting_checkpoints, Meeting};
+use crate::projects::Project;
+use crate::time::Time;
+
+const API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+/// Toggl Track account the spans are synced with. The token can be left out of the file and set
+/// in `TCHEATER_TOGGL_API_TOKEN`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TogglConfig {
+    /// Found in the profile settings of Toggl Track
+    #[serde(default)]
+    pub api_token: String,
+    /// Workspace the pushed entries are created in
+    pub workspace_id: i64,
+}
+
+/// Time entry of Toggl Track, running while it has no stop
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TimeEntry {
+    pub id: i64,
+    #[serde(default, deserialize_with = "nullable")]
+    pub description: String,
+    pub start: DateTime<Utc>,
+    #[serde(default)]
+    pub stop: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub project_id: Option<i64>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub billable: bool,
+}
+
+/// Toggl writes `null` for empty descriptions and tags
+fn nullable<'de, D: Deserializer<'de>, T: Deserialize<'de> + Default>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+/// Client of the Toggl Track API
+pub struct Toggl {
+    config: TogglConfig,
+    client: reqwest::Client,
+}
+
+impl Toggl {
+    pub fn new(config: TogglConfig) -> Self {
+        Self {
+            config,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Entries of the user starting between the times
+    pub async fn entries(&self, start: Time, end: Time) -> Result<Vec<TimeEntry>> {
+        let response = self
+            .client
+            .get(format!("{}/me/time_entries", API_URL))
+            .basic_auth(&self.config.api_token, Some("api_token"))
+            .query(&[
+                ("start_date", start.with_timezone(&Utc).to_rfc3339()),
+                ("end_date", end.with_timezone(&Utc).to_rfc3339()),
+            ])
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Toggl answered {}", status);
+        }
+        let mut entries: Vec<TimeEntry> = response.json().await?;
+        entries.sort_by_key(|entry| entry.start);
+        Ok(entries)
+    }
+
+    /// Creates the entry, or updates the one of its id, and returns its id
+    pub async fn save(&self, push: &Push) -> Result<i64> {
+        let url = format!(
+            "{}/workspaces/{}/time_entries",
+            API_URL, self.config.workspace_id
+        );
+        let request = match push.id {
+            Some(id) => self.client.put(format!("{}/{}", url, id)),
+            None => self.client.post(url),
+        };
+        let response = request
+            .basic_auth(&self.config.api_token, Some("api_token"))
+            .json(&push.body(self.config.workspace_id))
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            bail!("Toggl answered {}", status);
+        }
+        Ok(response.json::<TimeEntry>().await?.id)
+    }
+}
+
+/// A span as it is pushed to Toggl
+#[derive(Debug, Clone, PartialEq)]
+pub struct Push {
+    /// Index of the span's checkpoint among the checkpoints of its day
+    pub idx: usize,
+    /// Entry that is updated, a new one is created without it
+    pub id: Option<i64>,
+    pub description: String,
+    pub start: DateTime<Utc>,
+    pub stop: DateTime<Utc>,
+    pub project_id: Option<i64>,
+    pub tags: Vec<String>,
+    pub billable: bool,
+}
+
+impl Push {
+    fn body(&self, workspace_id: i64) -> Value {
+        json!({
+            "created_with": "tcheater",
+            "workspace_id": workspace_id,
+            "description": self.description,
+            "start": self.start,
+            "stop": self.stop,
+            "duration": (self.stop - self.start).num_seconds(),
+            "project_id": self.project_id,
+            "tags": self.tags,
+            "billable": self.billable,
+        })
+    }
+
+    /// Whether the entry holds the span already
+    fn is_in(&self, entry: &TimeEntry) -> bool {
+        entry.description == self.description
+            && entry.start == self.start
+            && entry.stop == Some(self.stop)
+            && entry.project_id == self.project_id
+            && entry.billable == self.billable
+    }
+}
+
+/// Toggl keeps the times to the second
+fn to_second(time: Time) -> DateTime<Utc> {
+    let time = time.with_timezone(&Utc);
+    time.duration_trunc(TimeDelta::seconds(1)).unwrap_or(time)
+}
+
+/// The checkpoints of the day with those of the entries that are new to tcheater, ordered by
+/// time. The pulled checkpoints have no id yet and their times are in the timezone.
+///
+/// An entry is placed like a meeting, it is left out when a checkpoint lies within it. A running
+/// entry is only taken when it starts after the last checkpoint.
+pub fn pull(
+    existing: &[Checkpoint],
+    entries: &[TimeEntry],
+    projects: &[Project],
+    timezone: Tz,
+) -> Vec<Checkpoint> {
+    let mut checkpoints = existing.to_vec();
+    for entry in entries {
+        if checkpoints.iter().any(|ch| ch.toggl_id == Some(entry.id)) {
+            continue;
+        }
+        let start = entry.start.with_timezone(&timezone);
+        let project = entry.project_id.and_then(|toggl_project| {
+            projects
+                .iter()
+                .find(|project| project.toggl_project == Some(toggl_project))
+                .map(|project| project.id.as_str())
+        });
+        let pulled = match entry.stop {
+            Some(stop) => {
+                let meeting = Meeting {
+                    title: entry.description.clone(),
+                    start,
+                    end: stop.with_timezone(&timezone),
+                };
+                meeting_checkpoints(&meeting, project, &checkpoints)
+            }
+            None if checkpoints.iter().all(|ch| ch.time < start) => {
+                let mut checkpoint = Checkpoint {
+                    time: start,
+                    project: project.map(str::to_string),
+                    ..Checkpoint::new()
+                };
+                checkpoint.set_message(entry.description.clone());
+                Some(vec![checkpoint])
+            }
+            None => None,
+        };
+        let Some(mut pulled) = pulled else {
+            continue;
+        };
+        let first = &mut pulled[0];
+        if entry.description.is_empty() {
+            first.message = None;
+        }
+        first.toggl_id = Some(entry.id);
+        first.billable = entry.billable;
+        for tag in &entry.tags {
+            if !first.tags.contains(tag) {
+                first.tags.push(tag.clone());
+            }
+        }
+        checkpoints.extend(pulled);
+        checkpoints.sort_by_key(|ch| ch.time);
+    }
+    checkpoints
+}
+
+/// The work spans of the day that are new to Toggl or changed since they were pushed. Spans
+/// whose entry was deleted in Toggl stay deleted, the running span is pushed once it ends.
+pub fn pushes(
+    checkpoints: &[Checkpoint],
+    entries: &[TimeEntry],
+    projects: &[Project],
+) -> Vec<Push> {
+    checkpoints
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0].is_work())
+        .filter_map(|(idx, pair)| {
+            let (start, end) = (&pair[0], &pair[1]);
+            let push = Push {
+                idx,
+                id: start.toggl_id,
+                description: start.message.clone().unwrap_or_default(),
+                start: to_second(start.time),
+                stop: to_second(end.time),
+                project_id: start
+                    .project
+                    .as_deref()
+                    .and_then(|id| crate::projects::find(projects, id))
+                    .and_then(|project| project.toggl_project),
+                tags: start.tags.clone(),
+                billable: start.billable,
+            };
+            match start.toggl_id {
+                None => Some(push),
+                Some(id) => {
+                    let entry = entries.iter().find(|entry| entry.id == id)?;
+                    (!push.is_in(entry)).then_some(push)
+                }
+            }
+        })
+        .collect()
+}
+
+/// The entries starting on the date in the timezone
+pub fn entries_on(entries: &[TimeEntry], date: NaiveDate, timezone: Tz) -> Vec<TimeEntry> {
+    entries
+        .iter()
+        .filter(|entry| entry.start.with_timezone(&timezone).date_naive() == date)
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::CheckpointKind;
+    use crate::projects::parse_projects;
+    use crate::time::test_util::at;
+
+    fn entry(id: i64, start: Time, stop: Option<Time>) -> TimeEntry {
+        TimeEntry {
+            id,
+            description: "Call #support".to_string(),
+            start: start.with_timezone(&Utc),
+            stop: stop.map(|stop| stop.with_timezone(&Utc)),
+            project_id: Some(900),
+            tags: vec!["phone".to_string()],
+            billable: false,
+        }
+    }
+
+    fn projects() -> Vec<Project> {
+        parse_projects("[[projects]]\nid = \"web\"\ntoggl_project = 900").unwrap()
+    }
+
+    #[test]
+    fn test_pull() {
+        let existing = vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("web".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+        let entries = vec![
+            entry(1, at(13, 0), Some(at(14, 0))),
+            // Overlaps the span tracked in tcheater
+            entry(2, at(11, 30), Some(at(12, 30))),
+            entry(3, at(15, 0), None),
+        ];
+
+        let checkpoints = pull(&existing, &entries, &projects(), chrono_tz::UTC);
+        let times: Vec<_> = checkpoints.iter().map(|ch| ch.time).collect();
+        assert_eq!(
+            times,
+            [at(8, 0), at(12, 0), at(13, 0), at(14, 0), at(15, 0)]
+        );
+        let pulled = &checkpoints[2];
+        assert_eq!(pulled.toggl_id, Some(1));
+        assert_eq!(pulled.project.as_deref(), Some("web"));
+        assert_eq!(pulled.tags, ["support", "phone"]);
+        assert!(!pulled.billable);
+        assert_eq!(checkpoints[3].kind, CheckpointKind::Stop);
+        assert_eq!(checkpoints[4].toggl_id, Some(3));
+
+        // Pulling again changes nothing
+        let pulled = pull(&checkpoints, &entries, &projects(), chrono_tz::UTC);
+        assert_eq!(pulled.len(), 5);
+    }
+
+    #[test]
+    fn test_pushes() {
+        let checkpoints = vec![
+            Checkpoint {
+                time: at(8, 0),
+                project: Some("web".to_string()),
+                message: Some("Deploy".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(10, 0),
+                toggl_id: Some(1),
+                message: Some("Call #support".to_string()),
+                tags: vec!["support".to_string(), "phone".to_string()],
+                project: Some("web".to_string()),
+                billable: false,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(11, 0),
+                toggl_id: Some(2),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(12, 0),
+                kind: CheckpointKind::Break,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(13, 0),
+                toggl_id: Some(3),
+                ..Checkpoint::new()
+            },
+        ];
+        let entries = vec![
+            entry(1, at(10, 0), Some(at(11, 0))),
+            entry(2, at(11, 0), Some(at(11, 30))),
+        ];
+
+        let pushes = pushes(&checkpoints, &entries, &projects());
+        // The span of entry 1 is unchanged and the running span waits for its end
+        assert_eq!(pushes.len(), 2);
+        assert_eq!(pushes[0].idx, 0);
+        assert_eq!(pushes[0].id, None);
+        assert_eq!(pushes[0].project_id, Some(900));
+        assert_eq!(pushes[0].stop, at(10, 0).with_timezone(&Utc));
+        assert_eq!(pushes[1].idx, 2);
+        assert_eq!(pushes[1].id, Some(2));
+        assert_eq!(
+            pushes[1].body(7)["duration"],
+            json!(3600),
+            "the entry changed in tcheater is updated"
+        );
+    }
+
+    #[test]
+    fn test_parse_entry() {
+        let entry: TimeEntry = serde_json::from_str(
+            r#"{"id": 4, "workspace_id": 7, "description": null, "start": "2025-01-06T07:00:00+00:00",
+                "stop": null, "duration": -1, "project_id": null, "tags": null, "billable": false}"#,
+        )
+        .unwrap();
+        assert_eq!(entry.description, "");
+        assert!(entry.tags.is_empty());
+        assert_eq!(entry.stop, None);
+    }
+}
@@ -0,0 +1,310 @@
+use std::env;
+use std::str::FromStr;
+
+use ratatui::{
+    style::Color,
+    symbols::{bar, border},
+    widgets::Block,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Colors and symbols used across the user interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Labels and help text
+    pub help: Color,
+    /// Checkpoint comment in the detail pane
+    pub comment: Color,
+    /// Spans without a message
+    pub missing_message: Color,
+    /// Background of the time label of unregistered checkpoints
+    pub unregistered: Color,
+    /// Background of the selected checkpoint
+    pub selection: Color,
+    /// Durations and highlighted items
+    pub accent: Color,
+    /// Values that are fine
+    pub good: Color,
+    /// Values that need attention
+    pub bad: Color,
+    pub symbols: Symbols,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            help: Color::Gray,
+            comment: Color::Green,
+            missing_message: Color::DarkGray,
+            unregistered: Color::Red,
+            selection: Color::DarkGray,
+            accent: Color::Yellow,
+            good: Color::Green,
+            bad: Color::Red,
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Theme readable on terminals with a light background
+    pub fn light() -> Self {
+        Self {
+            help: Color::DarkGray,
+            comment: Color::Indexed(28),
+            missing_message: Color::Gray,
+            unregistered: Color::LightRed,
+            selection: Color::Gray,
+            accent: Color::Indexed(130),
+            good: Color::Indexed(28),
+            bad: Color::Indexed(124),
+            symbols: Symbols::UNICODE,
+        }
+    }
+
+    /// Bordered block drawn with the theme's symbols
+    pub fn block(&self) -> Block<'static> {
+        Block::bordered().border_set(self.symbols.border)
+    }
+}
+
+/// Characters used to draw the timeline and the other widgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    /// Left end of the first span of a day
+    pub span_start: &'static str,
+    /// Boundary between two spans
+    pub span_join: &'static str,
+    /// Right end of the last span of a day
+    pub span_end: &'static str,
+    /// Spans with a project
+    pub span_fill: &'static str,
+    /// Non-billable spans with a project
+    pub non_billable_fill: &'static str,
+    /// Spans with a message but no project
+    pub message_fill: &'static str,
+    pub scroll_left: &'static str,
+    pub scroll_right: &'static str,
+    /// Marks the selected day in the minimap
+    pub selected_day: &'static str,
+    /// Days without work in the minimap
+    pub idle_day: &'static str,
+    /// Day in the tracked hours heatmap
+    pub heatmap_day: &'static str,
+    /// Prefix of the selected list item
+    pub highlight: &'static str,
+    /// Frames of the spinner shown while loading
+    pub spinner: &'static [&'static str],
+    pub border: border::Set<'static>,
+    pub bar: &'static bar::Set<'static>,
+    /// Filled and unfilled part of the goal gauges
+    pub gauge: [&'static str; 2],
+}
+
+impl Symbols {
+    pub const UNICODE: Symbols = Symbols {
+        span_start: "├",
+        span_join: "┼",
+        span_end: "┤",
+        span_fill: "─",
+        non_billable_fill: "┄",
+        message_fill: "╶",
+        scroll_left: "‹",
+        scroll_right: "›",
+        selected_day: "▲",
+        idle_day: "·",
+        heatmap_day: "■",
+        highlight: "▶ ",
+        spinner: &["⣾", "⣽", "⣻", "⢿", "⡿", "⣟", "⣯", "⣷"],
+        border: border::PLAIN,
+        bar: &bar::NINE_LEVELS,
+        gauge: ["━", "─"],
+    };
+
+    /// Symbols for fonts and terminals without box-drawing characters
+    pub const ASCII: Symbols = Symbols {
+        span_start: "|",
+        span_join: "+",
+        span_end: "|",
+        span_fill: "-",
+        non_billable_fill: "~",
+        message_fill: ".",
+        scroll_left: "<",
+        scroll_right: ">",
+        selected_day: "^",
+        idle_day: ".",
+        heatmap_day: "#",
+        highlight: "> ",
+        spinner: &["|", "/", "-", "\\"],
+        border: border::Set {
+            top_left: "+",
+            top_right: "+",
+            bottom_left: "+",
+            bottom_right: "+",
+            vertical_left: "|",
+            vertical_right: "|",
+            horizontal_top: "-",
+            horizontal_bottom: "-",
+        },
+        bar: &bar::Set {
+            full: "#",
+            seven_eighths: "#",
+            three_quarters: "#",
+            five_eighths: "#",
+            half: "#",
+            three_eighths: " ",
+            one_quarter: " ",
+            one_eighth: " ",
+            empty: " ",
+        },
+        gauge: ["#", "-"],
+    };
+}
+
+/// Whether the terminal can likely show box-drawing characters, judged from the locale and
+/// the terminal type like other terminal programs do
+fn unicode_supported() -> bool {
+    let var = |name| {
+        env::var(name)
+            .ok()
+            .filter(|value: &String| !value.is_empty())
+    };
+    let locale = var("LC_ALL")
+        .or_else(|| var("LC_CTYPE"))
+        .or_else(|| var("LANG"));
+    unicode_locale(locale.as_deref(), var("TERM").as_deref())
+}
+
+fn unicode_locale(locale: Option<&str>, term: Option<&str>) -> bool {
+    // The Linux console only has a limited font
+    if term == Some("linux") {
+        return false;
+    }
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        // Without a locale, e.g. on Windows, assume a modern terminal
+        None => true,
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+/// Built-in theme the configured colors are applied on top of.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeName {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Color written in the config as a name (`"dark-gray"`), index (`"28"`) or hex (`"#005f00"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThemeColor(pub Color);
+
+impl Serialize for ThemeColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Color::from_str(&value)
+            .map(ThemeColor)
+            .map_err(|_| serde::de::Error::custom(format!("invalid color `{}`", value)))
+    }
+}
+
+/// `[theme]` section of `config.toml`.
+///
+/// ```toml
+/// [theme]
+/// name = "light"
+/// comment = "#005f00"
+/// ascii = true
+/// ```
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ThemeConfig {
+    #[serde(default)]
+    pub name: ThemeName,
+    /// Draw only ASCII characters, detected from the locale when not set
+    pub ascii: Option<bool>,
+    pub help: Option<ThemeColor>,
+    pub comment: Option<ThemeColor>,
+    pub missing_message: Option<ThemeColor>,
+    pub unregistered: Option<ThemeColor>,
+    pub selection: Option<ThemeColor>,
+    pub accent: Option<ThemeColor>,
+    pub good: Option<ThemeColor>,
+    pub bad: Option<ThemeColor>,
+}
+
+impl ThemeConfig {
+    /// Resolves the configured colors against the selected built-in theme
+    pub fn theme(&self) -> Theme {
+        let base = match self.name {
+            ThemeName::Dark => Theme::dark(),
+            ThemeName::Light => Theme::light(),
+        };
+        Theme {
+            help: self.help.map_or(base.help, |c| c.0),
+            comment: self.comment.map_or(base.comment, |c| c.0),
+            missing_message: self.missing_message.map_or(base.missing_message, |c| c.0),
+            unregistered: self.unregistered.map_or(base.unregistered, |c| c.0),
+            selection: self.selection.map_or(base.selection, |c| c.0),
+            accent: self.accent.map_or(base.accent, |c| c.0),
+            good: self.good.map_or(base.good, |c| c.0),
+            bad: self.bad.map_or(base.bad, |c| c.0),
+            symbols: if self.ascii.unwrap_or_else(|| !unicode_supported()) {
+                Symbols::ASCII
+            } else {
+                Symbols::UNICODE
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_overrides_builtin() {
+        let config: ThemeConfig = toml::from_str(
+            r##"
+            name = "light"
+            comment = "#00ff00"
+            "##,
+        )
+        .unwrap();
+
+        let theme = config.theme();
+
+        assert_eq!(theme.comment, Color::Rgb(0, 255, 0));
+        assert_eq!(theme.help, Theme::light().help);
+    }
+
+    #[test]
+    fn test_ascii_symbols() {
+        let config: ThemeConfig = toml::from_str("ascii = true").unwrap();
+        assert_eq!(config.theme().symbols, Symbols::ASCII);
+
+        assert!(unicode_locale(Some("cs_CZ.UTF-8"), Some("xterm-256color")));
+        assert!(unicode_locale(None, None));
+        assert!(!unicode_locale(Some("C"), Some("xterm")));
+        assert!(!unicode_locale(Some("en_US.UTF-8"), Some("linux")));
+    }
+
+    #[test]
+    fn test_invalid_color_is_rejected() {
+        let config: Result<ThemeConfig, _> = toml::from_str(r#"help = "not-a-color""#);
+        assert!(config.is_err());
+    }
+}
@@ -1,29 +1,507 @@
-use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Weekday};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
 
-use crate::app::Checkpoint;
+use chrono::{
+    DateTime, Datelike, Days, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+    Utc, Weekday,
+};
 
-pub const UNIT: u32 = 15;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::app::{Checkpoint, CheckpointKind};
+
+/// Time of a checkpoint in the timezone it is shown and entered in
+pub type Time = DateTime<chrono_tz::Tz>;
+
+/// The timezone of the system, UTC when it can't be told
+pub fn system_timezone() -> chrono_tz::Tz {
+    static SYSTEM: OnceLock<chrono_tz::Tz> = OnceLock::new();
+    *SYSTEM.get_or_init(|| {
+        iana_time_zone::get_timezone()
+            .ok()
+            .and_then(|name| name.parse().ok())
+            .unwrap_or(chrono_tz::UTC)
+    })
+}
+
+/// How the times are shown and measured, set from the config
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeSettings {
+    /// Timezone the times are shown and entered in
+    pub timezone: chrono_tz::Tz,
+    pub rounding: Rounding,
+    pub locale: Locale,
+    /// Durations are written as decimal hours, as PBS and invoices want them
+    pub decimal_hours: bool,
+}
+
+impl Default for TimeSettings {
+    fn default() -> Self {
+        Self {
+            timezone: system_timezone(),
+            rounding: Rounding::DEFAULT,
+            locale: Locale::DEFAULT,
+            decimal_hours: false,
+        }
+    }
+}
+
+impl TimeSettings {
+    pub fn now(&self) -> Time {
+        Utc::now().with_timezone(&self.timezone)
+    }
+
+    /// The date and time of day in the timezone, the earlier one when it is ambiguous
+    pub fn display_time(&self, time: NaiveDateTime) -> Option<Time> {
+        self.timezone.from_local_datetime(&time).earliest()
+    }
+
+    /// Minutes as [`human_duration`], or as [`decimal_hours`] when they are set
+    pub fn duration(&self, minutes: u32) -> String {
+        if self.decimal_hours {
+            decimal_hours(minutes)
+        } else {
+            human_duration(minutes)
+        }
+    }
+
+    /// Signed duration such as `+1h30m` or `-45m`
+    pub fn balance(&self, minutes: i64) -> String {
+        let sign = if minutes < 0 { "-" } else { "+" };
+        format!("{}{}", sign, self.duration(minutes.unsigned_abs() as u32))
+    }
+}
+
+/// The time without its seconds. Subtracted from the instant, as the local time of the hour
+/// repeated when DST ends is ambiguous.
+fn whole_minute<Tz: TimeZone>(dt: DateTime<Tz>) -> DateTime<Tz> {
+    let seconds =
+        Duration::seconds(dt.second() as i64) + Duration::nanoseconds(dt.nanosecond() as i64);
+    dt - seconds
+}
+
+/// How the times of checkpoints are rounded before the spans between them are measured
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingStrategy {
+    /// Up from the half of the unit, down below it
+    #[default]
+    Nearest,
+    Up,
+    Down,
+    /// Times are only cut to whole minutes
+    None,
+}
+
+/// Rounding unit and strategy, set from the `[rounding]` table of the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rounding {
+    /// Minutes, one of [`Rounding::UNITS`]
+    #[serde(default = "default_unit", deserialize_with = "deserialize_unit")]
+    pub unit: u32,
+    #[serde(default)]
+    pub strategy: RoundingStrategy,
+    /// Spread the rounding error over the spans of a day, so that the rounded total of its work
+    /// is within one unit of the raw total
+    #[serde(default)]
+    pub reconcile: bool,
+}
+
+impl Rounding {
+    pub const DEFAULT: Rounding = Rounding {
+        unit: 15,
+        strategy: RoundingStrategy::Nearest,
+        reconcile: false,
+    };
+    /// Units dividing an hour evenly, so that rounded times fall on the same marks every hour
+    pub const UNITS: [u32; 5] = [5, 10, 15, 30, 60];
+
+    /// Rounds the time to the unit, seconds are always dropped
+    pub fn round<Tz: TimeZone>(&self, dt: DateTime<Tz>) -> DateTime<Tz> {
+        let remainder = (dt.minute() % self.unit) as i64;
+        let minutes = match self.strategy {
+            RoundingStrategy::Nearest if remainder * 2 >= self.unit as i64 => {
+                self.unit as i64 - remainder
+            }
+            RoundingStrategy::Up if remainder > 0 => self.unit as i64 - remainder,
+            RoundingStrategy::Nearest | RoundingStrategy::Down => -remainder,
+            RoundingStrategy::Up | RoundingStrategy::None => 0,
+        };
+        whole_minute(dt + Duration::minutes(minutes))
+    }
+
+    /// Minutes in one unit of a [`TimeSpan`], a single minute when times are not rounded
+    pub fn unit_minutes(&self) -> u32 {
+        match self.strategy {
+            RoundingStrategy::None => 1,
+            _ => self.unit,
+        }
+    }
+
+    /// Spans between the consecutive checkpoints of a day.
+    ///
+    /// Each span lasts from the rounded time of its checkpoint to the rounded time of the next
+    /// one, unless [`Rounding::reconcile`] is set. Then the work spans and the other spans are
+    /// each given units by the largest-remainder method of their raw lengths.
+    pub fn spans(&self, checkpoints: &[Checkpoint]) -> Vec<TimeSpan> {
+        let mut spans: Vec<TimeSpan> = checkpoints
+            .windows(2)
+            .map(|pair| {
+                let end = if ends_evening(&pair[1]) {
+                    whole_minute(pair[1].time + Duration::seconds(1))
+                } else {
+                    self.round(pair[1].time)
+                };
+                let rounded = end - self.round(pair[0].time);
+                let minutes = rounded.num_minutes().max(0) as u32;
+                let issue = (pair[1].time < pair[0].time).then_some(SpanIssue::Overlap);
+                TimeSpan {
+                    units: (minutes / self.unit_minutes()) as u16,
+                    unit: self.unit_minutes(),
+                    issue,
+                }
+            })
+            .collect();
+
+        if self.reconcile {
+            for work in [true, false] {
+                let (indices, raw): (Vec<usize>, Vec<f64>) = checkpoints
+                    .windows(2)
+                    .enumerate()
+                    .filter(|(_, pair)| pair[0].is_work() == work)
+                    .map(|(idx, pair)| {
+                        let seconds = (pair[1].time - pair[0].time).num_seconds().max(0);
+                        (idx, seconds as f64 / 60.0)
+                    })
+                    .unzip();
+                for (idx, units) in indices
+                    .into_iter()
+                    .zip(largest_remainder(&raw, self.unit_minutes()))
+                {
+                    spans[idx].units = units;
+                }
+            }
+        }
+
+        for span in &mut spans {
+            if span.issue.is_none() && span.units == 0 {
+                span.issue = Some(SpanIssue::Empty);
+            }
+        }
+        spans
+    }
+}
+
+/// Whole units for the lengths in minutes adding up to their rounded total. Every length gets
+/// its whole units, the units left over go to the lengths with the largest remainders.
+fn largest_remainder(minutes: &[f64], unit: u32) -> Vec<u16> {
+    let unit = unit as f64;
+    let mut units: Vec<u16> = minutes.iter().map(|m| (m / unit) as u16).collect();
+    let total = (minutes.iter().sum::<f64>() / unit).round() as u16;
+    let left = total.saturating_sub(units.iter().sum());
+
+    let remainder = |idx: usize| minutes[idx] / unit - units[idx] as f64;
+    let mut order: Vec<usize> = (0..minutes.len()).collect();
+    // The sort is stable, equal remainders go to the earlier spans first
+    order.sort_by(|&a, &b| remainder(b).total_cmp(&remainder(a)));
+    for idx in order.into_iter().take(left as usize) {
+        units[idx] += 1;
+    }
+    units
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn default_unit() -> u32 {
+    Rounding::DEFAULT.unit
+}
+
+fn deserialize_unit<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    let unit = u32::deserialize(deserializer)?;
+    if Rounding::UNITS.contains(&unit) {
+        Ok(unit)
+    } else {
+        Err(serde::de::Error::custom(format!(
+            "rounding unit must be one of {:?} minutes",
+            Rounding::UNITS
+        )))
+    }
+}
+
+/// How dates are written, the day first or ISO 8601
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateFormat {
+    /// `31.12.25`
+    #[default]
+    Dmy,
+    /// `2025-12-31`
+    Iso,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Clock {
+    /// `17:30`
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    /// `5:30pm`
+    #[serde(rename = "12h")]
+    H12,
+}
+
+/// First day of the week and formats of the dates and times, set from the `[locale]` table of
+/// the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Locale {
+    /// Weeks of the week view, the stats and the reports start with it
+    #[serde(default = "default_first_day_of_week")]
+    pub first_day_of_week: Weekday,
+    #[serde(default)]
+    pub date_format: DateFormat,
+    #[serde(default)]
+    pub clock: Clock,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl Locale {
+    pub const DEFAULT: Locale = Locale {
+        first_day_of_week: Weekday::Mon,
+        date_format: DateFormat::Dmy,
+        clock: Clock::H24,
+    };
+
+    /// First day of the week containing the date
+    pub fn week_start(&self, date: NaiveDate) -> NaiveDate {
+        date.week(self.first_day_of_week).first_day()
+    }
+
+    /// Date of the weekday in the week starting on `week_start`
+    pub fn date_in_week(&self, week_start: NaiveDate, weekday: Weekday) -> NaiveDate {
+        week_start + Days::new(weekday.days_since(self.first_day_of_week) as u64)
+    }
+
+    /// Number of the week containing the date. Weeks starting on Monday are numbered as in ISO
+    /// 8601, other weeks count from the one containing the 1st of January.
+    pub fn week_number(&self, date: NaiveDate) -> u32 {
+        if self.first_day_of_week == Weekday::Mon {
+            return date.iso_week().week();
+        }
+        let start = self.week_start(date);
+        let year = (start + Days::new(6)).year();
+        let first = self.week_start(NaiveDate::from_ymd_opt(year, 1, 1).unwrap());
+        (start - first).num_days() as u32 / 7 + 1
+    }
+
+    /// The seven weekdays starting with the first day of the week
+    pub fn weekdays(&self) -> [Weekday; 7] {
+        let mut weekday = self.first_day_of_week;
+        [(); 7].map(|_| {
+            let day = weekday;
+            weekday = weekday.succ();
+            day
+        })
+    }
+
+    /// The date with the year
+    pub fn date(&self, date: NaiveDate) -> String {
+        match self.date_format {
+            DateFormat::Dmy => date.format("%d.%m.%y"),
+            DateFormat::Iso => date.format("%Y-%m-%d"),
+        }
+        .to_string()
+    }
+
+    /// The day and month without the year, for dates within the shown week or month
+    pub fn day(&self, date: NaiveDate) -> String {
+        match self.date_format {
+            DateFormat::Dmy => date.format("%d.%m"),
+            DateFormat::Iso => date.format("%m-%d"),
+        }
+        .to_string()
+    }
+
+    /// Hours and minutes of the time
+    pub fn time(&self, time: NaiveTime) -> String {
+        match self.clock {
+            Clock::H24 => time.format("%H:%M"),
+            Clock::H12 => time.format("%-I:%M%P"),
+        }
+        .to_string()
+    }
+
+    /// The date with the year followed by the time
+    pub fn date_time(&self, time: Time) -> String {
+        format!(
+            "{} {}",
+            self.date(time.date_naive()),
+            self.time(time.time())
+        )
+    }
+}
+
+fn default_first_day_of_week() -> Weekday {
+    Locale::DEFAULT.first_day_of_week
+}
 
 #[derive(Default)]
 pub struct TimeSpan {
     pub units: u16,
+    /// Minutes in one of the units
+    pub unit: u32,
+    /// Set when the span does not render as the checkpoints suggest
+    pub issue: Option<SpanIssue>,
+}
+
+/// Problem with a span caused by the times of its checkpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanIssue {
+    /// Both checkpoints round to the same time, so the span has no length
+    Empty,
+    /// The next checkpoint is earlier than the span's checkpoint
+    Overlap,
+}
+
+impl SpanIssue {
+    pub fn description(&self) -> &'static str {
+        match self {
+            SpanIssue::Empty => "rounds to zero minutes",
+            SpanIssue::Overlap => "ends before it starts",
+        }
+    }
+
+    /// How to get rid of the issue
+    pub fn hint(&self) -> &'static str {
+        match self {
+            SpanIssue::Empty => "delete one of the checkpoints (d) or merge the spans (J)",
+            SpanIssue::Overlap => "put the checkpoints in order (O) or retime them (T)",
+        }
+    }
+}
+
+/// How [`fix_overlaps`] puts the checkpoints of a day back in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapFix {
+    /// Exchange the times of the checkpoints, their projects and messages stay in place
+    Swap,
+    /// Move a checkpoint earlier than its predecessor to the predecessor's time
+    Clamp,
+}
+
+/// Number of spans of the day ending before they start
+pub fn count_overlaps(checkpoints: &[Checkpoint]) -> usize {
+    checkpoints
+        .windows(2)
+        .filter(|pair| pair[1].time < pair[0].time)
+        .count()
+}
+
+/// Removes the spans ending before they start, e.g. after manual time edits.
+///
+/// # Returns
+///
+/// The indices of the checkpoints whose time changed, in ascending order.
+pub fn fix_overlaps(checkpoints: &mut [Checkpoint], fix: OverlapFix) -> Vec<usize> {
+    let mut changed = BTreeSet::new();
+    match fix {
+        OverlapFix::Swap => {
+            // Swapping a pair can invert the pair before it, so repeat until all are in order
+            let mut swapped = true;
+            while swapped {
+                swapped = false;
+                for i in 1..checkpoints.len() {
+                    if checkpoints[i].time < checkpoints[i - 1].time {
+                        let time = checkpoints[i].time;
+                        checkpoints[i].time = checkpoints[i - 1].time;
+                        checkpoints[i - 1].time = time;
+                        changed.extend([i - 1, i]);
+                        swapped = true;
+                    }
+                }
+            }
+        }
+        OverlapFix::Clamp => {
+            for i in 1..checkpoints.len() {
+                if checkpoints[i].time < checkpoints[i - 1].time {
+                    checkpoints[i].time = checkpoints[i - 1].time;
+                    changed.insert(i);
+                }
+            }
+        }
+    }
+    changed.into_iter().collect()
 }
 
 impl TimeSpan {
-    pub fn human_time(&self) -> String {
-        human_duration(self.units as u32 * UNIT)
+    /// Rounded length of the span
+    pub fn minutes(&self) -> u32 {
+        self.units as u32 * self.unit
+    }
+}
+
+/// Summary of a single day used to tell whether the day is ready for registration.
+///
+/// Breaks and absences are left out.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DayStats {
+    /// Total rounded duration of all spans in minutes
+    pub tracked_minutes: u32,
+    /// Number of spans without a project or message
+    pub incomplete_spans: usize,
+    /// Number of spans not yet registered
+    pub unregistered_spans: usize,
+    /// Number of spans with a [`SpanIssue`], including breaks and absences
+    pub span_warnings: usize,
+}
+
+impl DayStats {
+    pub fn from_checkpoints(checkpoints: &[Checkpoint], rounding: &Rounding) -> Self {
+        let spans = rounding.spans(checkpoints);
+        let mut stats = Self {
+            span_warnings: spans.iter().filter(|span| span.issue.is_some()).count(),
+            ..Self::default()
+        };
+        for (start, span) in checkpoints.iter().zip(spans) {
+            if !start.is_work() {
+                continue;
+            }
+            stats.tracked_minutes += span.minutes();
+            if start.project.is_none() || start.message.as_deref().unwrap_or("").is_empty() {
+                stats.incomplete_spans += 1;
+            }
+            if !start.registered {
+                stats.unregistered_spans += 1;
+            }
+        }
+        stats
     }
 }
 
+#[derive(Clone)]
 pub struct Week {
     pub mon: Vec<Checkpoint>,
     pub tue: Vec<Checkpoint>,
     pub wed: Vec<Checkpoint>,
     pub thu: Vec<Checkpoint>,
     pub fri: Vec<Checkpoint>,
+    pub sat: Vec<Checkpoint>,
+    pub sun: Vec<Checkpoint>,
+    /// Saturday and Sunday are shown and selectable, their hours count either way
+    pub show_weekend: bool,
     pub unregistered_checkpoints: Vec<(Checkpoint, u32)>,
     pub selected_weekday: Weekday,
     pub selected_checkpoint_idx: usize,
+    /// Day the week starts with, the days are ordered from it
+    pub first_day_of_week: Weekday,
 }
 
 impl Week {
@@ -34,9 +512,13 @@ impl Week {
             wed: vec![],
             thu: vec![],
             fri: vec![],
+            sat: vec![],
+            sun: vec![],
+            show_weekend: false,
             unregistered_checkpoints: vec![],
             selected_weekday: Weekday::Mon,
             selected_checkpoint_idx: 0,
+            first_day_of_week: Weekday::Mon,
         }
     }
     pub fn active_day(&self) -> &Vec<Checkpoint> {
@@ -46,8 +528,8 @@ impl Week {
             Weekday::Wed => &self.wed,
             Weekday::Thu => &self.thu,
             Weekday::Fri => &self.fri,
-            Weekday::Sat => unimplemented!(),
-            Weekday::Sun => unimplemented!(),
+            Weekday::Sat => &self.sat,
+            Weekday::Sun => &self.sun,
         }
     }
 
@@ -67,37 +549,24 @@ impl Week {
 
     pub fn select_next_day(&mut self) {
         self.selected_weekday = match self.selected_weekday {
-            Weekday::Mon => Weekday::Tue,
-            Weekday::Tue => Weekday::Wed,
-            Weekday::Wed => Weekday::Thu,
-            Weekday::Thu => Weekday::Fri,
-            Weekday::Fri => Weekday::Mon,
-            Weekday::Sat => unimplemented!(),
-            Weekday::Sun => unimplemented!(),
+            Weekday::Fri if !self.show_weekend => Weekday::Mon,
+            weekday => weekday.succ(),
         };
 
         self.select_max_checkpoint_idx();
     }
 
-    fn select_max_checkpoint_idx(&mut self) {
-        self.selected_checkpoint_idx = match self.active_day().len() {
-            0..1 => 0,
-            active_day_len if self.selected_checkpoint_idx > active_day_len - 1 => {
-                active_day_len - 2
-            }
-            _ => self.selected_checkpoint_idx,
-        };
+    /// Moves a selection past the end of the active day to its last checkpoint, which
+    /// [`Week::select_next_checkpoint`] reaches too
+    pub fn select_max_checkpoint_idx(&mut self) {
+        let last = self.active_day().len().saturating_sub(1);
+        self.selected_checkpoint_idx = self.selected_checkpoint_idx.min(last);
     }
 
     pub fn select_prev_day(&mut self) {
         self.selected_weekday = match self.selected_weekday {
-            Weekday::Mon => Weekday::Fri,
-            Weekday::Tue => Weekday::Mon,
-            Weekday::Wed => Weekday::Tue,
-            Weekday::Thu => Weekday::Wed,
-            Weekday::Fri => Weekday::Thu,
-            Weekday::Sat => unimplemented!(),
-            Weekday::Sun => unimplemented!(),
+            Weekday::Mon if !self.show_weekend => Weekday::Fri,
+            weekday => weekday.pred(),
         };
 
         self.select_max_checkpoint_idx();
@@ -107,15 +576,100 @@ impl Week {
         self.active_day_mut().push(checkpoint);
     }
 
-    fn active_day_mut(&mut self) -> &mut Vec<Checkpoint> {
+    /// Removes the selected checkpoint from the active day, keeping the selection in bounds.
+    pub fn remove_selected_checkpoint(&mut self) -> Option<Checkpoint> {
+        let idx = self.selected_checkpoint_idx;
+        let day = self.active_day_mut();
+        if idx >= day.len() {
+            return None;
+        }
+        let checkpoint = day.remove(idx);
+        self.select_max_checkpoint_idx();
+        Some(checkpoint)
+    }
+
+    /// Merges the selected span with the following one by removing the checkpoint between them.
+    ///
+    /// When `keep_next` is set, the merged span takes what the following span was spent on: its
+    /// project, activity, message, kind and billable flag. Returns the removed boundary checkpoint, or `None` if there is no following span.
+    pub fn merge_with_next(&mut self, keep_next: bool) -> Option<Checkpoint> {
+        let idx = self.selected_checkpoint_idx;
+        let day = self.active_day_mut();
+        if day.len() < idx + 3 {
+            return None;
+        }
+        let boundary = day.remove(idx + 1);
+        let selected = &mut day[idx];
+        if keep_next {
+            selected.project = boundary.project.clone();
+            selected.activity = boundary.activity.clone();
+            selected.message = boundary.message.clone();
+            selected.tags = boundary.tags.clone();
+            selected.kind = boundary.kind;
+            selected.billable = boundary.billable;
+        }
+        selected.registered = selected.registered && boundary.registered;
+        Some(boundary)
+    }
+
+    /// Inserts a checkpoint into the active day keeping it ordered by time and selects it.
+    pub fn insert_checkpoint(&mut self, checkpoint: Checkpoint) {
+        let day = self.active_day_mut();
+        let idx = day.partition_point(|ch| ch.time <= checkpoint.time);
+        day.insert(idx, checkpoint);
+        self.selected_checkpoint_idx = idx;
+    }
+
+    /// Checkpoints of all the days paired with their weekday, from the first day of the week of
+    /// [`Week::first_day_of_week`] on
+    pub fn days(&self) -> [(Weekday, &Vec<Checkpoint>); 7] {
+        let mut days = [
+            (Weekday::Mon, &self.mon),
+            (Weekday::Tue, &self.tue),
+            (Weekday::Wed, &self.wed),
+            (Weekday::Thu, &self.thu),
+            (Weekday::Fri, &self.fri),
+            (Weekday::Sat, &self.sat),
+            (Weekday::Sun, &self.sun),
+        ];
+        days.rotate_left(self.first_day_of_week.num_days_from_monday() as usize);
+        days
+    }
+
+    /// The days shown in the week view, the weekend only when [`Week::show_weekend`] is set
+    pub fn visible_days(&self) -> Vec<(Weekday, &Vec<Checkpoint>)> {
+        self.days()
+            .into_iter()
+            .filter(|(weekday, _)| {
+                self.show_weekend || !matches!(weekday, Weekday::Sat | Weekday::Sun)
+            })
+            .collect()
+    }
+
+    /// Checkpoints of all the days, in the order of [`Week::days`]
+    pub fn days_mut(&mut self) -> [&mut Vec<Checkpoint>; 7] {
+        let mut days = [
+            &mut self.mon,
+            &mut self.tue,
+            &mut self.wed,
+            &mut self.thu,
+            &mut self.fri,
+            &mut self.sat,
+            &mut self.sun,
+        ];
+        days.rotate_left(self.first_day_of_week.num_days_from_monday() as usize);
+        days
+    }
+
+    pub fn active_day_mut(&mut self) -> &mut Vec<Checkpoint> {
         match self.selected_weekday {
             Weekday::Mon => &mut self.mon,
             Weekday::Tue => &mut self.tue,
             Weekday::Wed => &mut self.wed,
             Weekday::Thu => &mut self.thu,
             Weekday::Fri => &mut self.fri,
-            Weekday::Sat => unimplemented!(),
-            Weekday::Sun => unimplemented!(),
+            Weekday::Sat => &mut self.sat,
+            Weekday::Sun => &mut self.sun,
         }
     }
 
@@ -140,87 +694,12 @@ impl Week {
     }
 
     pub fn selected_checkpoint_mut(&mut self) -> Option<&mut Checkpoint> {
-        match self.selected_weekday {
-            Weekday::Mon => {
-                if self.mon.len() > self.selected_checkpoint_idx {
-                    Some(&mut self.mon[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Tue => {
-                if self.tue.len() > self.selected_checkpoint_idx {
-                    Some(&mut self.tue[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Wed => {
-                if self.wed.len() > self.selected_checkpoint_idx {
-                    Some(&mut self.wed[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Thu => {
-                if self.thu.len() > self.selected_checkpoint_idx {
-                    Some(&mut self.thu[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Fri => {
-                if self.fri.len() > self.selected_checkpoint_idx {
-                    Some(&mut self.fri[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Sat => None,
-            Weekday::Sun => None,
-        }
+        let idx = self.selected_checkpoint_idx;
+        self.active_day_mut().get_mut(idx)
     }
 
     pub fn selected_checkpoint(&self) -> Option<&Checkpoint> {
-        match self.selected_weekday {
-            Weekday::Mon => {
-                if self.mon.len() > self.selected_checkpoint_idx {
-                    Some(&self.mon[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Tue => {
-                if self.tue.len() > self.selected_checkpoint_idx {
-                    Some(&self.tue[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Wed => {
-                if self.wed.len() > self.selected_checkpoint_idx {
-                    Some(&self.wed[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Thu => {
-                if self.thu.len() > self.selected_checkpoint_idx {
-                    Some(&self.thu[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Fri => {
-                if self.fri.len() > self.selected_checkpoint_idx {
-                    Some(&self.fri[self.selected_checkpoint_idx])
-                } else {
-                    None
-                }
-            }
-            Weekday::Sat => None,
-            Weekday::Sun => None,
-        }
+        self.active_day().get(self.selected_checkpoint_idx)
     }
 }
 
@@ -230,92 +709,26 @@ impl Default for Week {
     }
 }
 
-pub fn round_to_nearest_fifteen_minutes<Tz: TimeZone>(dt: DateTime<Tz>) -> DateTime<Tz> {
-    let minute = dt.minute();
-    let remainder = minute % 15;
-
-    let rounded_dt = if remainder >= 8 {
-        // Round up
-        let minutes_to_add = 15 - remainder;
-        dt + Duration::minutes(minutes_to_add as i64)
-    } else {
-        // Round down
-        let minutes_to_subtract = remainder;
-        dt - Duration::minutes(minutes_to_subtract as i64)
-    };
-
-    // Zero out seconds and microseconds
-    rounded_dt
-        .with_second(0)
-        .unwrap()
-        .with_nanosecond(0)
-        .unwrap()
-    /*
-        // Get time components
-        let minute = dt.minute();
-        let second = dt.second();
-
-        // Calculate total seconds and nanos into the current hour
-        let total_secs = minute * 60 + second;
-
-        // Duration of 15 minutes in seconds
-        let fifteen_mins_secs = UNIT * 60;
-
-        // Calculate the nearest 15-minute mark
-        let rounded_secs =
-            ((total_secs as f64 / fifteen_mins_secs as f64).round() * fifteen_mins_secs as f64) as i64;
-
-        // Create a duration from the start of the hour
-        let duration_from_hour_start = Duration::seconds(rounded_secs);
-
-        // Start of the current hour
-        let hour_start = dt.with_minute(0).unwrap().with_second(0).unwrap();
-
-        // Add the rounded duration to the start of the hour
-        hour_start + duration_from_hour_start
-    */
-}
-
-/// Calculates the number of 15-minute intervals between two DateTime objects.
-///
-/// This function assumes that both DateTime objects are already rounded to 15-minute intervals.
-/// If they are not, the result may not be accurate.
-///
-/// # Arguments
-///
-/// * `start` - The starting DateTime, assumed to be rounded to 15 minutes
-/// * `end` - The ending DateTime, assumed to be rounded to 15 minutes
-///
-/// # Returns
-///
-/// The number of 15-minute intervals between the two DateTimes.
-/// Returns a positive number if `end` is after `start`, or a negative number if `end` is before `start`.
-pub fn count_fifteen_minute_intervals<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> i64 {
-    // Calculate the duration between the two DateTimes
-    let duration = end.signed_duration_since(start);
-
-    // Convert the duration to minutes
-    let minutes = duration.num_minutes();
-
-    // Divide by 15 to get the number of 15-minute intervals
-    minutes / UNIT as i64
-}
-
 /// Calculates the duration between two DateTime objects in minutes.
 ///
-/// The start and end times are rounded to the nearest 15 minutes before calculating the duration.
+/// The start and end times are rounded with the [`Rounding`] before calculating the duration.
 ///
 /// # Arguments
 ///
 /// * `start` - The starting DateTime
 /// * `end` - The ending DateTime
+/// * `rounding` - How the times are rounded
 ///
 /// # Returns
 ///
 /// The duration in minutes. Returns 0 if the duration is negative.
-pub fn calculate_duration_minutes<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> u32 {
-    let rounded_start = round_to_nearest_fifteen_minutes(start);
-    let rounded_end = round_to_nearest_fifteen_minutes(end);
+pub fn calculate_duration_minutes<Tz: TimeZone>(
+    start: DateTime<Tz>,
+    end: DateTime<Tz>,
+    rounding: &Rounding,
+) -> u32 {
+    let rounded_start = rounding.round(start);
+    let rounded_end = rounding.round(end);
     let duration = rounded_end.signed_duration_since(rounded_start);
     duration.num_minutes().max(0) as u32
 }
@@ -344,48 +757,364 @@ pub fn human_duration(minutes: u32) -> String {
     }
 }
 
-pub fn time_spans(checkpoints: &[Checkpoint]) -> Vec<TimeSpan> {
-    // If we have fewer than 2 checkpoints, we can't calculate any time spans
-    if checkpoints.len() < 2 {
-        return Vec::new();
+/// Minutes as hours with at most two decimals, e.g. "7.75h" or "8h"
+pub fn decimal_hours(minutes: u32) -> String {
+    let hours = format!("{:.2}", minutes as f64 / 60.0);
+    format!("{}h", hours.trim_end_matches('0').trim_end_matches('.'))
+}
+
+/// Parses a wall-clock time in `HH:MM` (or `H:MM`) format.
+///
+/// # Returns
+///
+/// `None` if the input is not a valid time of day.
+pub fn parse_hh_mm(input: &str) -> Option<NaiveTime> {
+    let (hours, minutes) = input.trim().split_once(':')?;
+    if hours.is_empty() || hours.len() > 2 || minutes.len() != 2 {
+        return None;
+    }
+    NaiveTime::from_hms_opt(hours.parse().ok()?, minutes.parse().ok()?, 0)
+}
+
+/// Returns the last checkpoint when it is still running at `now`, with minutes elapsed since it.
+///
+/// A checkpoint is running when it is the last one of the same day as `now` and lies in the past.
+pub fn running_span<Tz: TimeZone>(
+    checkpoints: &[Checkpoint],
+    now: DateTime<Tz>,
+) -> Option<(&Checkpoint, u32)> {
+    let last = checkpoints.last()?;
+    let now = now.with_timezone(&last.time.timezone());
+    if last.kind == CheckpointKind::Stop
+        || last.time.date_naive() != now.date_naive()
+        || last.time > now
+    {
+        return None;
+    }
+    Some((last, (now - last.time).num_minutes() as u32))
+}
+
+/// Unregistered work spans with their rounded duration in minutes.
+///
+/// The checkpoints must be ordered by time and may span several days. The last checkpoint of each
+/// day only ends the previous span and is left out.
+pub fn unregistered_spans(
+    checkpoints: &[Checkpoint],
+    rounding: &Rounding,
+) -> Vec<(Checkpoint, u32)> {
+    checkpoints
+        .chunk_by(|a, b| a.time.date_naive() == b.time.date_naive())
+        .flat_map(|day| day.iter().zip(rounding.spans(day)))
+        .filter(|(start, _)| !start.registered && start.is_work())
+        .map(|(start, span)| (start.clone(), span.minutes()))
+        .collect()
+}
+
+/// Copies the checkpoints to another date keeping their time of day, project, message and kind.
+///
+/// The copies are unregistered and not yet stored.
+pub fn copy_to_date(checkpoints: &[Checkpoint], date: NaiveDate) -> Vec<Checkpoint> {
+    checkpoints
+        .iter()
+        .filter_map(|checkpoint| {
+            let time = checkpoint
+                .time
+                .timezone()
+                .from_local_datetime(&date.and_time(checkpoint.time.time()))
+                .earliest()?;
+            Some(Checkpoint {
+                id: None,
+                time,
+                registered: false,
+                toggl_id: None,
+                ..checkpoint.clone()
+            })
+        })
+        .collect()
+}
+
+/// Checkpoints after midnight earlier than this end the span started the evening before
+pub const OVERNIGHT_END: NaiveTime = NaiveTime::from_hms_opt(6, 0, 0).unwrap();
+
+/// Break added when the first checkpoint after it is appended, set from the `[lunch_break]`
+/// table of the config. It starts either at a fixed time or after hours of continuous work.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LunchBreak {
+    #[serde(default = "default_lunch_minutes")]
+    pub minutes: u32,
+    /// Time of day the break starts, e.g. `"12:00"`
+    #[serde(default)]
+    pub at: Option<NaiveTime>,
+    /// Hours of work without a break, absence or stop after which the break starts
+    #[serde(default)]
+    pub after_hours: Option<f32>,
+}
+
+fn default_lunch_minutes() -> u32 {
+    30
+}
+
+impl LunchBreak {
+    /// Start and end of the break to add before a checkpoint appended at `now`.
+    ///
+    /// There is none when the day already has a break, the clock is not running or the break
+    /// does not fit between the last checkpoint and `now`.
+    pub fn place(&self, checkpoints: &[Checkpoint], now: Time) -> Option<(Time, Time)> {
+        let day: Vec<&Checkpoint> = checkpoints
+            .iter()
+            .filter(|ch| ch.time.date_naive() == now.date_naive() && ch.time <= now)
+            .collect();
+        let last = day.last()?;
+        if !last.is_work() || day.iter().any(|ch| ch.kind == CheckpointKind::Break) {
+            return None;
+        }
+
+        let start = match (self.at, self.after_hours) {
+            (Some(at), _) => now
+                .timezone()
+                .from_local_datetime(&now.date_naive().and_time(at))
+                .earliest()
+                .filter(|start| *start >= last.time)?,
+            (None, Some(hours)) => {
+                // Work is continuous since the checkpoint after the last break, absence or stop
+                let first = day
+                    .iter()
+                    .rposition(|ch| !ch.is_work())
+                    .map_or(day[0], |idx| day[idx + 1]);
+                let threshold = first.time + Duration::minutes((hours * 60.0) as i64);
+                threshold.max(last.time)
+            }
+            (None, None) => return None,
+        };
+        let end = start + Duration::minutes(self.minutes as i64);
+        (self.minutes > 0 && end <= now).then_some((start, end))
     }
+}
 
-    let mut spans = Vec::new();
+/// Whether the second checkpoint lands in the same rounding unit as the first without adding
+/// anything to it, like after an accidental double press
+pub fn is_near_duplicate(first: &Checkpoint, second: &Checkpoint, rounding: &Rounding) -> bool {
+    let compatible = |a: &Option<String>, b: &Option<String>| {
+        a.as_deref().is_none_or(str::is_empty) || b.as_deref().is_none_or(str::is_empty) || a == b
+    };
+    first.rounded_time(rounding) == second.rounded_time(rounding)
+        && first.kind == second.kind
+        && compatible(&first.project, &second.project)
+        && compatible(&first.message, &second.message)
+}
+
+/// Merges each run of near-duplicate checkpoints of the day into its first checkpoint, which
+/// takes over the project and message it is missing.
+///
+/// # Returns
+///
+/// The first checkpoints that changed and the removed duplicates.
+pub fn merge_near_duplicates(
+    day: &mut Vec<Checkpoint>,
+    rounding: &Rounding,
+) -> (Vec<Checkpoint>, Vec<Checkpoint>) {
+    let mut kept: Vec<Checkpoint> = Vec::with_capacity(day.len());
+    let mut changed = BTreeSet::new();
+    let mut removed = vec![];
+    for checkpoint in day.drain(..) {
+        let idx = kept.len().wrapping_sub(1);
+        let Some(first) = kept
+            .last_mut()
+            .filter(|last| is_near_duplicate(last, &checkpoint, rounding))
+        else {
+            kept.push(checkpoint);
+            continue;
+        };
+        if first.project.is_none() && checkpoint.project.is_some() {
+            first.project = checkpoint.project.clone();
+            first.activity = checkpoint.activity.clone();
+            changed.insert(idx);
+        }
+        if first.message.as_deref().is_none_or(str::is_empty) && checkpoint.message.is_some() {
+            first.message = checkpoint.message.clone();
+            first.tags = checkpoint.tags.clone();
+            changed.insert(idx);
+        }
+        if first.registered && !checkpoint.registered {
+            first.registered = false;
+            changed.insert(idx);
+        }
+        removed.push(checkpoint);
+    }
+    *day = kept;
+    let changed = changed.into_iter().map(|idx| day[idx].clone()).collect();
+    (changed, removed)
+}
 
-    // Iterate through consecutive pairs of checkpoints
-    for i in 0..checkpoints.len() - 1 {
-        let start_time = checkpoints[i].time;
-        let end_time = checkpoints[i + 1].time;
+/// Time of the stop ending the evening half of an [overnight span](overnight_splits), the last
+/// second of the day
+pub const EVENING_END: NaiveTime = NaiveTime::from_hms_opt(23, 59, 59).unwrap();
 
-        let minutes = calculate_duration_minutes(start_time, end_time);
+/// The checkpoint ends the evening half of an [overnight span](overnight_splits), which lasts
+/// until midnight under every rounding strategy
+fn ends_evening(checkpoint: &Checkpoint) -> bool {
+    checkpoint.kind == CheckpointKind::Stop && checkpoint.time.time() == EVENING_END
+}
 
-        // Create a TimeSpan with the calculated number of intervals
-        let time_span = TimeSpan {
-            units: (minutes / UNIT) as u16,
+/// Checkpoints splitting the spans that run past midnight, so that each day keeps its part.
+///
+/// A span runs past midnight when it does not start with a stop and the next checkpoint falls
+/// on the following day before [`OVERNIGHT_END`]. It is split by a stop at [`EVENING_END`] and a
+/// copy of its checkpoint at 00:00 of the next day. The checkpoints must be ordered by time and
+/// the splits are not yet stored.
+pub fn overnight_splits(checkpoints: &[Checkpoint]) -> Vec<Checkpoint> {
+    let mut splits = vec![];
+    for pair in checkpoints.windows(2) {
+        let (start, end) = (&pair[0], &pair[1]);
+        let next_day = start.time.date_naive().succ_opt();
+        if start.kind == CheckpointKind::Stop
+            || Some(end.time.date_naive()) != next_day
+            || end.time.time() >= OVERNIGHT_END
+            || end.time.time() == NaiveTime::MIN
+        {
+            continue;
+        }
+        let timezone = start.time.timezone();
+        let at = |date: NaiveDate, time| timezone.from_local_datetime(&date.and_time(time));
+        let (Some(evening_end), Some(morning_start)) = (
+            at(start.time.date_naive(), EVENING_END).earliest(),
+            at(end.time.date_naive(), NaiveTime::MIN).earliest(),
+        ) else {
+            continue;
         };
+        splits.push(Checkpoint {
+            id: None,
+            time: evening_end,
+            kind: CheckpointKind::Stop,
+            registered: start.registered,
+            ..Checkpoint::new()
+        });
+        splits.push(Checkpoint {
+            id: None,
+            time: morning_start,
+            toggl_id: None,
+            ..start.clone()
+        });
+    }
+    splits
+}
+
+/// Parses a date in `YYYY-MM-DD` or `DD.MM.YYYY` format.
+pub fn parse_date(input: &str) -> Option<NaiveDate> {
+    let input = input.trim();
+    NaiveDate::parse_from_str(input, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(input, "%d.%m.%Y"))
+        .ok()
+}
 
-        spans.push(time_span);
+/// Parses a date optionally followed by a wall-clock time, e.g. `15.01.2025 08:30`.
+pub fn parse_date_time(input: &str) -> Option<(NaiveDate, Option<NaiveTime>)> {
+    let mut parts = input.split_whitespace();
+    let date = parse_date(parts.next()?)?;
+    let time = match parts.next() {
+        Some(time) => Some(parse_hh_mm(time)?),
+        None => None,
+    };
+    if parts.next().is_some() {
+        return None;
     }
-    spans
+    Some((date, time))
 }
 
-/// Returns all Mondays in the given month of the given year as NaiveDate objects.
+/// Sums the rounding units spent on each project.
+///
+/// Spans without a project are summed under `"-"`, breaks and absences are skipped.
+pub fn project_units(checkpoints: &[Checkpoint], rounding: &Rounding) -> BTreeMap<String, u16> {
+    units_by(checkpoints, rounding, |checkpoint| {
+        checkpoint.project.clone()
+    })
+}
+
+/// Like [`project_units`], with each activity of a project summed separately under
+/// `"project/activity"`.
+pub fn activity_units(checkpoints: &[Checkpoint], rounding: &Rounding) -> BTreeMap<String, u16> {
+    units_by(checkpoints, rounding, Checkpoint::project_label)
+}
+
+fn units_by(
+    checkpoints: &[Checkpoint],
+    rounding: &Rounding,
+    key: impl Fn(&Checkpoint) -> Option<String>,
+) -> BTreeMap<String, u16> {
+    let mut totals = BTreeMap::new();
+    for (checkpoint, span) in checkpoints.iter().zip(rounding.spans(checkpoints)) {
+        if !checkpoint.is_work() {
+            continue;
+        }
+        let key = key(checkpoint).unwrap_or_else(|| "-".to_string());
+        *totals.entry(key).or_insert(0) += span.units;
+    }
+    totals
+}
+
+/// Like [`project_units`], with only the billable spans
+pub fn billable_project_units(
+    checkpoints: &[Checkpoint],
+    rounding: &Rounding,
+) -> BTreeMap<String, u16> {
+    let mut units = BTreeMap::new();
+    for (checkpoint, span) in checkpoints.iter().zip(rounding.spans(checkpoints)) {
+        if checkpoint.is_work() && checkpoint.billable {
+            let project = checkpoint
+                .project
+                .clone()
+                .unwrap_or_else(|| "-".to_string());
+            *units.entry(project).or_insert(0) += span.units;
+        }
+    }
+    units
+}
+
+/// Units of billable and non-billable work
+pub fn billable_units(checkpoints: &[Checkpoint], rounding: &Rounding) -> (u16, u16) {
+    let mut units = (0, 0);
+    for (checkpoint, span) in checkpoints.iter().zip(rounding.spans(checkpoints)) {
+        if !checkpoint.is_work() {
+            continue;
+        }
+        if checkpoint.billable {
+            units.0 += span.units;
+        } else {
+            units.1 += span.units;
+        }
+    }
+    units
+}
+
+/// The project with the most units, ties go to the first project in alphabetical order
+pub fn dominant_project(checkpoints: &[Checkpoint], rounding: &Rounding) -> Option<String> {
+    project_units(checkpoints, rounding)
+        .into_iter()
+        .filter(|(_, units)| *units > 0)
+        .rev()
+        .max_by_key(|(_, units)| *units)
+        .map(|(project, _)| project)
+}
+
+/// Returns the first days of all the weeks in the given month of the given year, the weeks
+/// start with the first day of the week of the locale.
 ///
 /// # Arguments
 ///
 /// * `year` - The year
-/// * `month` - The month (1-12) for which to find all Mondays
+/// * `month` - The month (1-12) for which to find all the weeks
+/// * `locale` - Tells the first day of the week
 ///
 /// # Returns
 ///
-/// A vector of NaiveDate objects representing all Mondays in the specified month.
+/// A vector of NaiveDate objects representing the starts of the weeks of the specified month.
 /// Returns an empty vector if the month is invalid (not 1-12).
-pub fn get_mondays_in_month(year: i32, month: u32) -> Vec<NaiveDate> {
+pub fn get_week_starts_in_month(year: i32, month: u32, locale: &Locale) -> Vec<NaiveDate> {
     if !(1..=12).contains(&month) {
         return Vec::new();
     }
 
-    let mut mondays = Vec::new();
+    let mut week_starts = Vec::new();
 
     // Get the first day of the month
     let first_day = match NaiveDate::from_ymd_opt(year, month, 1) {
@@ -393,9 +1122,8 @@ pub fn get_mondays_in_month(year: i32, month: u32) -> Vec<NaiveDate> {
         None => return Vec::new(),
     };
 
-    // Find the Monday of the week containing the first day of the month.
-    let first_monday =
-        first_day - Duration::days(first_day.weekday().num_days_from_monday() as i64);
+    // Find the start of the week containing the first day of the month.
+    let first_week_start = locale.week_start(first_day);
 
     let (next_month, next_month_year) = if month == 12 {
         (1, year + 1)
@@ -404,23 +1132,828 @@ pub fn get_mondays_in_month(year: i32, month: u32) -> Vec<NaiveDate> {
     };
     let first_day_of_next_month = NaiveDate::from_ymd_opt(next_month_year, next_month, 1).unwrap();
 
-    // Collect all Mondays up to the next month.
-    let mut current_monday = first_monday;
-    while current_monday < first_day_of_next_month {
-        mondays.push(current_monday);
-        current_monday += Duration::days(7);
+    // Collect all the week starts up to the next month.
+    let mut week_start = first_week_start;
+    while week_start < first_day_of_next_month {
+        week_starts.push(week_start);
+        week_start += Duration::days(7);
     }
 
-    mondays
+    week_starts
+}
+
+/// Times and checkpoints the tests of all modules are written with
+#[cfg(test)]
+pub mod test_util {
+    use chrono::TimeZone;
+
+    use super::{system_timezone, Time};
+    use crate::app::{Checkpoint, CheckpointKind};
+
+    /// Time on a day of January 2025, the 6th is a Monday
+    pub fn on(day: u32, hour: u32, minute: u32) -> Time {
+        system_timezone()
+            .with_ymd_and_hms(2025, 1, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    /// Time on Monday, 6 January 2025
+    pub fn at(hour: u32, minute: u32) -> Time {
+        on(6, hour, minute)
+    }
+
+    /// Checkpoint of the kind and project at the time
+    pub fn checkpoint(time: Time, kind: CheckpointKind, project: &str) -> Checkpoint {
+        Checkpoint {
+            time,
+            kind,
+            project: Some(project.to_string()),
+            ..Checkpoint::new()
+        }
+    }
+
+    /// [`checkpoint`] with a message
+    pub fn noted(time: Time, kind: CheckpointKind, project: &str, message: &str) -> Checkpoint {
+        Checkpoint {
+            message: Some(message.to_string()),
+            ..checkpoint(time, kind, project)
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::test_util::{at, checkpoint, on};
     use super::*;
 
     #[test]
-    fn test_get_mondays_in_month() {
-        let mondays = get_mondays_in_month(2025, 1);
+    fn test_get_week_starts_in_month() {
+        let mondays = get_week_starts_in_month(2025, 1, &Locale::DEFAULT);
         assert!(!mondays.is_empty());
     }
+
+    #[test]
+    fn test_locale() {
+        let locale: Locale = toml::from_str(
+            "first_day_of_week = \"sunday\"\ndate_format = \"iso\"\nclock = \"12h\"",
+        )
+        .unwrap();
+        let date = NaiveDate::from_ymd_opt(2025, 1, 8).unwrap();
+        let sunday = NaiveDate::from_ymd_opt(2025, 1, 5).unwrap();
+
+        assert_eq!(locale.week_start(date), sunday);
+        assert_eq!(locale.week_start(sunday), sunday);
+        assert_eq!(
+            locale.date_in_week(sunday, Weekday::Sat),
+            sunday + Days::new(6)
+        );
+        assert_eq!(locale.weekdays()[..2], [Weekday::Sun, Weekday::Mon]);
+        // The week of the 1st of January is the first, also when it starts in December
+        assert_eq!(locale.week_number(sunday), 2);
+        assert_eq!(locale.week_number(sunday - Days::new(7)), 1);
+        assert_eq!(locale.week_number(sunday - Days::new(8)), 52);
+        assert_eq!(locale.date(date), "2025-01-08");
+        assert_eq!(locale.day(date), "01-08");
+        assert_eq!(
+            locale.time(NaiveTime::from_hms_opt(17, 5, 0).unwrap()),
+            "5:05pm"
+        );
+
+        let locale = Locale::default();
+        assert_eq!(locale.week_start(sunday), sunday - Days::new(6));
+        assert_eq!(locale.week_number(sunday), 1);
+        assert_eq!(locale.date(date), "08.01.25");
+        assert_eq!(locale.day(date), "08.01");
+        assert_eq!(
+            locale.time(NaiveTime::from_hms_opt(17, 5, 0).unwrap()),
+            "17:05"
+        );
+    }
+
+    #[test]
+    fn test_rounding_strategies() {
+        let time = system_timezone()
+            .with_ymd_and_hms(2025, 1, 6, 9, 7, 30)
+            .unwrap();
+        let round = |unit, strategy| {
+            Rounding {
+                unit,
+                strategy,
+                ..Rounding::DEFAULT
+            }
+            .round(time)
+        };
+
+        assert_eq!(round(15, RoundingStrategy::Nearest), at(9, 0));
+        assert_eq!(round(10, RoundingStrategy::Nearest), at(9, 10));
+        assert_eq!(round(15, RoundingStrategy::Up), at(9, 15));
+        assert_eq!(round(5, RoundingStrategy::Down), at(9, 5));
+        assert_eq!(round(60, RoundingStrategy::Up), at(10, 0));
+        assert_eq!(round(30, RoundingStrategy::None), at(9, 7));
+        assert_eq!(Rounding::DEFAULT.round(at(9, 8)), at(9, 15));
+        let down = Rounding {
+            strategy: RoundingStrategy::Down,
+            ..Rounding::DEFAULT
+        };
+        assert_eq!(down.round(at(23, 59)), at(23, 45));
+    }
+
+    #[test]
+    fn test_rounding_in_the_repeated_hour() {
+        let prague = chrono_tz::Europe::Prague;
+        let repeated = prague.with_ymd_and_hms(2025, 10, 26, 2, 37, 20);
+        let (summer, winter) = (repeated.earliest().unwrap(), repeated.latest().unwrap());
+
+        let rounded = Rounding::DEFAULT.round(summer);
+        assert_eq!(
+            rounded,
+            summer - Duration::minutes(7) - Duration::seconds(20)
+        );
+        assert_eq!(rounded.offset(), summer.offset());
+        assert_eq!(
+            Rounding::DEFAULT.round(winter),
+            winter - Duration::minutes(7) - Duration::seconds(20)
+        );
+    }
+
+    #[test]
+    fn test_reconciled_spans_keep_the_raw_total() {
+        let kinds = [
+            (8, 0, CheckpointKind::Work),
+            (8, 8, CheckpointKind::Break),
+            (8, 16, CheckpointKind::Work),
+            (8, 24, CheckpointKind::Break),
+            (8, 32, CheckpointKind::Work),
+            (8, 40, CheckpointKind::Stop),
+        ];
+        let checkpoints: Vec<Checkpoint> = kinds
+            .into_iter()
+            .map(|(hour, minute, kind)| Checkpoint {
+                time: at(hour, minute),
+                kind,
+                ..Checkpoint::new()
+            })
+            .collect();
+        let work_units = |rounding: Rounding| -> u16 {
+            checkpoints
+                .iter()
+                .zip(rounding.spans(&checkpoints))
+                .filter(|(ch, _)| ch.is_work())
+                .map(|(_, span)| span.units)
+                .sum()
+        };
+
+        // 24 minutes of work, rounding every checkpoint makes 45 of them
+        assert_eq!(work_units(Rounding::DEFAULT), 3);
+        let reconciled = Rounding {
+            reconcile: true,
+            ..Rounding::DEFAULT
+        };
+        assert_eq!(work_units(reconciled), 2);
+    }
+
+    #[test]
+    fn test_largest_remainder() {
+        assert_eq!(largest_remainder(&[8.0, 8.0, 8.0], 15), vec![1, 1, 0]);
+        assert_eq!(largest_remainder(&[20.0, 14.0, 11.0], 15), vec![1, 1, 1]);
+        assert_eq!(largest_remainder(&[22.0, 10.0, 5.0], 15), vec![1, 1, 0]);
+        assert!(largest_remainder(&[], 15).is_empty());
+    }
+
+    #[test]
+    fn test_rounding_unit_must_divide_an_hour() {
+        let parse = |toml: &str| toml::from_str::<Rounding>(toml);
+
+        assert_eq!(parse("unit = 30").unwrap().unit, 30);
+        assert_eq!(parse("").unwrap(), Rounding::DEFAULT);
+        assert!(parse("unit = 7").is_err());
+        assert_eq!(parse(r#"strategy = "none""#).unwrap().unit_minutes(), 1);
+    }
+
+    #[test]
+    fn test_decimal_hours() {
+        assert_eq!(decimal_hours(465), "7.75h");
+        assert_eq!(decimal_hours(480), "8h");
+        assert_eq!(decimal_hours(90), "1.5h");
+        assert_eq!(decimal_hours(5), "0.08h");
+        assert_eq!(decimal_hours(0), "0h");
+    }
+
+    #[test]
+    fn test_balance() {
+        let settings = TimeSettings::default();
+        assert_eq!(settings.balance(90), "+1h30m");
+        assert_eq!(settings.balance(-45), "-45m");
+        assert_eq!(settings.balance(0), "+0m");
+        let decimal = TimeSettings {
+            decimal_hours: true,
+            ..settings
+        };
+        assert_eq!(decimal.balance(-90), "-1.5h");
+    }
+
+    #[test]
+    fn test_parse_hh_mm() {
+        assert_eq!(parse_hh_mm("7:30"), NaiveTime::from_hms_opt(7, 30, 0));
+        assert_eq!(parse_hh_mm(" 09:05 "), NaiveTime::from_hms_opt(9, 5, 0));
+        assert_eq!(parse_hh_mm("24:00"), None);
+        assert_eq!(parse_hh_mm("12:60"), None);
+        assert_eq!(parse_hh_mm("930"), None);
+        assert_eq!(parse_hh_mm("9:5"), None);
+    }
+
+    #[test]
+    fn test_weekend_is_selectable_when_shown() {
+        let mut week = Week {
+            selected_weekday: Weekday::Fri,
+            ..Week::new()
+        };
+        week.select_next_day();
+        assert_eq!(week.selected_weekday, Weekday::Mon);
+        week.select_prev_day();
+        assert_eq!(week.visible_days().len(), 5);
+
+        week.show_weekend = true;
+        week.select_next_day();
+        assert_eq!(week.selected_weekday, Weekday::Sat);
+        week.select_next_day();
+        week.select_next_day();
+        assert_eq!(week.selected_weekday, Weekday::Mon);
+        week.select_prev_day();
+        assert_eq!(week.selected_weekday, Weekday::Sun);
+        assert_eq!(week.visible_days().len(), 7);
+    }
+
+    #[test]
+    fn test_select_max_checkpoint_idx_single_checkpoint() {
+        let mut week = Week::new();
+        week.append_checkpoint(Checkpoint::new());
+        week.selected_checkpoint_idx = 1;
+
+        week.select_max_checkpoint_idx();
+
+        assert_eq!(week.selected_checkpoint_idx, 0);
+    }
+
+    #[test]
+    fn test_last_checkpoint_stays_selectable() {
+        let mut week = Week::new();
+        for _ in 0..3 {
+            week.append_checkpoint(Checkpoint::new());
+        }
+        week.selected_weekday = Weekday::Tue;
+        for _ in 0..4 {
+            week.append_checkpoint(Checkpoint::new());
+        }
+
+        // Right to the last checkpoint of Tuesday, up to the shorter Monday and back
+        for _ in 0..5 {
+            week.select_next_checkpoint();
+        }
+        assert_eq!(week.selected_checkpoint_idx, 3);
+        week.select_prev_day();
+        assert_eq!(week.selected_checkpoint_idx, 2);
+        assert!(week.selected_checkpoint().is_some());
+
+        // Deleting the last checkpoint selects the one before it
+        week.remove_selected_checkpoint();
+        assert_eq!(week.selected_checkpoint_idx, 1);
+        week.remove_selected_checkpoint();
+        week.remove_selected_checkpoint();
+        assert_eq!(week.selected_checkpoint_idx, 0);
+        assert!(week.remove_selected_checkpoint().is_none());
+    }
+
+    #[test]
+    fn test_merge_with_next() {
+        let start = at(8, 0);
+        let mut week = Week::new();
+        for (offset, project) in [(0, "first"), (60, "second"), (120, "end")] {
+            week.append_checkpoint(Checkpoint {
+                time: start + Duration::minutes(offset),
+                project: Some(project.to_string()),
+                activity: Some(format!("{} review", project)),
+                billable: project != "second",
+                registered: true,
+                ..Checkpoint::new()
+            });
+        }
+        week.mon[1].kind = CheckpointKind::Break;
+
+        let boundary = week.merge_with_next(true).unwrap();
+
+        assert_eq!(boundary.project.as_deref(), Some("second"));
+        assert_eq!(week.mon.len(), 2);
+        assert_eq!(week.mon[0].time, start);
+        assert_eq!(week.mon[0].project.as_deref(), Some("second"));
+        assert_eq!(week.mon[0].activity.as_deref(), Some("second review"));
+        assert_eq!(week.mon[0].kind, CheckpointKind::Break);
+        assert!(!week.mon[0].billable);
+        assert!(week.merge_with_next(false).is_none());
+    }
+
+    #[test]
+    fn test_day_stats() {
+        let start = at(8, 0);
+        let checkpoints = vec![
+            Checkpoint {
+                time: start,
+                project: Some("1".to_string()),
+                message: Some("work".to_string()),
+                registered: true,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::minutes(90),
+                project: Some("1".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::minutes(120),
+                ..Checkpoint::new()
+            },
+        ];
+
+        let stats = DayStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(
+            stats,
+            DayStats {
+                tracked_minutes: 120,
+                incomplete_spans: 1,
+                unregistered_spans: 1,
+                span_warnings: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_time_spans_issues() {
+        let start = at(8, 0);
+        let checkpoints: Vec<Checkpoint> = [0, 60, 65, 30]
+            .into_iter()
+            .map(|offset| Checkpoint {
+                time: start + Duration::minutes(offset),
+                ..Checkpoint::new()
+            })
+            .collect();
+
+        let issues: Vec<_> = Rounding::DEFAULT
+            .spans(&checkpoints)
+            .iter()
+            .map(|span| span.issue)
+            .collect();
+
+        assert_eq!(
+            issues,
+            vec![None, Some(SpanIssue::Empty), Some(SpanIssue::Overlap)]
+        );
+        assert_eq!(
+            DayStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT).span_warnings,
+            2
+        );
+    }
+
+    fn checkpoints_at(offsets: &[i64]) -> Vec<Checkpoint> {
+        let start = at(8, 0);
+        offsets
+            .iter()
+            .map(|offset| Checkpoint {
+                time: start + Duration::minutes(*offset),
+                message: Some(offset.to_string()),
+                ..Checkpoint::new()
+            })
+            .collect()
+    }
+
+    fn offsets(checkpoints: &[Checkpoint]) -> Vec<i64> {
+        let start = checkpoints[0].time;
+        checkpoints
+            .iter()
+            .map(|ch| (ch.time - start).num_minutes())
+            .collect()
+    }
+
+    #[test]
+    fn test_fix_overlaps_swap() {
+        let mut checkpoints = checkpoints_at(&[0, 180, 60, 240]);
+        assert_eq!(count_overlaps(&checkpoints), 1);
+
+        let changed = fix_overlaps(&mut checkpoints, OverlapFix::Swap);
+
+        assert_eq!(changed, vec![1, 2]);
+        assert_eq!(offsets(&checkpoints), vec![0, 60, 180, 240]);
+        // The messages stay with their position in the day
+        assert_eq!(checkpoints[1].message.as_deref(), Some("180"));
+        assert_eq!(count_overlaps(&checkpoints), 0);
+    }
+
+    #[test]
+    fn test_fix_overlaps_swap_cascades() {
+        let mut checkpoints = checkpoints_at(&[60, 120, 0]);
+
+        let changed = fix_overlaps(&mut checkpoints, OverlapFix::Swap);
+
+        assert_eq!(changed, vec![0, 1, 2]);
+        assert_eq!(count_overlaps(&checkpoints), 0);
+    }
+
+    #[test]
+    fn test_fix_overlaps_clamp() {
+        let mut checkpoints = checkpoints_at(&[0, 180, 60, 120, 240]);
+
+        let changed = fix_overlaps(&mut checkpoints, OverlapFix::Clamp);
+
+        assert_eq!(changed, vec![2, 3]);
+        assert_eq!(offsets(&checkpoints), vec![0, 180, 180, 180, 240]);
+        assert_eq!(count_overlaps(&checkpoints), 0);
+    }
+
+    #[test]
+    fn test_lunch_break_at_fixed_time() {
+        let rule: LunchBreak = toml::from_str("minutes = 30\nat = \"12:00\"").unwrap();
+        let morning = vec![Checkpoint {
+            time: at(8, 0),
+            ..Checkpoint::new()
+        }];
+
+        assert_eq!(
+            rule.place(&morning, at(13, 0)),
+            Some((at(12, 0), at(12, 30)))
+        );
+        // Not before the break is over
+        assert_eq!(rule.place(&morning, at(12, 15)), None);
+
+        let mut had_break = morning.clone();
+        had_break.push(Checkpoint {
+            time: at(11, 0),
+            kind: CheckpointKind::Break,
+            ..Checkpoint::new()
+        });
+        had_break.push(Checkpoint {
+            time: at(11, 30),
+            ..Checkpoint::new()
+        });
+        assert_eq!(rule.place(&had_break, at(13, 0)), None);
+    }
+
+    #[test]
+    fn test_lunch_break_after_continuous_work() {
+        let rule = LunchBreak {
+            minutes: 30,
+            at: None,
+            after_hours: Some(6.0),
+        };
+        let day = vec![
+            Checkpoint {
+                time: at(7, 0),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(8, 0),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(8, 30),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: at(15, 0),
+                ..Checkpoint::new()
+            },
+        ];
+
+        // Counted from 8:30, but the break can't start before the last checkpoint
+        assert_eq!(rule.place(&day, at(16, 0)), Some((at(15, 0), at(15, 30))));
+        assert_eq!(
+            rule.place(&day[..3], at(16, 0)),
+            Some((at(14, 30), at(15, 0)))
+        );
+        assert_eq!(rule.place(&day[..3], at(14, 45)), None);
+    }
+
+    #[test]
+    fn test_merge_near_duplicates() {
+        let mut day = checkpoints_at(&[0, 3, 5, 60, 62]);
+        day[2].project = Some("ABC-1".to_string());
+        day[4].kind = CheckpointKind::Stop;
+        for checkpoint in &mut day[..3] {
+            checkpoint.message = None;
+        }
+
+        assert!(is_near_duplicate(&day[0], &day[1], &Rounding::DEFAULT));
+        // A stop right after a start is on purpose
+        assert!(!is_near_duplicate(&day[3], &day[4], &Rounding::DEFAULT));
+
+        let (changed, removed) = merge_near_duplicates(&mut day, &Rounding::DEFAULT);
+
+        assert_eq!(offsets(&day), vec![0, 60, 62]);
+        assert_eq!(day[0].project.as_deref(), Some("ABC-1"));
+        assert_eq!(changed.len(), 1);
+        assert_eq!(removed.len(), 2);
+    }
+
+    #[test]
+    fn test_near_duplicates_with_different_projects_are_kept() {
+        let mut day = checkpoints_at(&[0, 3]);
+        day[0].project = Some("ABC-1".to_string());
+        day[1].project = Some("ABC-2".to_string());
+        day[1].message = day[0].message.clone();
+
+        assert!(!is_near_duplicate(&day[0], &day[1], &Rounding::DEFAULT));
+        assert!(merge_near_duplicates(&mut day, &Rounding::DEFAULT)
+            .1
+            .is_empty());
+    }
+
+    #[test]
+    fn test_breaks_are_not_counted() {
+        let start = at(8, 0);
+        let checkpoints = vec![
+            Checkpoint {
+                time: start,
+                project: Some("a".to_string()),
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::minutes(60),
+                kind: CheckpointKind::Break,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::minutes(90),
+                ..Checkpoint::new()
+            },
+        ];
+
+        assert_eq!(
+            project_units(&checkpoints, &Rounding::DEFAULT).get("a"),
+            Some(&4)
+        );
+        assert_eq!(project_units(&checkpoints, &Rounding::DEFAULT).len(), 1);
+        assert_eq!(
+            DayStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT).tracked_minutes,
+            60
+        );
+        assert_eq!(
+            DayStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT).unregistered_spans,
+            1
+        );
+    }
+
+    #[test]
+    fn test_project_units() {
+        let start = at(8, 0);
+        let checkpoints: Vec<Checkpoint> = [
+            (0, Some("a")),
+            (60, Some("b")),
+            (90, Some("a")),
+            (120, None),
+        ]
+        .into_iter()
+        .map(|(offset, project)| Checkpoint {
+            time: start + Duration::minutes(offset),
+            project: project.map(str::to_string),
+            ..Checkpoint::new()
+        })
+        .collect();
+
+        let totals = project_units(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(totals.get("a"), Some(&6));
+        assert_eq!(totals.get("b"), Some(&2));
+        assert_eq!(totals.get("-"), None);
+        assert_eq!(
+            dominant_project(&checkpoints, &Rounding::DEFAULT),
+            Some("a".to_string())
+        );
+        assert_eq!(
+            dominant_project(&checkpoints[3..], &Rounding::DEFAULT),
+            None
+        );
+    }
+
+    #[test]
+    fn test_activity_units() {
+        let start = at(8, 0);
+        let checkpoints: Vec<Checkpoint> = [
+            (0, Some("review")),
+            (60, None),
+            (90, Some("review")),
+            (120, None),
+        ]
+        .into_iter()
+        .map(|(offset, activity)| Checkpoint {
+            time: start + Duration::minutes(offset),
+            project: Some("a".to_string()),
+            activity: activity.map(str::to_string),
+            ..Checkpoint::new()
+        })
+        .collect();
+
+        let totals = activity_units(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(totals.get("a/review"), Some(&6));
+        assert_eq!(totals.get("a"), Some(&2));
+        assert_eq!(
+            project_units(&checkpoints, &Rounding::DEFAULT).get("a"),
+            Some(&8)
+        );
+    }
+
+    #[test]
+    fn test_billable_units() {
+        let start = at(8, 0);
+        let checkpoints: Vec<Checkpoint> = [
+            (0, true, CheckpointKind::Work),
+            (60, false, CheckpointKind::Work),
+            (90, false, CheckpointKind::Break),
+            (120, true, CheckpointKind::Work),
+        ]
+        .into_iter()
+        .map(|(offset, billable, kind)| Checkpoint {
+            time: start + Duration::minutes(offset),
+            billable,
+            kind,
+            ..Checkpoint::new()
+        })
+        .collect();
+
+        assert_eq!(billable_units(&checkpoints, &Rounding::DEFAULT), (4, 2));
+    }
+
+    #[test]
+    fn test_unregistered_spans() {
+        let start = at(8, 0);
+        let checkpoints = vec![
+            Checkpoint {
+                time: start,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::hours(1),
+                registered: true,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::hours(2),
+                ..Checkpoint::new()
+            },
+            // the last checkpoint of a day does not start a span
+            Checkpoint {
+                time: start + Duration::days(1),
+                ..Checkpoint::new()
+            },
+        ];
+
+        let spans = unregistered_spans(&checkpoints, &Rounding::DEFAULT);
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].0.time, start);
+        assert_eq!(spans[0].1, 60);
+    }
+
+    #[test]
+    fn test_running_span() {
+        let start = at(8, 0);
+        let checkpoints = vec![Checkpoint {
+            time: start,
+            ..Checkpoint::new()
+        }];
+
+        let (_, minutes) = running_span(&checkpoints, start + Duration::minutes(83)).unwrap();
+        assert_eq!(minutes, 83);
+        assert!(running_span(&checkpoints, start - Duration::minutes(1)).is_none());
+        assert!(running_span(&checkpoints, start + Duration::days(1)).is_none());
+        assert!(running_span(&[], start).is_none());
+    }
+
+    #[test]
+    fn test_running_span_stopped() {
+        let start = at(8, 0);
+        let checkpoints = vec![
+            Checkpoint {
+                time: start,
+                ..Checkpoint::new()
+            },
+            Checkpoint {
+                time: start + Duration::hours(1),
+                kind: CheckpointKind::Stop,
+                ..Checkpoint::new()
+            },
+        ];
+
+        assert!(running_span(&checkpoints, start + Duration::hours(2)).is_none());
+        assert_eq!(
+            DayStats::from_checkpoints(&checkpoints, &Rounding::DEFAULT).tracked_minutes,
+            60
+        );
+    }
+
+    #[test]
+    fn test_copy_to_date() {
+        let start = at(8, 30);
+        let checkpoints = vec![Checkpoint {
+            id: Some("abc".to_string()),
+            time: start,
+            project: Some("a".to_string()),
+            message: Some("standup".to_string()),
+            registered: true,
+            ..Checkpoint::new()
+        }];
+        let date = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+
+        let copies = copy_to_date(&checkpoints, date);
+
+        assert_eq!(copies.len(), 1);
+        assert_eq!(copies[0].id, None);
+        assert_eq!(copies[0].time, start + Duration::days(1));
+        assert_eq!(copies[0].message.as_deref(), Some("standup"));
+        assert!(!copies[0].registered);
+    }
+
+    #[test]
+    fn test_overnight_splits() {
+        let stored = |time: Time, kind| Checkpoint {
+            id: Some(time.to_string()),
+            ..checkpoint(time, kind, "a")
+        };
+        let checkpoints = vec![
+            stored(on(6, 17, 0), CheckpointKind::Stop),
+            stored(on(6, 22, 0), CheckpointKind::Work),
+            stored(on(7, 1, 30), CheckpointKind::Stop),
+            stored(on(7, 9, 0), CheckpointKind::Work),
+            stored(on(8, 8, 0), CheckpointKind::Work),
+        ];
+
+        let splits = overnight_splits(&checkpoints);
+
+        assert_eq!(splits.len(), 2);
+        assert_eq!(splits[0].time, on(6, 23, 59) + Duration::seconds(59));
+        assert_eq!(splits[0].kind, CheckpointKind::Stop);
+        assert_eq!(splits[1].time, on(7, 0, 0));
+        assert_eq!(splits[1].project.as_deref(), Some("a"));
+        assert_eq!(splits[1].id, None);
+
+        let mut day = vec![checkpoints[1].clone(), splits[0].clone()];
+        day.extend(overnight_splits(&day));
+        assert_eq!(day.len(), 2);
+        assert_eq!(Rounding::DEFAULT.spans(&day)[0].minutes(), 120);
+        let down = Rounding {
+            strategy: RoundingStrategy::Down,
+            ..Rounding::DEFAULT
+        };
+        assert_eq!(down.spans(&day)[0].minutes(), 120);
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let expected = NaiveDate::from_ymd_opt(2025, 1, 15);
+        assert_eq!(parse_date("2025-01-15"), expected);
+        assert_eq!(parse_date("15.01.2025"), expected);
+        assert_eq!(parse_date("15.1.2025"), expected);
+        assert_eq!(parse_date("2025-02-30"), None);
+    }
+
+    #[test]
+    fn test_parse_date_time() {
+        let date = NaiveDate::from_ymd_opt(2025, 1, 15).unwrap();
+        assert_eq!(parse_date_time(" 15.01.2025 "), Some((date, None)));
+        assert_eq!(
+            parse_date_time("2025-01-15 23:45"),
+            Some((date, NaiveTime::from_hms_opt(23, 45, 0)))
+        );
+        assert_eq!(parse_date_time("2025-01-15 25:00"), None);
+        assert_eq!(parse_date_time("2025-01-15 08:00 x"), None);
+        assert_eq!(parse_date_time(""), None);
+    }
+
+    #[test]
+    fn test_insert_checkpoint_keeps_order() {
+        let start = at(8, 0);
+        let mut week = Week::new();
+        for offset in [0, 120] {
+            week.append_checkpoint(Checkpoint {
+                time: start + Duration::minutes(offset),
+                ..Checkpoint::new()
+            });
+        }
+
+        week.insert_checkpoint(Checkpoint {
+            time: start + Duration::minutes(60),
+            ..Checkpoint::new()
+        });
+
+        assert_eq!(week.selected_checkpoint_idx, 1);
+        let times: Vec<_> = week.mon.iter().map(|ch| ch.time).collect();
+        assert_eq!(
+            times,
+            vec![
+                start,
+                start + Duration::minutes(60),
+                start + Duration::minutes(120)
+            ]
+        );
+    }
 }
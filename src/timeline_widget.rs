@@ -1,95 +1,404 @@
-use crate::{app::Checkpoint, time::time_spans};
+use std::ops::{Range, RangeInclusive};
+
+use crate::{
+    app::{Checkpoint, DayType},
+    theme::Theme,
+    time::{Locale, Rounding, TimeSettings, TimeSpan},
+};
+use chrono::{NaiveDate, Timelike};
 use ratatui::{
-    layout::{Constraint, Layout},
+    layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
     text::Line,
     widgets::{Block, Paragraph, Widget},
 };
 
-const FIFTEEN_LEN: u16 = 4;
+/// Minutes the zoom levels and braille cells are measured in, independent of the rounding unit
+const QUARTER: u32 = 15;
 
-pub struct Timeline<'a> {
-    pub checkpoints: &'a Vec<Checkpoint>,
+/// Horizontal scale of the timeline: `cells` terminal cells represent `units` quarter hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Zoom {
+    pub cells: u16,
+    pub units: u16,
+}
+
+impl Zoom {
+    /// Zoom levels from the most compressed to the most detailed
+    pub const LEVELS: [Zoom; 5] = [
+        Zoom { cells: 1, units: 2 },
+        Zoom { cells: 1, units: 1 },
+        Zoom { cells: 2, units: 1 },
+        Zoom { cells: 4, units: 1 },
+        Zoom { cells: 6, units: 1 },
+    ];
+    pub const DEFAULT_LEVEL: usize = 3;
+
+    /// Number of cells used to draw a span of the given minutes, never collapsing it to nothing
+    pub fn cells_for(&self, minutes: u32) -> u16 {
+        (minutes * self.cells as u32).div_ceil(self.units as u32 * QUARTER) as u16
+    }
+}
+
+impl Default for Zoom {
+    fn default() -> Self {
+        Self::LEVELS[Self::DEFAULT_LEVEL]
+    }
+}
+
+/// Returns the range of spans that fit into `available` columns while keeping `focus` visible.
+fn visible_spans(widths: &[u16], available: u16, focus: usize) -> Range<usize> {
+    let focus = focus.min(widths.len().saturating_sub(1));
+
+    // Walk back from the focused span as long as the spans still fit
+    let mut first = focus;
+    let mut used = widths.get(focus).copied().unwrap_or(0);
+    while first > 0 && used + widths[first - 1] <= available {
+        first -= 1;
+        used += widths[first];
+    }
+
+    // Fill the remaining space with the following spans
+    let mut end = (focus + 1).min(widths.len());
+    while end < widths.len() && used + widths[end] <= available {
+        used += widths[end];
+        end += 1;
+    }
+
+    first..end
+}
+
+/// Where the spans of a day end up within the timeline's main area
+struct SpanLayout {
+    spans: Vec<TimeSpan>,
+    widths: Vec<u16>,
+    visible: Range<usize>,
+    /// Whether some spans are scrolled out of view
+    scrolled: bool,
+    /// Column of the first visible span
+    x: u16,
+}
+
+/// Splits the area into the weekday prelude and the main area holding the spans
+fn split_timeline_area(area: Rect) -> [Rect; 2] {
+    Layout::horizontal(vec![Constraint::Length(5), Constraint::Fill(1)])
+        .spacing(1)
+        .areas(area)
+}
+
+fn span_layout(
+    checkpoints: &[Checkpoint],
+    selected_checkpoint_idx: Option<usize>,
+    zoom: Zoom,
+    rounding: &Rounding,
+    main_area: Rect,
+) -> SpanLayout {
+    let spans = rounding.spans(checkpoints);
+
+    let widths = spans
+        .iter()
+        .map(|s| zoom.cells_for(s.minutes()) + 2) // border
+        .collect::<Vec<u16>>();
+
+    // Scroll horizontally when the day does not fit, reserving a column for each indicator
+    let visible = if widths.iter().sum::<u16>() <= main_area.width {
+        0..spans.len()
+    } else {
+        let focus = selected_checkpoint_idx.unwrap_or(0);
+        visible_spans(&widths, main_area.width.saturating_sub(2), focus)
+    };
+    let scrolled = visible.len() < spans.len();
+    let x = if scrolled {
+        main_area.x + 1
+    } else {
+        main_area.x
+    };
+
+    SpanLayout {
+        spans,
+        widths,
+        visible,
+        scrolled,
+        x,
+    }
+}
+
+/// Columns at which full hours fall within the visible spans, with the hour
+fn hour_marks(
+    checkpoints: &[Checkpoint],
+    layout: &SpanLayout,
+    zoom: Zoom,
+    rounding: &Rounding,
+) -> Vec<(u16, u32)> {
+    let mut marks = vec![];
+    let mut x = layout.x;
+    for i in layout.visible.clone() {
+        let start = checkpoints[i].rounded_time(rounding);
+        let end = checkpoints[i + 1].rounded_time(rounding);
+        let span_minutes = (end - start).num_minutes();
+        let start_minute = (start.hour() * 60 + start.minute()) as i64;
+
+        let mut hour = start.hour() + u32::from(start.minute() > 0);
+        while hour < 24 {
+            let elapsed = hour as i64 * 60 - start_minute;
+            if elapsed >= span_minutes {
+                break;
+            }
+            let column = if elapsed == 0 {
+                x
+            } else {
+                x + 1 + (elapsed as u32 * zoom.cells as u32 / (zoom.units as u32 * QUARTER)) as u16
+            };
+            marks.push((column, hour));
+            hour += 1;
+        }
+        x += layout.widths[i];
+    }
+    marks
+}
+
+/// Row of hour labels aligned with the [`Timeline`] of the same checkpoints.
+pub struct HourRuler<'a> {
+    pub checkpoints: &'a [Checkpoint],
     pub selected_checkpoint_idx: Option<usize>,
+    pub theme: Theme,
+    pub zoom: Zoom,
+    pub settings: TimeSettings,
 }
 
-impl<'a> Widget for Timeline<'a> {
+impl<'a> Widget for HourRuler<'a> {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
     where
         Self: Sized,
     {
-        let [pre_area, main_area] =
-            Layout::horizontal(vec![Constraint::Length(5), Constraint::Fill(1)])
-                .spacing(1)
-                .areas(area);
-
-        let mut prelude_p = Paragraph::default();
-
-        if let Some(ch) = self.checkpoints.first() {
-            prelude_p = Paragraph::new(vec![
-                Line::from(ch.time.format("%a").to_string()),
-                Line::from(ch.time.format("%d.").to_string()),
-            ])
-            .centered();
+        let [_, main_area] = split_timeline_area(area);
+        let layout = span_layout(
+            self.checkpoints,
+            self.selected_checkpoint_idx,
+            self.zoom,
+            &self.settings.rounding,
+            main_area,
+        );
+        let style = Style::new().fg(self.theme.help);
 
-            if self.selected_checkpoint_idx.is_some() {
-                prelude_p = prelude_p.bg(Color::DarkGray).fg(Color::Black).bold();
+        let mut free_from = main_area.x;
+        let marks = hour_marks(
+            self.checkpoints,
+            &layout,
+            self.zoom,
+            &self.settings.rounding,
+        );
+        for (column, hour) in marks {
+            let label = format!("{:02}", hour);
+            if column < free_from || column + label.len() as u16 > main_area.right() {
+                continue;
             }
+            buf.set_string(column, main_area.y, &label, style);
+            free_from = column + label.len() as u16 + 1;
         }
-        prelude_p.render(pre_area, buf);
+    }
+}
 
-        let spans = time_spans(self.checkpoints);
+/// Weekday and date of the day, with a mark of locked days and of work logged on a public
+/// holiday
+fn day_lines(
+    date: NaiveDate,
+    locked: bool,
+    holiday: bool,
+    theme: Theme,
+    locale: &Locale,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from(date.format("%a").to_string()),
+        Line::from(locale.day(date)),
+    ];
+    if locked {
+        lines.push(Line::from("lock").fg(theme.help));
+    } else if holiday {
+        lines.push(Line::from("hol").fg(theme.bad));
+    }
+    lines
+}
 
-        let timeline_constraint = spans
-            .iter()
-            .map(|s| Constraint::Length(s.units * FIFTEEN_LEN + 2)) // border
-            .collect::<Vec<Constraint>>();
+/// The [lines of the day](day_lines) in front of its timeline, nothing for a day without any
+fn render_prelude(
+    lines: Option<Vec<Line>>,
+    selected: bool,
+    theme: Theme,
+    area: Rect,
+    buf: &mut ratatui::prelude::Buffer,
+) {
+    let mut prelude_p = Paragraph::default();
 
-        let areas = Layout::horizontal(timeline_constraint).split(main_area);
+    if let Some(lines) = lines {
+        prelude_p = Paragraph::new(lines).centered();
 
-        for (i, span) in spans.iter().enumerate() {
+        if selected {
+            prelude_p = prelude_p.bg(theme.selection).fg(Color::Black).bold();
+        }
+    }
+    prelude_p.render(area, buf);
+}
+
+pub struct Timeline<'a> {
+    pub checkpoints: &'a Vec<Checkpoint>,
+    /// The day is locked against changes
+    pub locked: bool,
+    /// The day is a public holiday, so its spans are work logged by mistake
+    pub holiday: bool,
+    pub selected_checkpoint_idx: Option<usize>,
+    /// Checkpoints covered by the visual selection
+    pub selected_range: Option<RangeInclusive<usize>>,
+    pub theme: Theme,
+    pub zoom: Zoom,
+    /// Label the spans with the rounded times rather than the stored ones
+    pub rounded_times: bool,
+    /// Spans of checkpoints without this tag are dimmed
+    pub tag_filter: Option<&'a str>,
+    /// Spans of other projects are dimmed
+    pub project_filter: Option<&'a str>,
+    pub settings: TimeSettings,
+}
+
+/// Whether the span of the checkpoint lacks the tag or is of another project
+fn filtered_out(ch: &Checkpoint, tag: Option<&str>, project: Option<&str>) -> bool {
+    let other_tag = tag.is_some_and(|tag| !ch.tags.iter().any(|t| t == tag));
+    let other_project = project.is_some_and(|project| {
+        !ch.project
+            .as_deref()
+            .is_some_and(|p| p.eq_ignore_ascii_case(project))
+    });
+    other_tag || other_project
+}
+
+impl<'a> Widget for Timeline<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let [pre_area, main_area] = split_timeline_area(area);
+        let lines = self.checkpoints.first().map(|ch| {
+            let date = ch.time.date_naive();
+            day_lines(
+                date,
+                self.locked,
+                self.holiday,
+                self.theme,
+                &self.settings.locale,
+            )
+        });
+        render_prelude(
+            lines,
+            self.selected_checkpoint_idx.is_some(),
+            self.theme,
+            pre_area,
+            buf,
+        );
+
+        let SpanLayout {
+            spans,
+            widths,
+            visible,
+            scrolled,
+            mut x,
+        } = span_layout(
+            self.checkpoints,
+            self.selected_checkpoint_idx,
+            self.zoom,
+            &self.settings.rounding,
+            main_area,
+        );
+        let indicator_style = Style::new().fg(self.theme.accent).bold();
+        if scrolled {
+            if visible.start > 0 {
+                buf.set_string(
+                    main_area.x,
+                    main_area.y + 1,
+                    self.theme.symbols.scroll_left,
+                    indicator_style,
+                );
+            }
+            if visible.end < spans.len() {
+                buf.set_string(
+                    main_area.right() - 1,
+                    main_area.y + 1,
+                    self.theme.symbols.scroll_right,
+                    indicator_style,
+                );
+            }
+        }
+
+        for i in visible {
+            let span = &spans[i];
+            let span_area = Rect::new(x, main_area.y, widths[i], main_area.height);
+            x += widths[i];
             let current_ch = &self.checkpoints[i];
 
-            let title_top = Line::from(span.human_time()).centered();
-            let mut title_bottom = Line::from(current_ch.time.format("%H:%M").to_string());
-            let timeline_style = Style::new().fg(current_ch.color());
+            let (duration, time) = if self.rounded_times {
+                let time = current_ch.rounded_time(&self.settings.rounding);
+                (self.settings.duration(span.minutes()), time)
+            } else {
+                let next_time = self.checkpoints[i + 1].time;
+                let minutes = (next_time - current_ch.time).num_minutes().max(0);
+                (self.settings.duration(minutes as u32), current_ch.time)
+            };
+            // The activity follows the duration as far as the span is wide enough
+            let title_top = match &current_ch.activity {
+                Some(activity) => Line::from(format!("{} {}", duration, activity)),
+                None => Line::from(duration),
+            }
+            .centered();
+            let mut title_bottom = Line::from(self.settings.locale.time(time.time()));
+            let mut timeline_style = Style::new().fg(current_ch.color(&self.theme));
+            let filtered_out = filtered_out(current_ch, self.tag_filter, self.project_filter);
+            if !current_ch.is_work() || filtered_out {
+                timeline_style = timeline_style.dim();
+            }
 
-            let mut fill_char = "─";
+            let symbols = self.theme.symbols;
+            let mut fill_char = if current_ch.billable {
+                symbols.span_fill
+            } else {
+                symbols.non_billable_fill
+            };
 
             if current_ch.project.is_none() {
                 if current_ch.message.as_deref().unwrap_or("").is_empty() {
                     fill_char = " ";
                 } else {
-                    fill_char = "╶";
+                    fill_char = symbols.message_fill;
                 }
             }
 
-            let text = fill_char
-                .repeat(FIFTEEN_LEN.into())
-                .repeat(span.units as usize);
+            let text = fill_char.repeat(self.zoom.cells_for(span.minutes()).into());
 
             if !current_ch.registered {
-                title_bottom = title_bottom.bg(Color::Red).fg(Color::White);
+                title_bottom = title_bottom.bg(self.theme.unregistered).fg(Color::White);
             }
 
-            let text_span = ratatui::text::Span::from(text);
-            let mut left_marker = if i == 0 {
-                ratatui::text::Span::from("├")
+            let mut text_span = ratatui::text::Span::from(text);
+            if self.selected_range.as_ref().is_some_and(|r| r.contains(&i)) {
+                text_span = text_span.bg(self.theme.selection);
+            }
+            let mut left_marker = if span.issue.is_some() {
+                ratatui::text::Span::from("!").fg(self.theme.bad).bold()
+            } else if i == 0 {
+                ratatui::text::Span::from(symbols.span_start)
             } else {
-                ratatui::text::Span::from("┼")
+                ratatui::text::Span::from(symbols.span_join)
             };
             let mut right_marker = if i + 1 == spans.len() {
-                ratatui::text::Span::from("┤")
+                ratatui::text::Span::from(symbols.span_end)
             } else {
                 ratatui::text::Span::from(fill_char)
             };
 
             if let Some(j) = self.selected_checkpoint_idx {
                 if i == j {
-                    left_marker = left_marker.bg(Color::DarkGray);
+                    left_marker = left_marker.bg(self.theme.selection);
                 }
                 if i + 1 == j && i + 1 == spans.len() {
-                    right_marker = right_marker.bg(Color::DarkGray);
+                    right_marker = right_marker.bg(self.theme.selection);
                 }
             }
 
@@ -97,7 +406,197 @@ impl<'a> Widget for Timeline<'a> {
                 .style(timeline_style)
                 .block(Block::new().title(title_top).title_bottom(title_bottom))
                 .centered();
-            p.render(areas[i], buf);
+            p.render(span_area, buf);
+        }
+    }
+}
+
+/// Vacation, sick day or holiday drawn as a bar across the whole timeline
+pub struct DayTypeBar {
+    pub date: NaiveDate,
+    pub day_type: DayType,
+    /// Shown instead of the label of the type, e.g. the name of the public holiday
+    pub name: Option<String>,
+    pub selected: bool,
+    pub theme: Theme,
+    pub locale: Locale,
+}
+
+impl Widget for DayTypeBar {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let [pre_area, main_area] = split_timeline_area(area);
+        let lines = day_lines(self.date, false, false, self.theme, &self.locale);
+        render_prelude(Some(lines), self.selected, self.theme, pre_area, buf);
+
+        let bar_area =
+            Rect::new(main_area.x, main_area.y + 1, main_area.width, 1).intersection(main_area);
+        let label = self
+            .name
+            .unwrap_or_else(|| self.day_type.label().to_string());
+        Paragraph::new(label)
+            .centered()
+            .style(
+                Style::new()
+                    .bg(self.day_type.color(&self.theme))
+                    .fg(Color::Black),
+            )
+            .render(bar_area, buf);
+    }
+}
+
+/// How much of a braille cell's dot column a quarter hour fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dots {
+    None,
+    /// Only the bottom dot, for spans with a message but no project
+    Low,
+    Full,
+}
+
+/// Braille character drawing two quarter hours side by side
+fn braille_char(left: Dots, right: Dots) -> char {
+    let bits = |dots, full, low| match dots {
+        Dots::None => 0,
+        Dots::Low => low,
+        Dots::Full => full,
+    };
+    // Dots 1, 2, 3 and 7 form the left column, dots 4, 5, 6 and 8 the right one
+    let code = 0x2800 + bits(left, 0x47, 0x40) + bits(right, 0xb8, 0x80);
+    char::from_u32(code).unwrap_or(' ')
+}
+
+/// Index of the span each quarter hour of the day starts in
+fn quarter_spans(spans: &[TimeSpan]) -> Vec<usize> {
+    let ends: Vec<u32> = spans
+        .iter()
+        .scan(0, |end, span| {
+            *end += span.minutes();
+            Some(*end)
+        })
+        .collect();
+    let total = ends.last().copied().unwrap_or(0);
+    (0..total.div_ceil(QUARTER))
+        .filter_map(|quarter| ends.iter().position(|&end| quarter * QUARTER < end))
+        .collect()
+}
+
+/// Day drawn with braille characters, each cell holding two quarter hours, so long days
+/// fit into narrow terminals. Hours are labelled above the spans and unregistered spans are
+/// underlined.
+pub struct BrailleTimeline<'a> {
+    pub checkpoints: &'a Vec<Checkpoint>,
+    /// The day is locked against changes
+    pub locked: bool,
+    /// The day is a public holiday, so its spans are work logged by mistake
+    pub holiday: bool,
+    pub selected_checkpoint_idx: Option<usize>,
+    /// Checkpoints covered by the visual selection
+    pub selected_range: Option<RangeInclusive<usize>>,
+    pub theme: Theme,
+    /// Spans of checkpoints without this tag are dimmed
+    pub tag_filter: Option<&'a str>,
+    /// Spans of other projects are dimmed
+    pub project_filter: Option<&'a str>,
+    pub settings: TimeSettings,
+}
+
+impl<'a> Widget for BrailleTimeline<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let [pre_area, main_area] = split_timeline_area(area);
+        let lines = self.checkpoints.first().map(|ch| {
+            let date = ch.time.date_naive();
+            day_lines(
+                date,
+                self.locked,
+                self.holiday,
+                self.theme,
+                &self.settings.locale,
+            )
+        });
+        render_prelude(
+            lines,
+            self.selected_checkpoint_idx.is_some(),
+            self.theme,
+            pre_area,
+            buf,
+        );
+        let Some(first) = self.checkpoints.first() else {
+            return;
+        };
+        // The hours, the dots and the marks of the unregistered spans take a row each
+        let rows = main_area.height;
+        if rows < 2 {
+            return;
+        }
+
+        let rounding = &self.settings.rounding;
+        let units = quarter_spans(&rounding.spans(self.checkpoints));
+        let cells = units.len().div_ceil(2);
+        // Scroll so that the start of the selected span stays in view
+        let width = main_area.width as usize;
+        let focus = self
+            .selected_checkpoint_idx
+            .and_then(|idx| units.iter().position(|&i| i == idx))
+            .map_or(0, |unit| unit / 2);
+        let offset = (focus + 1).saturating_sub(width);
+
+        let start = first.rounded_time(rounding);
+        let label_style = Style::new().fg(self.theme.help);
+        let mut free_from = 0;
+        for cell in offset..cells.min(offset + width) {
+            let x = main_area.x + (cell - offset) as u16;
+            let cell_units: Vec<usize> = units.iter().skip(cell * 2).take(2).copied().collect();
+            let dots: Vec<Dots> = cell_units
+                .iter()
+                .map(|&i| {
+                    let ch = &self.checkpoints[i];
+                    if ch.project.is_some() {
+                        Dots::Full
+                    } else if ch.message.as_deref().unwrap_or("").is_empty() {
+                        Dots::None
+                    } else {
+                        Dots::Low
+                    }
+                })
+                .collect();
+            let symbol = braille_char(dots[0], dots.get(1).copied().unwrap_or(Dots::None));
+
+            let ch = &self.checkpoints[cell_units[0]];
+            let mut style = Style::new().fg(ch.color(&self.theme));
+            let filtered_out = filtered_out(ch, self.tag_filter, self.project_filter);
+            if !ch.is_work() || filtered_out {
+                style = style.dim();
+            }
+            let selected = cell_units.iter().any(|i| {
+                Some(*i) == self.selected_checkpoint_idx
+                    || self.selected_range.as_ref().is_some_and(|r| r.contains(i))
+            });
+            if selected {
+                style = style.bg(self.theme.selection);
+            }
+            buf[(x, main_area.y + 1)].set_char(symbol).set_style(style);
+
+            if rows > 2 && cell_units.iter().any(|&i| !self.checkpoints[i].registered) {
+                buf[(x, main_area.y + 2)]
+                    .set_symbol("▔")
+                    .set_style(Style::new().fg(self.theme.unregistered));
+            }
+
+            // Label full hours falling on the left unit of the cell
+            let time = start + chrono::Duration::minutes((cell * 2) as i64 * QUARTER as i64);
+            if time.minute() == 0 && cell >= free_from {
+                let label = format!("{:02}", time.hour());
+                if x + label.len() as u16 <= main_area.right() {
+                    buf.set_string(x, main_area.y, &label, label_style);
+                    free_from = cell + label.len() + 1;
+                }
+            }
         }
     }
 }
@@ -105,15 +604,199 @@ impl<'a> Widget for Timeline<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Local};
-    use ratatui::{backend::TestBackend, Terminal};
+    use crate::time::test_util::at;
+    use chrono::Duration;
+    use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+    #[test]
+    fn test_visible_spans_keeps_focus_in_view() {
+        let widths = [10, 10, 10, 10, 10];
+
+        assert_eq!(visible_spans(&widths, 50, 0), 0..5);
+        assert_eq!(visible_spans(&widths, 25, 0), 0..2);
+        assert_eq!(visible_spans(&widths, 25, 3), 2..4);
+        assert_eq!(visible_spans(&widths, 25, 4), 3..5);
+        assert_eq!(visible_spans(&widths, 5, 2), 2..3);
+    }
+
+    #[test]
+    fn test_hour_marks() {
+        let start = at(8, 0);
+        let checkpoints: Vec<Checkpoint> = [0, 90, 120]
+            .into_iter()
+            .map(|offset| Checkpoint {
+                time: start + Duration::minutes(offset),
+                ..Checkpoint::new()
+            })
+            .collect();
+        let zoom = Zoom::default();
+        let layout = span_layout(
+            &checkpoints,
+            None,
+            zoom,
+            &Rounding::DEFAULT,
+            Rect::new(0, 0, 100, 3),
+        );
+
+        // 08:00 at the first marker, 09:00 four units of four cells into the first span, the
+        // second span starts at 09:30 and contains no full hour
+        assert_eq!(
+            hour_marks(&checkpoints, &layout, zoom, &Rounding::DEFAULT),
+            vec![(0, 8), (17, 9)]
+        );
+    }
+
+    #[test]
+    fn test_braille_char() {
+        assert_eq!(braille_char(Dots::None, Dots::None), '⠀');
+        assert_eq!(braille_char(Dots::Full, Dots::Full), '⣿');
+        assert_eq!(braille_char(Dots::Full, Dots::None), '⡇');
+        assert_eq!(braille_char(Dots::Low, Dots::Full), '⣸');
+    }
+
+    #[test]
+    fn test_braille_timeline_fits_long_day() {
+        let backend = TestBackend::new(40, 3);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        // Twelve hours of work, the last span unregistered
+        let start = at(7, 0);
+        let checkpoints: Vec<Checkpoint> = [0, 360, 705, 720]
+            .into_iter()
+            .map(|offset| Checkpoint {
+                time: start + Duration::minutes(offset),
+                project: Some("ABC-1".to_string()),
+                registered: offset < 705,
+                ..Checkpoint::new()
+            })
+            .collect();
+
+        let widget = || BrailleTimeline {
+            checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
+            selected_checkpoint_idx: None,
+            selected_range: None,
+            theme: Theme::default(),
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
+        };
+        terminal
+            .draw(|f| f.render_widget(widget(), f.area()))
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let row = |y| (6..40).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+        assert!(row(0).starts_with("07  09"), "{}", row(0));
+        assert_eq!(row(1).trim_end(), "⣿".repeat(24));
+        assert_eq!(row(2).trim_end(), format!("{}▔", " ".repeat(23)));
+
+        // A shorter area drops the marks of the unregistered spans
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 40, 2));
+        widget().render(buffer.area, &mut buffer);
+        assert_eq!(buffer[(6, 1)].symbol(), "⣿");
+    }
+
+    #[test]
+    fn test_render_rounded_times() {
+        let start = at(8, 7);
+        let checkpoints: Vec<Checkpoint> = [0, 50]
+            .into_iter()
+            .map(|offset| Checkpoint {
+                time: start + Duration::minutes(offset),
+                ..Checkpoint::new()
+            })
+            .collect();
+
+        for (rounded_times, duration, time) in [(false, "50m", "08:07"), (true, "1h", "08:00")] {
+            let mut terminal = Terminal::new(TestBackend::new(40, 3)).unwrap();
+            let widget = Timeline {
+                checkpoints: &checkpoints,
+                locked: false,
+                holiday: false,
+                selected_checkpoint_idx: None,
+                selected_range: None,
+                theme: Theme::default(),
+                zoom: Zoom::default(),
+                rounded_times,
+                tag_filter: None,
+                project_filter: None,
+                settings: TimeSettings::default(),
+            };
+            terminal
+                .draw(|f| f.render_widget(widget, f.area()))
+                .unwrap();
+
+            let buffer = terminal.backend().buffer();
+            let row = |y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+            assert!(row(0).contains(duration), "{}", row(0));
+            assert!(row(2).contains(time), "{}", row(2));
+        }
+    }
+
+    #[test]
+    fn test_zoom_cells_for() {
+        assert_eq!(Zoom::default().cells_for(45), 12);
+        assert_eq!(Zoom::LEVELS[0].cells_for(60), 2);
+        assert_eq!(Zoom::LEVELS[0].cells_for(15), 1);
+        assert_eq!(Zoom::default().cells_for(5), 2);
+    }
+
+    #[test]
+    fn test_render_scroll_indicator_for_long_day() {
+        let backend = TestBackend::new(40, 5);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let start_time = TimeSettings::default().now();
+        // Ten 30-minute spans are far wider than the terminal
+        let checkpoints: Vec<Checkpoint> = (0..11)
+            .map(|i| Checkpoint {
+                time: start_time + Duration::minutes(30 * i),
+                ..Checkpoint::new()
+            })
+            .collect();
+
+        let widget = Timeline {
+            checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
+            selected_checkpoint_idx: Some(9),
+            selected_range: None,
+            theme: Theme::default(),
+            zoom: Zoom::default(),
+            rounded_times: false,
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
+        };
+
+        terminal
+            .draw(|f| {
+                f.render_widget(widget, f.area());
+            })
+            .unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let line_text: String = (0..40).map(|x| buffer[(x, 1)].symbol()).collect();
+        assert!(
+            line_text.contains('‹'),
+            "Missing left indicator: {}",
+            line_text
+        );
+        assert!(
+            line_text.contains('┤'),
+            "Selected last span not visible: {}",
+            line_text
+        );
+    }
 
     #[test]
     fn test_render_spaces_for_empty_checkpoint() {
         let backend = TestBackend::new(40, 5);
         let mut terminal = Terminal::new(backend).unwrap();
 
-        let start_time = Local::now();
+        let start_time = TimeSettings::default().now();
         // Create two checkpoints 15 minutes apart to get 1 unit span
         let checkpoints = vec![
             Checkpoint {
@@ -132,7 +815,16 @@ mod tests {
 
         let widget = Timeline {
             checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
             selected_checkpoint_idx: None,
+            selected_range: None,
+            theme: Theme::default(),
+            zoom: Zoom::default(),
+            rounded_times: false,
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
         };
 
         terminal
@@ -162,7 +854,7 @@ mod tests {
         let backend = TestBackend::new(40, 5);
         let mut terminal = Terminal::new(backend).unwrap();
 
-        let start_time = Local::now();
+        let start_time = TimeSettings::default().now();
         let checkpoints = vec![
             Checkpoint {
                 time: start_time,
@@ -180,7 +872,16 @@ mod tests {
 
         let widget = Timeline {
             checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
             selected_checkpoint_idx: None,
+            selected_range: None,
+            theme: Theme::default(),
+            zoom: Zoom::default(),
+            rounded_times: false,
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
         };
 
         terminal
@@ -208,7 +909,7 @@ mod tests {
         let backend = TestBackend::new(40, 5);
         let mut terminal = Terminal::new(backend).unwrap();
 
-        let start_time = Local::now();
+        let start_time = TimeSettings::default().now();
         // Create two checkpoints 15 minutes apart to get 1 unit span
         let checkpoints = vec![
             Checkpoint {
@@ -227,7 +928,16 @@ mod tests {
 
         let widget = Timeline {
             checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
             selected_checkpoint_idx: Some(0), // Select the first one
+            selected_range: None,
+            theme: Theme::default(),
+            zoom: Zoom::default(),
+            rounded_times: false,
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
         };
 
         terminal
@@ -264,7 +974,7 @@ mod tests {
         let backend = TestBackend::new(40, 5);
         let mut terminal = Terminal::new(backend).unwrap();
 
-        let start_time = Local::now();
+        let start_time = TimeSettings::default().now();
         // Create two checkpoints 15 minutes apart
         let checkpoints = vec![
             Checkpoint {
@@ -284,7 +994,16 @@ mod tests {
         // Select the last checkpoint (index 1)
         let widget = Timeline {
             checkpoints: &checkpoints,
+            locked: false,
+            holiday: false,
             selected_checkpoint_idx: Some(1),
+            selected_range: None,
+            theme: Theme::default(),
+            zoom: Zoom::default(),
+            rounded_times: false,
+            tag_filter: None,
+            project_filter: None,
+            settings: TimeSettings::default(),
         };
 
         terminal
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use color_eyre::{
+    eyre::{bail, eyre, WrapErr},
+    Report, Result,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::app::Checkpoint;
+use crate::firestore::utc_time;
+
+/// What happened to the checkpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Event {
+    Insert,
+    Update,
+    /// Marked registered in the week view
+    Register,
+    /// Registered in PBS or Jira by `tcheater submit`
+    Submit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Events the hook is called on, all of them when left out
+    #[serde(default)]
+    pub events: Vec<Event>,
+    /// JSON template of the posted payload
+    #[serde(default)]
+    pub payload: Option<String>,
+}
+
+impl WebhookConfig {
+    fn fires_on(&self, event: Event) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// The value of a placeholder of the template, `None` for unknown ones
+fn placeholder(name: &str, event: Event, checkpoint: &Checkpoint) -> Option<String> {
+    let value = match name {
+        "event" => json!(event),
+        "id" => json!(checkpoint.id),
+        "time" => json!(utc_time::format(checkpoint.time)),
+        "date" => json!(checkpoint.time.date_naive()),
+        "project" => json!(checkpoint.project),
+        "activity" => json!(checkpoint.activity),
+        "message" => json!(checkpoint.message),
+        "kind" => json!(checkpoint.kind),
+        "tags" => json!(checkpoint.tags.join(", ")),
+        "registered" => json!(checkpoint.registered),
+        "billable" => json!(checkpoint.billable),
+        _ => return None,
+    };
+    // Strings go inside the quotes of the template, missing values are empty
+    Some(match value {
+        Value::String(text) => {
+            let quoted = Value::String(text).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        Value::Null => String::new(),
+        other => other.to_string(),
+    })
+}
+
+/// The payload posted for the checkpoint
+pub fn payload(template: Option<&str>, event: Event, checkpoint: &Checkpoint) -> Result<Value> {
+    let Some(template) = template else {
+        return Ok(json!({ "event": event, "checkpoint": checkpoint }));
+    };
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            bail!("A placeholder of the payload is not closed");
+        };
+        let name = rest[start + 2..start + end].trim();
+        let value = placeholder(name, event, checkpoint)
+            .ok_or_else(|| eyre!("The payload has an unknown placeholder {{{{{}}}}}", name))?;
+        rendered.push_str(&value);
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    serde_json::from_str(&rendered).wrap_err("The payload is not JSON")
+}
+
+/// Posts the checkpoints to the hooks called on the event, one request per checkpoint. Returns
+/// the errors of the failed calls, the other calls are made anyway.
+pub async fn fire(
+    hooks: &[WebhookConfig],
+    event: Event,
+    checkpoints: &[Checkpoint],
+) -> Vec<Report> {
+    // A dead endpoint is given up on instead of holding back the calls after it
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => return vec![err.into()],
+    };
+    let mut errors = vec![];
+    for hook in hooks.iter().filter(|hook| hook.fires_on(event)) {
+        for checkpoint in checkpoints {
+            if let Err(err) = post(&client, hook, event, checkpoint).await {
+                errors.push(err);
+            }
+        }
+    }
+    errors
+}
+
+async fn post(
+    client: &reqwest::Client,
+    hook: &WebhookConfig,
+    event: Event,
+    checkpoint: &Checkpoint,
+) -> Result<()> {
+    let body = payload(hook.payload.as_deref(), event, checkpoint)?;
+    let response = client
+        .post(&hook.url)
+        .json(&body)
+        .send()
+        .await
+        .wrap_err_with(|| format!("Webhook {} failed", hook.url))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("Webhook {} answered {}", hook.url, status);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time::test_util::at;
+
+    fn login_fix() -> Checkpoint {
+        let mut checkpoint = Checkpoint {
+            id: Some("abc".to_string()),
+            time: at(8, 0),
+            project: Some("web".to_string()),
+            ..Checkpoint::new()
+        };
+        checkpoint.set_message("fix \"login\" #bug".to_string());
+        checkpoint
+    }
+
+    #[test]
+    fn test_payload() {
+        let template = r#"{"title": "{{event}} {{project}}", "text": "{{message}}",
+            "activity": "{{activity}}", "registered": {{registered}}, "date": "{{ date }}"}"#;
+        let payload = payload(Some(template), Event::Insert, &login_fix()).unwrap();
+        assert_eq!(
+            payload,
+            json!({
+                "title": "insert web",
+                "text": "fix \"login\" #bug",
+                "activity": "",
+                "registered": false,
+                "date": "2025-01-06",
+            })
+        );
+
+        let default = super::payload(None, Event::Register, &login_fix()).unwrap();
+        assert_eq!(default["event"], "register");
+        assert_eq!(default["checkpoint"]["project"], "web");
+
+        assert!(
+            super::payload(Some("{\"a\": \"{{nope}}\"}"), Event::Update, &login_fix()).is_err()
+        );
+        assert!(super::payload(Some("{{project}}"), Event::Update, &login_fix()).is_err());
+    }
+
+    #[test]
+    fn test_fires_on() {
+        let hook: WebhookConfig =
+            toml::from_str("url = \"http://localhost\"\nevents = [\"submit\"]").unwrap();
+        assert!(hook.fires_on(Event::Submit));
+        assert!(!hook.fires_on(Event::Insert));
+        let all: WebhookConfig = toml::from_str("url = \"http://localhost\"").unwrap();
+        assert!(all.fires_on(Event::Insert));
+    }
+}
@@ -1,44 +1,697 @@
+use crate::app::project_color;
+use crate::config::GoalsConfig;
+use crate::earnings::Rates;
+use crate::stats::PeriodStats;
+use crate::theme::Theme;
+use crate::time::{DayStats, Locale, TimeSettings};
+use chrono::{Datelike, Days, NaiveDate, NaiveTime, Weekday};
 use ratatui::{
-    style::{Color, Style},
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
     text::{Line, Span},
-    widgets::Widget,
+    widgets::{Bar, BarChart, Clear, LineGauge, Padding, Paragraph, Widget, Wrap},
 };
+use std::collections::BTreeMap;
 
-#[derive(Default)]
-pub struct HelpLine {}
+/// Keys of the most important actions, entries that don't fit the width are left out from the end
+pub struct HelpLine {
+    /// Descriptions with their keys, the most important first
+    pub entries: Vec<(&'static str, String)>,
+    pub theme: Theme,
+}
+
+/// Separates the entries of the help line
+const HELP_SEPARATOR: &str = " | ";
 
 impl Widget for HelpLine {
     fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
     where
         Self: Sized,
     {
-        let help_style = Style::new().fg(Color::Gray);
-        let line = Line::from(vec![
-            Span::styled("Add: ", help_style),
-            Span::raw("<space>"),
-            Span::styled(" | Del: ", help_style),
-            Span::raw("d"),
-            Span::styled(" | Split: ", help_style),
-            Span::raw("s"),
-            Span::styled(" | Message: ", help_style),
-            Span::raw("m"),
-            Span::styled(" | Lenghten: ", help_style),
-            Span::raw("<ctrl> h"),
-            Span::styled("/", help_style),
-            Span::raw("l"),
-            Span::styled(" | Next: ", help_style),
-            Span::raw("\u{003e}"),
-            Span::styled(" | Prev: ", help_style),
-            Span::raw("\u{003c}"),
-            Span::styled(" | Cycle Days: ", help_style),
-            Span::raw("<tab>"),
-            Span::styled(" | Registered: ", help_style),
-            Span::raw("r"),
-            Span::styled(" | Tasks: ", help_style),
-            Span::raw("p"),
-            Span::styled(" | Quit: ", help_style),
-            Span::raw("q"),
+        let help_style = Style::new().fg(self.theme.help);
+        // 1 for the left margin
+        let count = fitting_entries(&self.entries, area.width.saturating_sub(1) as usize);
+        let mut spans = vec![];
+        for (idx, (description, keys)) in self.entries.into_iter().take(count).enumerate() {
+            if idx > 0 {
+                spans.push(Span::styled(HELP_SEPARATOR, help_style));
+            }
+            spans.push(Span::styled(format!("{}: ", description), help_style));
+            spans.push(Span::raw(keys));
+        }
+        buf.set_line(area.left() + 1, area.top(), &Line::from(spans), area.width);
+    }
+}
+
+/// Number of entries from the start that fit into the width
+fn fitting_entries(entries: &[(&str, String)], width: usize) -> usize {
+    let mut used = 0;
+    for (idx, (description, keys)) in entries.iter().enumerate() {
+        let separator = if idx > 0 { HELP_SEPARATOR.len() } else { 0 };
+        used += separator + description.chars().count() + 2 + keys.chars().count();
+        if used > width {
+            return idx;
+        }
+    }
+    entries.len()
+}
+
+/// State of today's clock.
+pub enum Clock {
+    /// What the currently running span is spent on and for how many minutes
+    Running {
+        label: String,
+        minutes: u32,
+    },
+    Stopped,
+}
+
+/// Hours over the expected ones are good, missing ones bad
+fn balance_style(minutes: i64, theme: &Theme) -> Style {
+    Style::new().fg(if minutes < 0 { theme.bad } else { theme.good })
+}
+
+/// One-line summary of the selected day.
+pub struct StatusLine {
+    pub stats: DayStats,
+    /// Public holiday the day falls on, work logged on it is flagged
+    pub holiday: Option<String>,
+    /// Flex balance in minutes, when expected hours are configured
+    pub flex: Option<i64>,
+    /// Today's clock, if today is in the displayed week
+    pub clock: Option<Clock>,
+    /// Tag the timeline is filtered by
+    pub tag_filter: Option<String>,
+    /// Project the timeline is filtered by
+    pub project_filter: Option<String>,
+    /// Frame of the spinner while background calls are in flight
+    pub spinner: Option<&'static str>,
+    pub theme: Theme,
+    pub settings: TimeSettings,
+}
+
+impl Widget for StatusLine {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let help_style = Style::new().fg(self.theme.help);
+        let count_style = |count: usize| {
+            if count == 0 {
+                Style::new().fg(self.theme.good)
+            } else {
+                Style::new().fg(self.theme.bad)
+            }
+        };
+        let mut spans = vec![];
+        if let Some(frame) = self.spinner {
+            spans.extend([
+                Span::styled(frame, Style::new().fg(self.theme.accent)),
+                Span::raw(" "),
+            ]);
+        }
+        match self.clock {
+            Some(Clock::Running { label, minutes }) => spans.extend([
+                Span::styled("Running: ", help_style),
+                Span::styled(
+                    self.settings.duration(minutes),
+                    Style::new().fg(self.theme.accent),
+                ),
+                Span::styled(" on ", help_style),
+                Span::raw(label),
+                Span::styled(" | ", help_style),
+            ]),
+            Some(Clock::Stopped) => spans.extend([
+                Span::styled("Stopped", Style::new().fg(self.theme.bad)),
+                Span::styled(" | ", help_style),
+            ]),
+            None => {}
+        }
+        if let Some(project) = self.project_filter {
+            spans.extend([
+                Span::styled("Project: ", help_style),
+                Span::styled(project, Style::new().fg(self.theme.accent)),
+                Span::styled(" | ", help_style),
+            ]);
+        }
+        if let Some(tag) = self.tag_filter {
+            spans.extend([
+                Span::styled("Tag: ", help_style),
+                Span::styled(format!("#{}", tag), Style::new().fg(self.theme.accent)),
+                Span::styled(" | ", help_style),
+            ]);
+        }
+        spans.extend([
+            Span::styled("Total: ", help_style),
+            Span::raw(self.settings.duration(self.stats.tracked_minutes)),
+            Span::styled(" | Incomplete: ", help_style),
+            Span::styled(
+                self.stats.incomplete_spans.to_string(),
+                count_style(self.stats.incomplete_spans),
+            ),
+            Span::styled(" | Unregistered: ", help_style),
+            Span::styled(
+                self.stats.unregistered_spans.to_string(),
+                count_style(self.stats.unregistered_spans),
+            ),
         ]);
+        if let Some(flex) = self.flex {
+            spans.extend([
+                Span::styled(" | Flex: ", help_style),
+                Span::styled(
+                    self.settings.balance(flex),
+                    balance_style(flex, &self.theme),
+                ),
+            ]);
+        }
+        if let Some(holiday) = self.holiday.filter(|_| self.stats.tracked_minutes > 0) {
+            spans.extend([
+                Span::styled(" | Work on holiday: ", help_style),
+                Span::styled(holiday, Style::new().fg(self.theme.bad)),
+            ]);
+        }
+        if self.stats.span_warnings > 0 {
+            spans.extend([
+                Span::styled(" | Warnings: ", help_style),
+                Span::styled(
+                    self.stats.span_warnings.to_string(),
+                    count_style(self.stats.span_warnings),
+                ),
+                Span::styled(" (w)", help_style),
+            ]);
+        }
+        let line = Line::from(spans);
         buf.set_line(area.left() + 1, area.top(), &line, area.width);
     }
 }
+
+/// Popup asking the user to confirm an action by pressing one of the listed keys.
+pub struct ConfirmPopup<'a> {
+    pub title: &'a str,
+    pub message: &'a str,
+    /// Pairs of key and its description, e.g. `("y", "confirm")`
+    pub choices: &'a [(&'a str, &'a str)],
+    pub theme: Theme,
+}
+
+impl<'a> Widget for ConfirmPopup<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let help_style = Style::new().fg(self.theme.help);
+        let mut choices = vec![];
+        for (i, (key, description)) in self.choices.iter().enumerate() {
+            if i > 0 {
+                choices.push(Span::styled(" | ", help_style));
+            }
+            choices.push(Span::raw(*key));
+            choices.push(Span::styled(format!(": {}", description), help_style));
+        }
+        let lines = vec![
+            Line::from(self.message),
+            Line::default(),
+            Line::from(choices),
+        ];
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .centered()
+            .block(self.theme.block().title(self.title).fg(self.theme.accent))
+            .render(area, buf);
+    }
+}
+
+/// Turns the text already rendered in the area into an OSC 8 hyperlink to the URL.
+///
+/// Terminals without hyperlink support ignore the escape sequences and show the plain text.
+pub struct Hyperlink<'a> {
+    pub url: &'a str,
+}
+
+impl<'a> Widget for Hyperlink<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        if area.is_empty() {
+            return;
+        }
+        let row = area.y;
+        let text: String = (area.x..area.right())
+            .map(|x| buf[(x, row)].symbol().to_string())
+            .collect();
+        // The sequences are not counted into the symbol width, so each cell carries two
+        // characters and hides the one following it, see ratatui/ratatui#902
+        let chars: Vec<char> = text.trim_end().chars().collect();
+        for (i, pair) in chars.chunks(2).enumerate() {
+            let pair: String = pair.iter().collect();
+            let x = area.x + i as u16 * 2;
+            buf[(x, row)].set_symbol(&osc8_link(self.url, &pair));
+        }
+    }
+}
+
+/// Wraps the text in the OSC 8 escape sequences of a hyperlink to the URL
+fn osc8_link(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// One cell per working day of the loaded weeks, colored by the project worked on the most.
+///
+/// The days of a week sit under the label of its Monday, the selected day is marked.
+pub struct Minimap<'a> {
+    pub mondays: &'a [NaiveDate],
+    /// Days of each week shown in the week view, in their order
+    pub weekdays: Vec<Weekday>,
+    /// Tells the dates of the weekdays
+    pub locale: Locale,
+    /// Dominant project of each day with any work
+    pub projects: &'a BTreeMap<NaiveDate, String>,
+    pub selected: NaiveDate,
+    pub theme: Theme,
+}
+
+impl<'a> Widget for Minimap<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        // Matches the labels of the weeks, a column per shown day and a space
+        let width = self.weekdays.len() as u16 + 1;
+        for (week, monday) in self.mondays.iter().enumerate() {
+            for (day, weekday) in self.weekdays.iter().enumerate() {
+                let x = area.x + week as u16 * width + day as u16;
+                if x >= area.right() || area.height == 0 {
+                    return;
+                }
+                let date = self.locale.date_in_week(*monday, *weekday);
+                let mut style = match self.projects.get(&date) {
+                    Some(project) => Style::new().bg(project_color(project)),
+                    None => Style::new().fg(self.theme.help),
+                };
+                let symbol = if date == self.selected {
+                    style = style.fg(self.theme.accent).bold();
+                    self.theme.symbols.selected_day
+                } else if self.projects.contains_key(&date) {
+                    " "
+                } else {
+                    self.theme.symbols.idle_day
+                };
+                buf[(x, area.y)].set_symbol(symbol).set_style(style);
+            }
+        }
+    }
+}
+
+/// Progress of the tracked time towards the daily and weekly targets
+pub struct Goals {
+    pub day_minutes: u32,
+    pub week_minutes: u32,
+    pub goals: GoalsConfig,
+    pub theme: Theme,
+    pub settings: TimeSettings,
+}
+
+impl Widget for Goals {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        let gauges: Vec<(&str, u32, f32)> = [
+            ("Day", self.day_minutes, self.goals.daily_target_hours),
+            ("Week", self.week_minutes, self.goals.weekly_target_hours),
+        ]
+        .into_iter()
+        .filter_map(|(name, minutes, target)| Some((name, minutes, target?)))
+        .collect();
+        if gauges.is_empty() {
+            return;
+        }
+
+        let areas = Layout::horizontal(vec![Constraint::Fill(1); gauges.len()])
+            .spacing(2)
+            .split(area);
+        for ((name, minutes, target_hours), area) in gauges.into_iter().zip(areas.iter()) {
+            let (label, ratio) = goal_progress(name, minutes, target_hours, &self.settings);
+            let color = if ratio >= 1.0 {
+                self.theme.good
+            } else {
+                self.theme.accent
+            };
+            let [filled, unfilled] = self.theme.symbols.gauge;
+            LineGauge::default()
+                .filled_symbol(filled)
+                .unfilled_symbol(unfilled)
+                .ratio(ratio)
+                .label(label)
+                .filled_style(Style::new().fg(color))
+                .unfilled_style(Style::new().fg(self.theme.help))
+                .render(*area, buf);
+        }
+    }
+}
+
+/// Label and ratio, at most 1, of the tracked minutes to the target
+fn goal_progress(
+    name: &str,
+    minutes: u32,
+    target_hours: f32,
+    settings: &TimeSettings,
+) -> (String, f64) {
+    let target = (target_hours.max(0.0) * 60.0).round() as u32;
+    let ratio = if target == 0 {
+        1.0
+    } else {
+        (minutes as f64 / target as f64).min(1.0)
+    };
+    let remaining = if minutes >= target {
+        "done".to_string()
+    } else {
+        format!("{} left", settings.duration(target - minutes))
+    };
+    let label = format!(
+        "{} {}/{} ({})",
+        name,
+        settings.duration(minutes),
+        settings.duration(target),
+        remaining
+    );
+    (label, ratio)
+}
+
+/// Error message shown for a few seconds without blocking the input
+pub struct Toast<'a> {
+    pub message: &'a str,
+    pub theme: Theme,
+}
+
+impl<'a> Widget for Toast<'a> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer)
+    where
+        Self: Sized,
+    {
+        Clear.render(area, buf);
+        Paragraph::new(self.message)
+            .block(
+                self.theme
+                    .block()
+                    .padding(Padding::horizontal(1))
+                    .fg(self.theme.bad),
+            )
+            .render(area, buf);
+    }
+}
+
+/// Weeks shown in the heatmap of the stats screen, about three months
+pub const HEATMAP_WEEKS: u16 = 13;
+const HEATMAP_WEEKDAYS: u16 = 7;
+
+/// Full screen summary of the tracked work in the selected week and month.
+pub struct StatsScreen<'a> {
+    pub week: &'a PeriodStats,
+    pub month: &'a PeriodStats,
+    /// Stats of the days shown in the heatmap
+    pub days: &'a BTreeMap<NaiveDate, DayStats>,
+    pub today: NaiveDate,
+    /// Titles of the week and month panels
+    pub titles: (String, String),
+    /// Flex balance in minutes, when expected hours are configured
+    pub flex: Option<i64>,
+    /// Hourly rates of the projects, the earnings are shown when any project has one
+    pub rates: Rates,
+    pub theme: Theme,
+    pub settings: TimeSettings,
+}
+
+impl<'a> StatsScreen<'a> {
+    fn render_period(&self, title: String, stats: &PeriodStats, area: Rect, buf: &mut Buffer) {
+        let block = self.theme.block().title(title);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let [summary_area, chart_area] =
+            Layout::vertical([Constraint::Length(4), Constraint::Fill(1)]).areas(inner);
+
+        let help_style = Style::new().fg(self.theme.help);
+        let (settings, locale) = (&self.settings, &self.settings.locale);
+        let time = |time: Option<NaiveTime>| {
+            time.map(|time| locale.time(time))
+                .unwrap_or_else(|| "-".to_string())
+        };
+        let total: u32 = stats.project_minutes.iter().map(|(_, m)| m).sum();
+        let longest = stats
+            .longest_day
+            .map(|(date, minutes)| {
+                format!(
+                    "{} {} ({})",
+                    date.format("%a"),
+                    locale.day(date),
+                    settings.duration(minutes)
+                )
+            })
+            .unwrap_or_else(|| "-".to_string());
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("        Total: ", help_style),
+                Span::raw(settings.duration(total)),
+            ]),
+            Line::from(vec![
+                Span::styled("Average start: ", help_style),
+                Span::raw(time(stats.average_start)),
+                Span::styled(" | end: ", help_style),
+                Span::raw(time(stats.average_end)),
+            ]),
+            Line::from(vec![
+                Span::styled("  Longest day: ", help_style),
+                Span::raw(longest),
+            ]),
+        ];
+        if !self.rates.is_empty() {
+            let earned = stats.total_earned(&self.rates).unwrap_or(0.0);
+            lines.push(Line::from(vec![
+                Span::styled("       Earned: ", help_style),
+                Span::raw(self.rates.format(earned)),
+            ]));
+        }
+        Paragraph::new(lines).render(summary_area, buf);
+
+        let bars: Vec<Bar> = stats
+            .project_minutes
+            .iter()
+            .map(|(project, minutes)| {
+                let mut value = settings.duration(*minutes);
+                if let Some(earned) = stats.earned(&self.rates, project) {
+                    value = format!("{} {}", value, self.rates.format(earned));
+                }
+                Bar::with_label(project.as_str(), *minutes as u64).text_value(value)
+            })
+            .collect();
+        BarChart::horizontal(bars)
+            .bar_set(self.theme.symbols.bar.clone())
+            .bar_width(1)
+            .bar_gap(0)
+            .bar_style(Style::new().fg(self.theme.accent))
+            .value_style(Style::new().fg(Color::Black).bg(self.theme.accent))
+            .render(chart_area, buf);
+    }
+}
+
+impl<'a> Widget for StatsScreen<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let [periods_area, heatmap_area, help_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(HEATMAP_WEEKDAYS + 3),
+            Constraint::Length(1),
+        ])
+        .areas(area);
+        let [week_area, month_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(periods_area);
+
+        Clear.render(area, buf);
+        let (week_title, month_title) = self.titles.clone();
+        self.render_period(week_title, self.week, week_area, buf);
+        self.render_period(month_title, self.month, month_area, buf);
+        Heatmap {
+            days: self.days,
+            today: self.today,
+            weeks: HEATMAP_WEEKS,
+            theme: self.theme,
+            locale: self.settings.locale,
+        }
+        .render(heatmap_area, buf);
+
+        let help_style = Style::new().fg(self.theme.help);
+        let mut spans = vec![];
+        if let Some(flex) = self.flex {
+            spans.extend([
+                Span::styled("Flex balance: ", help_style),
+                Span::styled(
+                    self.settings.balance(flex),
+                    balance_style(flex, &self.theme),
+                ),
+                Span::styled(" | ", help_style),
+            ]);
+        }
+        spans.extend([Span::styled("Close: ", help_style), Span::raw("<esc>")]);
+        let line = Line::from(spans);
+        buf.set_line(
+            help_area.left() + 1,
+            help_area.top(),
+            &line,
+            help_area.width,
+        );
+    }
+}
+
+/// Grid of days, one column per week, colored by the tracked hours. Days with unregistered spans
+/// are outlined.
+pub struct Heatmap<'a> {
+    pub days: &'a BTreeMap<NaiveDate, DayStats>,
+    /// Last day shown
+    pub today: NaiveDate,
+    pub weeks: u16,
+    pub theme: Theme,
+    /// Tells the first day of the columns
+    pub locale: Locale,
+}
+
+impl<'a> Heatmap<'a> {
+    /// Colors from no work to a full day
+    const LEVELS: [Color; 5] = [
+        Color::DarkGray,
+        Color::Indexed(22),
+        Color::Indexed(28),
+        Color::Indexed(34),
+        Color::Indexed(46),
+    ];
+
+    fn level(minutes: u32) -> usize {
+        match minutes {
+            0 => 0,
+            1..120 => 1,
+            120..240 => 2,
+            240..360 => 3,
+            _ => 4,
+        }
+    }
+}
+
+impl<'a> Widget for Heatmap<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let block = self.theme.block().title("Tracked hours");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let help_style = Style::new().fg(self.theme.help);
+        let last_monday = self.locale.week_start(self.today);
+        let first_monday = last_monday - Days::new(7 * (self.weeks as u64 - 1));
+
+        // Every other weekday is labeled, from the second day of the week
+        let weekdays = self.locale.weekdays();
+        for row in [1, 3, 5] {
+            let y = inner.y + 1 + row;
+            if y >= inner.bottom() {
+                break;
+            }
+            let label = weekdays[row as usize].to_string();
+            buf.set_stringn(inner.x, y, label, inner.width as usize, help_style);
+        }
+
+        let mut last_month = None;
+        for week in 0..self.weeks {
+            let x = inner.x + 4 + week * 3;
+            if x + 3 > inner.right() {
+                break;
+            }
+            let monday = first_monday + Days::new(7 * week as u64);
+            if inner.height > 0 && last_month != Some(monday.month()) {
+                last_month = Some(monday.month());
+                buf.set_string(x, inner.y, monday.format("%b").to_string(), help_style);
+            }
+
+            for weekday in 0..7 {
+                let date = monday + Days::new(weekday);
+                if date > self.today || inner.y + 1 + weekday as u16 >= inner.bottom() {
+                    break;
+                }
+                let stats = self.days.get(&date).copied().unwrap_or_default();
+                let color = Self::LEVELS[Self::level(stats.tracked_minutes)];
+                let y = inner.y + 1 + weekday as u16;
+                buf.set_string(
+                    x + 1,
+                    y,
+                    self.theme.symbols.heatmap_day,
+                    Style::new().fg(color),
+                );
+                if stats.unregistered_spans > 0 {
+                    let outline = Style::new().fg(self.theme.bad);
+                    buf.set_string(x, y, "[", outline);
+                    buf.set_string(x + 2, y, "]", outline);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fitting_entries() {
+        let entries = vec![
+            ("Add", "<space>".to_string()),
+            ("Quit", "q".to_string()),
+            ("Zoom", "+/-".to_string()),
+        ];
+
+        // "Add: <space> | Quit: q" takes 22 columns
+        assert_eq!(fitting_entries(&entries, 22), 2);
+        assert_eq!(fitting_entries(&entries, 21), 1);
+        assert_eq!(fitting_entries(&entries, 100), 3);
+        assert_eq!(fitting_entries(&entries, 0), 0);
+    }
+
+    #[test]
+    fn test_goal_progress() {
+        let settings = TimeSettings::default();
+        assert_eq!(
+            goal_progress("Day", 6 * 60, 8.0, &settings),
+            ("Day 6h/8h (2h left)".to_string(), 0.75)
+        );
+        assert_eq!(
+            goal_progress("Week", 41 * 60, 40.0, &settings),
+            ("Week 41h/40h (done)".to_string(), 1.0)
+        );
+        assert_eq!(
+            goal_progress("Day", 30, 7.5, &settings),
+            ("Day 30m/7h30m (7h left)".to_string(), 30.0 / 450.0)
+        );
+    }
+
+    #[test]
+    fn test_heatmap_labels_stay_inside() {
+        let heatmap = Heatmap {
+            days: &BTreeMap::new(),
+            today: NaiveDate::from_ymd_opt(2025, 1, 8).unwrap(),
+            weeks: 4,
+            theme: Theme::default(),
+            locale: Locale::default(),
+        };
+        // Room for two columns and three rows inside the border
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 5));
+        heatmap.render(buffer.area, &mut buffer);
+        assert_eq!(buffer[(1, 3)].symbol(), "T");
+        assert_eq!(buffer[(2, 3)].symbol(), "u");
+        assert_eq!(buffer[(3, 3)].symbol(), "│");
+    }
+}
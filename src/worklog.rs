@@ -0,0 +1,172 @@
+use color_eyre::{eyre::eyre, Result};
+use futures::future::BoxFuture;
+
+use crate::config::Config;
+use crate::jira::JiraSink;
+use crate::pbs::{AuthConfig, Session, WorkEntry};
+use crate::projects::{billing_code, Project};
+use crate::time::Time;
+
+/// Work of a span as submitted to a sink
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worklog {
+    /// What the sink logs the work under, e.g. a PBS task id or a Jira issue key
+    pub target: String,
+    pub start: Time,
+    pub minutes: u32,
+    pub description: String,
+    pub billing_code: Option<String>,
+}
+
+impl Worklog {
+    pub fn new(target: String, start: Time, minutes: u32, message: Option<&str>) -> Self {
+        Self {
+            target,
+            start,
+            minutes,
+            description: message.unwrap_or_default().to_string(),
+            billing_code: None,
+        }
+    }
+}
+
+/// A system the work is logged in
+pub trait WorklogSink: Send + Sync {
+    /// Shown with the spans submitted to the sink
+    fn name(&self) -> &'static str;
+
+    /// What the work of the project is logged under, `None` when the sink doesn't take it
+    fn target(&self, project: &str, projects: &[Project]) -> Option<String>;
+
+    /// Logs in before the first worklog is submitted
+    fn connect(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn submit<'a>(&'a self, worklog: &'a Worklog) -> BoxFuture<'a, Result<()>>;
+}
+
+/// The sinks of the config in the order they are asked for a span, Jira goes first as its
+/// projects are mapped explicitly
+pub fn sinks(config: &Config) -> Vec<Box<dyn WorklogSink>> {
+    let mut sinks: Vec<Box<dyn WorklogSink>> = vec![];
+    if let Some(jira) = &config.jira {
+        sinks.push(Box::new(JiraSink::new(jira.clone())));
+    }
+    // Profiles that don't register their time in PBS leave out [auth]
+    if !config.auth.login_url.is_empty() {
+        sinks.push(Box::new(PbsSink::new(config.auth.clone())));
+    }
+    sinks
+}
+
+/// Registers the work as entries of PBS tasks
+pub struct PbsSink {
+    auth: AuthConfig,
+    session: Option<Session>,
+}
+
+impl PbsSink {
+    pub fn new(auth: AuthConfig) -> Self {
+        Self {
+            auth,
+            session: None,
+        }
+    }
+}
+
+impl WorklogSink for PbsSink {
+    fn name(&self) -> &'static str {
+        "PBS"
+    }
+
+    fn target(&self, project: &str, _projects: &[Project]) -> Option<String> {
+        let task_id: i32 = project.trim().parse().ok()?;
+        Some(task_id.to_string())
+    }
+
+    fn connect(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            let session = Session::login(&self.auth)
+                .await
+                .map_err(|err| eyre!("{}", err))?;
+            self.session = Some(session);
+            Ok(())
+        })
+    }
+
+    fn submit<'a>(&'a self, worklog: &'a Worklog) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let session = self
+                .session
+                .as_ref()
+                .ok_or_else(|| eyre!("Not logged into PBS"))?;
+            let entry = WorkEntry {
+                task_id: worklog.target.parse()?,
+                date: worklog.start.date_naive(),
+                minutes: worklog.minutes,
+                description: worklog.description.clone(),
+                billing_code: worklog.billing_code.clone(),
+            };
+            session.submit(&entry).await.map_err(|err| eyre!("{}", err))
+        })
+    }
+}
+
+/// The first sink that takes the project of the span and its worklog
+pub fn route(
+    sinks: &[Box<dyn WorklogSink>],
+    projects: &[Project],
+    project: &str,
+    start: Time,
+    minutes: u32,
+    message: Option<&str>,
+) -> Option<(usize, Worklog)> {
+    sinks.iter().enumerate().find_map(|(idx, sink)| {
+        let target = sink.target(project, projects)?;
+        let worklog = Worklog {
+            billing_code: billing_code(projects, Some(project)).map(str::to_string),
+            ..Worklog::new(target, start, minutes, message)
+        };
+        Some((idx, worklog))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jira::JiraConfig;
+    use crate::projects::parse_projects;
+    use crate::time::test_util::at;
+
+    #[test]
+    fn test_route() {
+        let sinks: Vec<Box<dyn WorklogSink>> = vec![
+            Box::new(JiraSink::new(JiraConfig::default())),
+            Box::new(PbsSink::new(AuthConfig::default())),
+        ];
+        let projects = parse_projects(
+            "[[projects]]\nid = \"119627\"\nbilling_code = \"B-100\"\n\
+             [[projects]]\nid = \"web\"\njira_issue = \"WEB-12\"",
+        )
+        .unwrap();
+        let start = at(8, 0);
+        let route = |project| route(&sinks, &projects, project, start, 90, Some("review"));
+
+        let (sink, worklog) = route("119627").unwrap();
+        assert_eq!(sinks[sink].name(), "PBS");
+        assert_eq!(worklog.target, "119627");
+        assert_eq!(worklog.billing_code.as_deref(), Some("B-100"));
+        assert_eq!(worklog.description, "review");
+
+        let (sink, worklog) = route("web").unwrap();
+        assert_eq!(sinks[sink].name(), "Jira");
+        assert_eq!(worklog.target, "WEB-12");
+
+        let (sink, worklog) = route("OPS-7").unwrap();
+        assert_eq!(sinks[sink].name(), "Jira");
+        assert_eq!(worklog.target, "OPS-7");
+
+        assert!(route("internal").is_none());
+    }
+}